@@ -1,195 +1,16 @@
-use regex::Regex;
-use std::collections::HashMap;
-use std::str::FromStr;
-
-#[derive(Debug, Copy, Clone)]
-enum WireType {
-    Val,
-    Id,
-}
-
-#[derive(Debug, Clone)]
-struct Wire {
-    w_type: WireType,
-    val: Option<u16>,
-    id: Option<String>,
-}
-
-impl FromStr for Wire {
-    type Err = ();
-
-    fn from_str(input: &str) -> Result<Wire, Self::Err> {
-        if let Ok(n) = input.parse::<u16>() {
-            Ok(Wire {
-                w_type: WireType::Val,
-                val: Some(n),
-                id: None,
-            })
-        } else if !input.is_empty() {
-            Ok(Wire {
-                w_type: WireType::Id,
-                val: None,
-                id: Some(input.to_string()),
-            })
-        } else {
-            Err(())
-        }
-    }
-}
-
-impl Wire {
-    fn to_value(&self, map: &HashMap<String, u16>) -> Option<u16> {
-        match self.w_type {
-            WireType::Val => self.val,
-            WireType::Id => self
-                .id
-                .as_ref()
-                .and_then(|id| map.get(id.as_str()))
-                .cloned(),
-        }
-    }
-}
-
-#[derive(Debug, Copy, Clone)]
-enum OperationType {
-    Equal,
-    And,
-    Or,
-    Lshift,
-    Rshift,
-    Not,
-}
-
-impl FromStr for OperationType {
-    type Err = ();
-
-    fn from_str(input: &str) -> Result<OperationType, Self::Err> {
-        match input {
-            "" => Ok(OperationType::Equal),
-            "AND" => Ok(OperationType::And),
-            "OR" => Ok(OperationType::Or),
-            "LSHIFT" => Ok(OperationType::Lshift),
-            "RSHIFT" => Ok(OperationType::Rshift),
-            "NOT" => Ok(OperationType::Not),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-struct Operation {
-    op: OperationType,
-    left: Option<Wire>,
-    right: Option<Wire>,
-    target: String,
-}
-
-impl FromStr for Operation {
-    type Err = ();
-
-    fn from_str(input: &str) -> Result<Operation, Self::Err> {
-        let re =
-            Regex::new(r"^(?:([0-9a-z]*)[ ])?(AND|OR|LSHIFT|RSHIFT|NOT|)[ ]?([0-9a-z]+) -> (\w*)$")
-                .unwrap();
-        let res = if let Some(cap) = re.captures_iter(input).next() {
-            let op: OperationType = OperationType::from_str(&cap[2])?;
-            let target = cap[4].to_string();
-            match op {
-                OperationType::Equal | OperationType::Not => Ok(Operation {
-                    op,
-                    left: None,
-                    right: Some(Wire::from_str(&cap[3])?),
-                    target,
-                }),
-                OperationType::And
-                | OperationType::Or
-                | OperationType::Lshift
-                | OperationType::Rshift => Ok(Operation {
-                    op,
-                    left: Some(Wire::from_str(&cap[1])?),
-                    right: Some(Wire::from_str(&cap[3])?),
-                    target,
-                }),
-            }
-        } else {
-            Err(())
-        };
-        res
-    }
-}
-
-impl Operation {
-    fn execute(&self, map: &mut HashMap<String, u16>) {
-        if !map.contains_key(self.target.as_str()) {
-            match self.op {
-                OperationType::Equal => {
-                    if let Some(v) = self.right.clone().unwrap().to_value(map) {
-                        map.insert(self.target.clone(), v);
-                    }
-                }
-                OperationType::And => {
-                    if let Some(v1) = self.left.clone().unwrap().to_value(map) {
-                        if let Some(v2) = self.right.clone().unwrap().to_value(map) {
-                            map.insert(self.target.clone(), v1 & v2);
-                        }
-                    }
-                }
-                OperationType::Or => {
-                    if let Some(v1) = self.left.clone().unwrap().to_value(map) {
-                        if let Some(v2) = self.right.clone().unwrap().to_value(map) {
-                            map.insert(self.target.clone(), v1 | v2);
-                        }
-                    }
-                }
-                OperationType::Lshift => {
-                    if let Some(v1) = self.left.clone().unwrap().to_value(map) {
-                        if let Some(v2) = self.right.clone().unwrap().to_value(map) {
-                            map.insert(self.target.clone(), v1 << v2);
-                        }
-                    }
-                }
-                OperationType::Rshift => {
-                    if let Some(v1) = self.left.clone().unwrap().to_value(map) {
-                        if let Some(v2) = self.right.clone().unwrap().to_value(map) {
-                            map.insert(self.target.clone(), v1 >> v2);
-                        }
-                    }
-                }
-                OperationType::Not => {
-                    if let Some(v) = self.right.clone().unwrap().to_value(map) {
-                        map.insert(self.target.clone(), !v);
-                    }
-                }
-            }
-        }
-    }
-}
+use util::circuit::Circuit;
 
 fn main() {
     let s = util::file_as_string("aoc_2015/input/day_07.txt").expect("Cannot open input file");
 
-    let ops: Vec<Operation> = s
-        .lines()
-        .filter_map(|s| Operation::from_str(s).ok())
-        .collect();
+    let mut circuit: Circuit = s.parse::<Circuit>().unwrap().with_width(16);
 
-    let mut map: HashMap<String, u16> = HashMap::new();
-
-    while !map.contains_key("a") {
-        ops.iter().for_each(|op| op.execute(&mut map));
-    }
-
-    let first_a = map.get("a").cloned().unwrap();
+    let first_a: u64 = circuit.evaluate().unwrap().get("a").unwrap();
 
     println!("Part1: The wire 'a' contains {first_a}");
 
-    map.clear();
-    map.insert("b".to_string(), first_a);
-
-    while !map.contains_key("a") {
-        ops.iter().for_each(|op| op.execute(&mut map));
-    }
+    circuit.set("b", first_a);
 
-    let second_a = map.get("a").unwrap();
+    let second_a: u64 = circuit.evaluate().unwrap().get("a").unwrap();
     println!("Part2: The wire 'a' now contains {second_a}");
 }
@@ -1,38 +1,29 @@
-use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use util::graph::NodeMap;
+use util::paths::{longest_tour, shortest_tour, Route};
 
 fn main() {
     let s = util::file_as_string("aoc_2015/input/day_09.txt").expect("Cannot open input file");
 
-    let mut towns: HashSet<String> = HashSet::new();
-    let mut distances: HashMap<(String, String), u16> = HashMap::new();
-
-    s.lines().for_each(|s| {
-        let split: Vec<&str> = s.split(' ').collect();
-        let town_1 = split[0];
-        let town_2 = split[2];
-        let dist = split[4].parse::<u16>().unwrap();
-        towns.insert(town_1.to_string());
-        towns.insert(town_2.to_string());
-        distances.insert((town_1.to_string(), town_2.to_string()), dist);
-        distances.insert((town_2.to_string(), town_1.to_string()), dist);
-    });
-
-    let dists: Vec<u16> = towns
-        .iter()
-        .permutations(towns.len())
-        .map(|perm| {
-            perm.windows(2)
-                .map(|pair| {
-                    let tuple: (String, String) = (pair[0].to_string(), pair[1].to_string());
-                    distances.get(&tuple).cloned().unwrap()
-                })
-                .sum::<u16>()
+    let mut towns: NodeMap<String> = NodeMap::new();
+    let roads: Vec<(usize, usize, u16)> = s
+        .lines()
+        .map(|s| {
+            let split: Vec<&str> = s.split(' ').collect();
+            let town_1 = towns.id(split[0].to_string());
+            let town_2 = towns.id(split[2].to_string());
+            let dist = split[4].parse::<u16>().unwrap();
+            (town_1, town_2, dist)
         })
         .collect();
 
-    let min_dist: u16 = dists.iter().copied().min().unwrap();
-    let max_dist: u16 = dists.iter().copied().max().unwrap();
+    let mut distances: Vec<Vec<Option<u16>>> = vec![vec![None; towns.len()]; towns.len()];
+    for (town_1, town_2, dist) in roads {
+        distances[town_1][town_2] = Some(dist);
+        distances[town_2][town_1] = Some(dist);
+    }
+
+    let min_dist: u16 = shortest_tour(&distances, Route::Path).unwrap();
+    let max_dist: u16 = longest_tour(&distances, Route::Path).unwrap();
 
     println!("Part1: Min distance to cover is {min_dist}");
     println!("Part2: Max distance to cover is {max_dist}");
@@ -1,78 +1,52 @@
-use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use util::graph::NodeMap;
+use util::paths::{longest_tour, Route};
 
 fn main() {
     let s = util::file_as_string("aoc_2015/input/day_13.txt").expect("Cannot open input file");
 
-    let mut happy_map: HashMap<(String, String), i32> = HashMap::new();
-    let mut guests: HashSet<String> = HashSet::new();
-
-    s.lines().for_each(|s| {
-        let words: Vec<&str> = s.split(' ').collect();
-        guests.insert(words[0].to_string());
-        let val: i32 = words[3].parse().unwrap();
-
-        //Remove trailing dot from last name
-        let mut last_name = words[10].chars();
-        last_name.next_back();
-        let key = (words[0].to_string(), last_name.as_str().to_string());
-        let rev_key = (last_name.as_str().to_string(), words[0].to_string());
-
-        match words[2] {
-            "gain" => {
-                let entry = happy_map.entry(key).or_insert(0);
-                *entry += val;
-                let rev_entry = happy_map.entry(rev_key).or_insert(0);
-                *rev_entry += val;
+    let mut guests: NodeMap<String> = NodeMap::new();
+    let changes: Vec<(usize, usize, i32)> = s
+        .lines()
+        .map(|s| {
+            let words: Vec<&str> = s.split(' ').collect();
+            let val: i32 = words[3].parse().unwrap();
+
+            //Remove trailing dot from last name
+            let last_name: &str = words[10].trim_end_matches('.');
+            let (guest, neighbour) = (
+                guests.id(words[0].to_string()),
+                guests.id(last_name.to_string()),
+            );
+            match words[2] {
+                "lose" => (guest, neighbour, -val),
+                _ => (guest, neighbour, val),
             }
-            "lose" => {
-                let entry = happy_map.entry(key).or_insert(0);
-                *entry -= val;
-                let rev_entry = happy_map.entry(rev_key).or_insert(0);
-                *rev_entry -= val;
-            }
-            _ => (),
-        }
-    });
+        })
+        .collect();
 
-    println!("Guests: {guests:?}");
+    //Sitting next to each other changes the happiness of both guests
+    let mut happy_map: Vec<Vec<Option<i32>>> = vec![vec![Some(0); guests.len()]; guests.len()];
+    for (guest, neighbour, val) in changes {
+        happy_map[guest][neighbour] = happy_map[guest][neighbour].map(|h| h + val);
+        happy_map[neighbour][guest] = happy_map[neighbour][guest].map(|h| h + val);
+    }
 
-    let total_happy_sum: Option<i32> = guests
-        .iter()
-        .permutations(guests.len())
-        .map(|perm| {
-            let mut happy_sum: i32 = perm
-                .windows(2)
-                .flat_map(|pair| happy_map.get(&(pair[0].to_string(), pair[1].to_string())))
-                .sum();
-            //We need to join the table in a circle
-            let first: String = perm.first().cloned().cloned().unwrap();
-            let last: String = perm.last().cloned().cloned().unwrap();
-            happy_sum += happy_map.get(&(first, last)).unwrap();
-            happy_sum
-        })
-        .max();
+    println!(
+        "Guests: {:?}",
+        (0..guests.len())
+            .map(|g| guests.name(g))
+            .collect::<Vec<_>>()
+    );
 
+    //We need to join the table in a circle
     println!(
         "Part1: The maximum happiness change is {}",
-        total_happy_sum.unwrap()
+        longest_tour(&happy_map, Route::Cycle).unwrap()
     );
 
-    let total_happy_sum_2: Option<i32> = guests
-        .iter()
-        .permutations(guests.len())
-        .map(|perm| {
-            //We don't need to join the table in a circle, there is you !
-            let happy_sum: i32 = perm
-                .windows(2)
-                .flat_map(|pair| happy_map.get(&(pair[0].to_string(), pair[1].to_string())))
-                .sum();
-            happy_sum
-        })
-        .max();
-
+    //We don't need to join the table in a circle, there is you !
     println!(
         "Part2: The maximum happiness change is now {}",
-        total_happy_sum_2.unwrap()
+        longest_tour(&happy_map, Route::Path).unwrap()
     );
 }
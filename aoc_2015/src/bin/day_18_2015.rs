@@ -1,3 +1,7 @@
+use util::automaton::{moore, Board};
+
+const SIZE: usize = 100;
+
 fn main() {
     let s = util::file_as_string("aoc_2015/input/day_18.txt").expect("Cannot open input file");
 
@@ -5,90 +9,30 @@ fn main() {
         .lines()
         .map(|s| s.chars().map(|c| matches!(c, '#')).collect())
         .collect();
+    let lights: Board<bool> = Board::from_grid(grid, moore(SIZE, SIZE));
 
-    let mut grid_1 = grid.clone();
+    let mut lights_1: Board<bool> = lights.clone();
     (0..100).for_each(|_| {
-        grid_1 = step(&grid_1);
+        lights_1.step(rule);
     });
-    let nb_lights_1: usize = grid_1
-        .into_iter()
-        .map(|row| row.into_iter().filter(|&b| b).count())
-        .sum();
+    let nb_lights_1: usize = lights_1.count(&true);
     println!("Part1: There are {nb_lights_1} lights on ");
 
-    let mut grid_2 = grid;
-    grid_2[0][0] = true;
-    grid_2[0][99] = true;
-    grid_2[99][0] = true;
-    grid_2[99][99] = true;
+    //The corners are stuck on
+    let corners: [usize; 4] = [0, SIZE - 1, SIZE * (SIZE - 1), SIZE * SIZE - 1];
+    let mut lights_2: Board<bool> = lights;
+    corners.iter().for_each(|&c| lights_2.cells[c] = true);
     (0..100).for_each(|_| {
-        grid_2 = step_fixed_corner(&grid_2);
+        lights_2.step(rule);
+        corners.iter().for_each(|&c| lights_2.cells[c] = true);
     });
-    let nb_lights_2: usize = grid_2
-        .into_iter()
-        .map(|row| row.into_iter().filter(|&b| b).count())
-        .sum();
+    let nb_lights_2: usize = lights_2.count(&true);
     println!("Part2: There are now {nb_lights_2} lights on ");
 }
 
-fn step(grid: &[Vec<bool>]) -> Vec<Vec<bool>> {
-    let new_grid: Vec<Vec<bool>> = grid
-        .iter()
-        .enumerate()
-        .map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .map(|(x, &b)| {
-                    matches!((nb_neighboors((x, y), grid), b), (2..=3, true) | (3, false))
-                })
-                .collect()
-        })
-        .collect();
-    new_grid
-}
-
-fn step_fixed_corner(grid: &[Vec<bool>]) -> Vec<Vec<bool>> {
-    let new_grid: Vec<Vec<bool>> = grid
-        .iter()
-        .enumerate()
-        .map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .map(|(x, &b)| {
-                    matches!(
-                        (x, y, nb_neighboors((x, y), grid), b),
-                        (0, 0, _, _)
-                            | (0, 99, _, _)
-                            | (99, 0, _, _)
-                            | (99, 99, _, _)
-                            | (_, _, 2..=3, true)
-                            | (_, _, 3, false)
-                    )
-                })
-                .collect()
-        })
-        .collect();
-    new_grid
-}
-
-fn nb_neighboors((x, y): (usize, usize), grid: &[Vec<bool>]) -> usize {
-    neighboors((x as isize, y as isize), 0, grid.len())
-        .iter()
-        .map(|&(i, j)| grid[j][i])
-        .filter(|&b| b)
-        .count()
-}
-
-#[rustfmt::skip]
-fn neighboors((x,y): (isize ,isize), min: usize, max: usize) -> Vec<(usize,usize)> {
-    let nbrs = [
-        (x-1, y+1), (x, y+1), (x+1, y+1),
-        (x-1, y),               (x+1, y),
-        (x-1, y-1), (x, y-1), (x+1, y-1),
-    ];
-    nbrs
-        .into_iter()
-        .map(|(x, y)| (x as usize, y as usize))
-        .filter(|&(x,y)| x >= min && x < max && y >= min && y < max)
-        .collect()
+fn rule(&on: &bool, neighbours: &[bool]) -> bool {
+    matches!(
+        (neighbours.iter().filter(|&&b| b).count(), on),
+        (2..=3, true) | (3, false)
+    )
 }
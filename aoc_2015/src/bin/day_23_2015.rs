@@ -1,98 +1,76 @@
-use util::registry::Registry;
+use std::str::FromStr;
 
+//Registers "a" and "b" are compiled to the indexes 0 and 1
 #[derive(Debug, Copy, Clone)]
-enum Instruction<'a> {
-    Half(&'a Registry),
-    Triple(&'a Registry),
-    Increment(&'a Registry),
+enum Instruction {
+    Half(usize),
+    Triple(usize),
+    Increment(usize),
     Jump(isize),
-    JumpIfEven(&'a Registry, isize),
-    JumpIfOne(&'a Registry, isize),
+    JumpIfEven(usize, isize),
+    JumpIfOne(usize, isize),
 }
 
-impl Instruction<'_> {
-    fn compute(&self, i: isize) -> isize {
-        match self {
-            Instruction::Half(reg) => {
-                reg.half();
-                i + 1
-            }
-            Instruction::Triple(reg) => {
-                reg.triple();
-                i + 1
-            }
-            Instruction::Increment(reg) => {
-                reg.incr();
-                i + 1
-            }
-            Instruction::Jump(offset) => i + offset,
-            Instruction::JumpIfEven(reg, offset) => {
-                if reg.is_even() {
-                    i + offset
-                } else {
-                    i + 1
-                }
-            }
-            Instruction::JumpIfOne(reg, offset) => {
-                if reg.is_one() {
-                    i + offset
-                } else {
-                    i + 1
-                }
-            }
+impl Instruction {
+    fn compute(&self, regs: &mut [usize; 2], i: isize) -> isize {
+        match *self {
+            Instruction::Half(r) => regs[r] /= 2,
+            Instruction::Triple(r) => regs[r] *= 3,
+            Instruction::Increment(r) => regs[r] += 1,
+            Instruction::Jump(offset) => return i + offset,
+            Instruction::JumpIfEven(r, offset) if regs[r].is_multiple_of(2) => return i + offset,
+            Instruction::JumpIfOne(r, offset) if regs[r] == 1 => return i + offset,
+            Instruction::JumpIfEven(..) | Instruction::JumpIfOne(..) => (),
         }
+        i + 1
     }
 }
 
-fn main() {
-    let a = Registry::default();
-    let b = Registry::default();
-    let mut instructions: [Instruction; 48] = [Instruction::Jump(0); 48];
-    let s = util::file_as_string("aoc_2015/input/day_23.txt").expect("Cannot open input file");
-    s.lines().enumerate().for_each(|(i, s)| {
-        let words: Vec<&str> = s.split(' ').collect();
-        match (words[0], words[1]) {
-            ("hlf", _) => instructions[i] = Instruction::Half(&a),
-            ("tpl", _) => instructions[i] = Instruction::Triple(&a),
-            ("inc", "a") => instructions[i] = Instruction::Increment(&a),
-            ("inc", "b") => instructions[i] = Instruction::Increment(&b),
-            ("jmp", offset) => {
-                let of = offset.parse::<isize>().unwrap();
-                instructions[i] = Instruction::Jump(of);
-            }
-            ("jie", _) => {
-                let of = words[2].parse::<isize>().unwrap();
-                instructions[i] = Instruction::JumpIfEven(&a, of);
-            }
-            ("jio", _) => {
-                let of = words[2].parse::<isize>().unwrap();
-                instructions[i] = Instruction::JumpIfOne(&a, of);
+impl FromStr for Instruction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split([' ', ',']).filter(|w| !w.is_empty()).collect();
+        let reg = |w: &str| -> Result<usize, ()> {
+            match w {
+                "a" => Ok(0),
+                "b" => Ok(1),
+                _ => Err(()),
             }
-            _ => (),
+        };
+        let offset = |w: &str| -> Result<isize, ()> { w.parse().map_err(|_| ()) };
+        match words[..] {
+            ["hlf", r] => Ok(Instruction::Half(reg(r)?)),
+            ["tpl", r] => Ok(Instruction::Triple(reg(r)?)),
+            ["inc", r] => Ok(Instruction::Increment(reg(r)?)),
+            ["jmp", o] => Ok(Instruction::Jump(offset(o)?)),
+            ["jie", r, o] => Ok(Instruction::JumpIfEven(reg(r)?, offset(o)?)),
+            ["jio", r, o] => Ok(Instruction::JumpIfOne(reg(r)?, offset(o)?)),
+            _ => Err(()),
         }
-    });
+    }
+}
+
+fn run(instructions: &[Instruction], a: usize) -> usize {
+    let mut regs: [usize; 2] = [a, 0];
     let mut i: isize = 0;
-    let instr_size: isize = instructions.len() as isize;
-    while i >= 0 && i < instr_size {
-        let instr = instructions[i as usize];
-        i = instr.compute(i);
+    while i >= 0 && (i as usize) < instructions.len() {
+        i = instructions[i as usize].compute(&mut regs, i);
     }
+    regs[1]
+}
+
+fn main() {
+    let s = util::file_as_string("aoc_2015/input/day_23.txt").expect("Cannot open input file");
+    let instructions: Vec<Instruction> = s.lines().map(|l| l.parse().unwrap()).collect();
 
     println!(
         "Part1: Registry B contains {} when starting with 0 in registry A",
-        b.get()
+        run(&instructions, 0)
     );
 
-    i = 0;
-    a.set(1);
-    b.set(0);
-    while i >= 0 && i < instr_size {
-        let instr = instructions[i as usize];
-        i = instr.compute(i);
-    }
-
     println!(
         "Part2: Registry B contains {} when starting with 1 in registry A",
-        b.get()
+        run(&instructions, 1)
     );
 }
@@ -1,11 +1,11 @@
 use std::collections::HashSet;
+use util::coord::PosI;
 use util::orientation::Dir;
 
 struct Position {
-    visited: HashSet<(i16, i16)>,
+    visited: HashSet<PosI>,
     dir: Dir,
-    x: i16,
-    y: i16,
+    pos: PosI,
     found_hq: bool,
 }
 
@@ -18,16 +18,11 @@ impl Position {
     }
 
     fn advance_one(&mut self) {
-        match self.dir {
-            Dir::North => self.y += 1,
-            Dir::East => self.x += 1,
-            Dir::South => self.y -= 1,
-            Dir::West => self.x -= 1,
-        };
-        if !self.visited.insert((self.x, self.y)) && !self.found_hq {
+        self.pos = self.pos.add(self.dir.delta());
+        if !self.visited.insert(self.pos) && !self.found_hq {
             println!(
                 "Part2: The Easter Bunny HQ is at {} blocks",
-                self.x.abs() + self.y.abs()
+                self.pos.distance(PosI(0, 0))
             );
             self.found_hq = true;
         }
@@ -45,8 +40,7 @@ fn main() {
     let mut pos: Position = Position {
         visited: HashSet::new(),
         dir: Dir::North,
-        x: 0,
-        y: 0,
+        pos: PosI(0, 0),
         found_hq: false,
     };
 
@@ -69,6 +63,6 @@ fn main() {
 
     println!(
         "Part1: The destination is at {} blocks",
-        pos.x.abs() + pos.y.abs()
+        pos.pos.distance(PosI(0, 0))
     );
 }
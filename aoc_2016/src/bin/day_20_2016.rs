@@ -1,44 +1,24 @@
-use std::cmp::max;
+use util::interval::{Interval, IntervalSet};
+
+const MAX_IP: u64 = 4294967295;
 
 fn main() {
     let s = util::file_as_string("aoc_2016/input/day_20.txt").expect("Cannot open input file");
 
     let now = std::time::Instant::now();
-    let mut bls: Vec<(u64, u64)> = s
+    let blocked: IntervalSet<u64> = s
         .lines()
         .map(|s| {
             let words: Vec<u64> = s.split('-').map(|w| w.parse().unwrap()).collect();
-            (words[0], words[1])
+            Interval::closed(words[0], words[1])
         })
         .collect();
-
-    bls.sort();
+    let allowed: IntervalSet<u64> = blocked.complement(Interval::closed(0, MAX_IP));
 
     println!(
         "There are {} ips allowed, the first one is: {}, found in {:?}",
-        nb_allowed(&bls),
-        min_allowed(&bls),
+        allowed.len(),
+        allowed.min().unwrap(),
         now.elapsed()
     );
 }
-
-fn min_allowed(bls: &[(u64, u64)]) -> u64 {
-    bls.iter().fold(0, |acc, (low, high)| {
-        if *low > acc {
-            return acc;
-        }
-        max(high + 1, acc)
-    })
-}
-
-fn nb_allowed(bls: &[(u64, u64)]) -> u64 {
-    bls.iter()
-        .fold((0, 0), |(high_all, count), (low, high)| {
-            if *low < high_all {
-                (max(high_all, *high + 1), count)
-            } else {
-                (*high + 1, count + (low - high_all))
-            }
-        })
-        .1
-}
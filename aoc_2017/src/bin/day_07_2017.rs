@@ -8,6 +8,7 @@ use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
 use util::basic_parser::parse_usize;
+use util::graph::{Graph, NodeMap};
 
 #[derive(Debug, Clone)]
 struct Program {
@@ -16,12 +17,6 @@ struct Program {
     balancing: Vec<String>,
 }
 
-impl Program {
-    fn is_balancing(&self, other: &str) -> bool {
-        self.balancing.contains(&other.to_string())
-    }
-}
-
 impl FromStr for Program {
     type Err = ();
 
@@ -58,21 +53,17 @@ struct RecursiveCircus {
 }
 
 impl RecursiveCircus {
-    fn parent(&self, name: &str) -> Option<String> {
-        for (n, p) in self.programs.iter() {
-            if p.is_balancing(name) {
-                return Some(n.clone());
-            }
-        }
-        None
-    }
-
     fn bottom_program(&self) -> String {
-        let mut name = self.programs.keys().next().unwrap().clone();
-        while let Some(parent) = self.parent(&name) {
-            name = parent;
+        let mut names: NodeMap<&str> = NodeMap::new();
+        let mut tower: Graph = Graph::default();
+        for p in self.programs.values() {
+            let below: usize = names.id(&p.name);
+            for above in p.balancing.iter() {
+                tower.add_edge(below, names.id(above));
+            }
         }
-        name
+        //The only program not standing on another one
+        names.name(tower.roots()[0]).to_string()
     }
 
     fn balance_disc(&self, name: &str) -> (Option<usize>, usize, usize) {
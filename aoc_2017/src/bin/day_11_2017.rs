@@ -1,80 +1,22 @@
-use nom::character::complete::{alpha1, char};
-use nom::combinator::map;
-use nom::multi::separated_list1;
-use nom::IResult;
-use nom::Parser;
-use std::cmp::max;
 use std::str::FromStr;
-use util::coord::PosI;
+use util::hex::{Hex, HexDir, Orientation};
 
-#[derive(Debug, Clone)]
-enum HexMove {
-    NorthWest,
-    North,
-    NorthEast,
-    SouthWest,
-    South,
-    SouthEast,
-}
-
-impl HexMove {
-    fn from(&self, PosI(x, y): PosI) -> PosI {
-        match self {
-            HexMove::NorthWest => PosI(x - 1, y + 1),
-            HexMove::North => PosI(x, y + 1),
-            HexMove::NorthEast => PosI(x + 1, y),
-            HexMove::SouthWest => PosI(x - 1, y),
-            HexMove::South => PosI(x, y - 1),
-            HexMove::SouthEast => PosI(x + 1, y - 1),
-        }
-    }
-}
-
-impl FromStr for HexMove {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "nw" => Ok(HexMove::NorthWest),
-            "n" => Ok(HexMove::North),
-            "ne" => Ok(HexMove::NorthEast),
-            "sw" => Ok(HexMove::SouthWest),
-            "s" => Ok(HexMove::South),
-            "se" => Ok(HexMove::SouthEast),
-            _ => Err(()),
-        }
-    }
-}
 #[derive(Debug, Clone)]
 struct HexEd {
-    moves: Vec<HexMove>,
+    moves: Vec<HexDir>,
 }
 
 impl HexEd {
-    fn dist_between(PosI(a, b): PosI, PosI(x, y): PosI) -> isize {
-        let dx = x - a;
-        let dy = y - b;
+    fn dist(&self) -> (usize, usize) {
+        let mut pos: Hex = Hex::origin();
+        let mut max_dist: usize = 0;
 
-        if dx * dy >= 0 {
-            (dx + dy).abs()
-        } else {
-            max(dx.abs(), dy.abs())
+        for &m in &self.moves {
+            pos = pos.step(Orientation::Flat, m);
+            max_dist = max_dist.max(pos.distance(Hex::origin()));
         }
-    }
-
-    fn dist(&self) -> (isize, isize) {
-        let mut pos: PosI = PosI(0, 0);
-        let mut max_dist: isize = 0;
 
-        for m in &self.moves {
-            pos = m.from(pos);
-            let d: isize = HexEd::dist_between(pos, PosI(0, 0));
-            if d > max_dist {
-                max_dist = d;
-            }
-        }
-
-        (HexEd::dist_between(pos, PosI(0, 0)), max_dist)
+        (pos.distance(Hex::origin()), max_dist)
     }
 }
 
@@ -82,15 +24,8 @@ impl FromStr for HexEd {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_moves(s: &str) -> IResult<&str, Vec<HexMove>> {
-            separated_list1(
-                char(','),
-                map(alpha1, |w: &str| w.parse::<HexMove>().unwrap()),
-            )
-            .parse(s)
-        }
-
-        let moves: Vec<HexMove> = s.lines().next().map(|l| parse_moves(l).unwrap().1).unwrap();
+        let line: &str = s.lines().next().ok_or(())?;
+        let moves: Vec<HexDir> = Orientation::Flat.parse_path(line).map_err(|_| ())?;
         Ok(HexEd { moves })
     }
 }
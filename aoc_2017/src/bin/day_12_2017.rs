@@ -1,4 +1,3 @@
-use fxhash::{FxHashMap, FxHashSet};
 use nom::bytes::complete::tag;
 use nom::multi::separated_list1;
 use nom::sequence::terminated;
@@ -6,50 +5,20 @@ use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
 use util::basic_parser::parse_usize;
+use util::graph::Graph;
 
 #[derive(Debug, Clone)]
 struct DigitalPlumbing {
-    pipes: FxHashMap<usize, Vec<usize>>,
+    pipes: Graph,
 }
 
 impl DigitalPlumbing {
-    fn group(&self, id: usize) -> FxHashSet<usize> {
-        let mut group_set: FxHashSet<usize> = FxHashSet::default();
-        let mut queue: Vec<usize> = vec![id];
-
-        group_set.insert(id);
-
-        while let Some(current) = queue.pop() {
-            let neighbours: &[usize] = self.pipes.get(&current).unwrap();
-
-            for n in neighbours {
-                if group_set.insert(*n) {
-                    queue.push(*n);
-                }
-            }
-        }
-
-        group_set
-    }
-
     fn group_zero(&self) -> usize {
-        self.group(0).len()
+        self.pipes.reachable(0).len()
     }
 
     fn nb_groups(&self) -> usize {
-        let mut pipes: FxHashMap<usize, Vec<usize>> = self.pipes.clone();
-
-        if let Some(&start_id) = pipes.keys().last() {
-            let set = self.group(start_id);
-            for id in set {
-                pipes.remove(&id);
-            }
-
-            let sub_plumbing = DigitalPlumbing { pipes };
-            sub_plumbing.nb_groups() + 1
-        } else {
-            0
-        }
+        self.pipes.components().len()
     }
 }
 
@@ -64,8 +33,10 @@ impl FromStr for DigitalPlumbing {
             Ok((s, (source, targets)))
         }
 
-        let pipes: FxHashMap<usize, Vec<usize>> =
-            s.lines().map(|l| parse_pipe(l).unwrap().1).collect();
+        let mut pipes: Graph = Graph::default();
+        for (source, targets) in s.lines().map(|l| parse_pipe(l).unwrap().1) {
+            targets.into_iter().for_each(|t| pipes.add_edge(source, t));
+        }
         Ok(DigitalPlumbing { pipes })
     }
 }
@@ -40,27 +40,9 @@ impl DiskDefragmenter {
     }
 
     fn from_str(s: &str) -> DiskDefragmenter {
-        let grid: Vec<Vec<bool>> = (0..128)
-            .map(|i| {
-                let source: String = format!("{s}-{i}");
-                let khash: KnotHash = KnotHash::new(&source);
-                khash
-                    .hash_vec()
-                    .into_iter()
-                    .flat_map(|mut n| {
-                        let mut v: Vec<bool> = Vec::new();
-                        (0..8).for_each(|_| {
-                            v.push(n % 2 != 0);
-                            n /= 2;
-                        });
-                        v.reverse();
-                        v
-                    })
-                    .collect()
-            })
-            .collect();
-
-        DiskDefragmenter { grid }
+        DiskDefragmenter {
+            grid: KnotHash::disk_grid(s),
+        }
     }
 }
 
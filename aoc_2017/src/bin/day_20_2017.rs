@@ -7,34 +7,26 @@ use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
 use util::basic_parser::parse_isize;
-use util::coord::Pos3I;
+use util::vector::Vector;
 
 #[derive(Debug, Copy, Clone)]
 struct Particle {
-    position: Pos3I,
-    velocity: Pos3I,
-    acceleration: Pos3I,
+    position: Vector<isize, 3>,
+    velocity: Vector<isize, 3>,
+    acceleration: Vector<isize, 3>,
 }
 
 impl Particle {
     fn abs_acceleration(&self) -> usize {
-        self.acceleration.distance(Pos3I(0, 0, 0))
+        self.acceleration.manhattan(&Vector::zero()) as usize
     }
     fn abs_velocity(&self) -> usize {
-        self.velocity.distance(Pos3I(0, 0, 0))
+        self.velocity.manhattan(&Vector::zero()) as usize
     }
 
     fn tick(&mut self) {
-        self.velocity = Pos3I(
-            self.velocity.0 + self.acceleration.0,
-            self.velocity.1 + self.acceleration.1,
-            self.velocity.2 + self.acceleration.2,
-        );
-        self.position = Pos3I(
-            self.position.0 + self.velocity.0,
-            self.position.1 + self.velocity.1,
-            self.position.2 + self.velocity.2,
-        );
+        self.velocity = self.velocity + self.acceleration;
+        self.position = self.position + self.velocity;
     }
 }
 
@@ -42,10 +34,10 @@ impl FromStr for Particle {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_triplet(s: &str) -> IResult<&str, Pos3I> {
+        fn parse_triplet(s: &str) -> IResult<&str, Vector<isize, 3>> {
             let (s, list) = separated_list1(char(','), parse_isize).parse(s)?;
 
-            Ok((s, Pos3I(list[0], list[1], list[2])))
+            Ok((s, Vector([list[0], list[1], list[2]])))
         }
 
         fn parse_particle(s: &str) -> IResult<&str, Particle> {
@@ -92,7 +84,7 @@ impl ParticleSwarm {
         //Seems like 50 ticks are enough
         for _ in 0..50 {
             let mut to_delete: FxHashSet<usize> = FxHashSet::default();
-            let mut cache: FxHashMap<Pos3I, usize> = FxHashMap::default();
+            let mut cache: FxHashMap<Vector<isize, 3>, usize> = FxHashMap::default();
 
             //Advance all particules
             moving_parts.iter_mut().for_each(|(n, p)| {
@@ -7,6 +7,7 @@ use nom::sequence::separated_pair;
 use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
+use util::symmetry::canonical;
 
 const INPUT: &str = ".#./..#/###";
 
@@ -22,29 +23,11 @@ impl Square {
         Square { grid: new_grid }
     }
 
-    fn mirror(&self) -> Square {
-        let new_grid: Vec<Vec<bool>> = self
-            .grid
-            .iter()
-            .map(|row| {
-                let mut new_row = row.clone();
-                new_row.reverse();
-                new_row
-            })
-            .collect();
-
-        Square { grid: new_grid }
-    }
-
-    fn rotate(&self) -> Square {
-        let mut new_grid: Vec<Vec<bool>> = self.grid.clone();
-
-        for (y, row) in self.grid.iter().enumerate() {
-            for (x, &p) in row.iter().enumerate() {
-                new_grid[self.grid.len() - x - 1][y] = p;
-            }
+    //Same value for all the rotations and flips of a square
+    fn canonical(&self) -> Square {
+        Square {
+            grid: canonical(&self.grid),
         }
-        Square { grid: new_grid }
     }
 
     fn number_on(&self) -> usize {
@@ -132,23 +115,18 @@ struct Fractal {
 }
 
 impl Fractal {
-    fn iterate_once(&self, square: &Square) -> Square {
+    fn iterate_once(&self, square: &Square, cache: &mut FxHashMap<Square, Square>) -> Square {
         //Split the big square
         let mut squares = square.split();
 
         //Find the corresponding rule for each submatrix
+        //The canonical form is only computed once for each distinct submatrix
         squares.iter_mut().for_each(|row| {
-            row.iter_mut().for_each(|sq| loop {
-                if let Some(target) = self.rules.get(sq) {
-                    *sq = target.clone();
-                    break;
-                }
-                let mirror: Square = sq.mirror();
-                if let Some(target) = self.rules.get(&mirror) {
-                    *sq = target.clone();
-                    break;
-                }
-                *sq = sq.rotate();
+            row.iter_mut().for_each(|sq| {
+                *sq = cache
+                    .entry(sq.clone())
+                    .or_insert_with(|| self.rules.get(&sq.canonical()).unwrap().clone())
+                    .clone()
             })
         });
 
@@ -157,9 +135,10 @@ impl Fractal {
     }
     fn iterate(&self, times: usize) -> usize {
         let mut square: Square = INPUT.parse().unwrap();
+        let mut cache: FxHashMap<Square, Square> = FxHashMap::default();
 
         for _ in 0..times {
-            square = self.iterate_once(&square);
+            square = self.iterate_once(&square, &mut cache);
         }
 
         square.number_on()
@@ -174,9 +153,11 @@ impl FromStr for Fractal {
             let (s, (source, target)): (&str, (&str, &str)) =
                 separated_pair(take_till(|c| c == ' '), tag(" => "), rest).parse(s)?;
 
-            Ok((s, (source.parse().unwrap(), target.parse().unwrap())))
+            let source: Square = source.parse().unwrap();
+            Ok((s, (source.canonical(), target.parse().unwrap())))
         }
 
+        //Rules are stored by canonical form, so any rotation or flip of a square matches
         let rules: FxHashMap<Square, Square> =
             s.lines().map(|l| parse_squares(l).unwrap().1).collect();
 
@@ -203,6 +184,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::symmetry::Symmetry;
     const EXAMPLE_1: &str = "../.# => ##./#../...
 .#./..#/### => #..#/..../..../#..#
 ";
@@ -212,7 +194,7 @@ mod tests {
         let sq1: Square = "##./#.#/...".parse().unwrap();
         let sq2: Square = ".##/..#/.#.".parse().unwrap();
 
-        assert_eq!(sq1, sq2.rotate())
+        assert_eq!(sq1.grid, Symmetry::rotation(3).view(&sq2.grid).to_vec())
     }
 
     #[test]
@@ -220,7 +202,7 @@ mod tests {
         let sq1: Square = ".##/##./#.#".parse().unwrap();
         let sq2: Square = "##./.##/#.#".parse().unwrap();
 
-        assert_eq!(sq1, sq2.mirror())
+        assert_eq!(sq1.grid, Symmetry::flip().view(&sq2.grid).to_vec())
     }
 
     #[test]
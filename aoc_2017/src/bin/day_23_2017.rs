@@ -2,6 +2,7 @@ use nom::bytes::complete::tag;
 use nom::sequence::preceded;
 use nom::Parser;
 use util::basic_parser::parse_usize;
+use util::num::is_prime;
 
 fn main() {
     let now = std::time::Instant::now();
@@ -29,12 +30,6 @@ fn optimized_loop(reg_b: usize) -> (usize, usize) {
 
     //Both inside loops start at 2 and end at reg_b - 1
     let nb_mul: usize = (reg_b - 2) * (reg_b - 2);
-    let reg_h: usize = (start..=end)
-        .step_by(17)
-        .filter(|&i| {
-            let sqrt: usize = (i as f64).sqrt() as usize;
-            (2..=sqrt).any(|div| i % div == 0)
-        })
-        .count();
+    let reg_h: usize = (start..=end).step_by(17).filter(|&i| !is_prime(i)).count();
     (nb_mul, reg_h)
 }
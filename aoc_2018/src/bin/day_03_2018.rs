@@ -1,4 +1,3 @@
-use fxhash::FxHashSet;
 use nom::bytes::complete::tag;
 use nom::character::complete::char;
 use nom::sequence::{preceded, separated_pair};
@@ -6,12 +5,12 @@ use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
 use util::basic_parser::parse_usize;
-use util::coord::Pos;
+use util::cuboid::{Cuboid, CuboidSet};
+use util::vector::Vector;
 
 #[derive(Debug)]
 struct Claim {
-    width: Pos,
-    height: Pos,
+    area: Cuboid<usize, 2>,
 }
 
 impl FromStr for Claim {
@@ -31,9 +30,9 @@ impl FromStr for Claim {
             )
             .parse(s)?;
 
+            let min: Vector<usize, 2> = Vector([min_x, min_y]);
             let claim: Claim = Claim {
-                width: Pos(min_x, min_x + width),
-                height: Pos(min_y, min_y + height),
+                area: Cuboid::new(min, min + Vector([width, height])),
             };
             Ok((s, claim))
         }
@@ -49,42 +48,25 @@ struct Fabric {
 
 impl Fabric {
     fn overlap(&self) -> (usize, Option<usize>) {
-        let mut coord_set: FxHashSet<Pos> = FxHashSet::default();
-
-        let mut overlap_set: FxHashSet<Pos> = FxHashSet::default();
+        let mut covered: CuboidSet<usize, 2> = CuboidSet::new();
+        let mut overlaps: CuboidSet<usize, 2> = CuboidSet::new();
 
         for c in self.claims.iter() {
-            let Pos(i, j) = c.width;
-            let Pos(k, l) = c.height;
-
-            for x in i..j {
-                for y in k..l {
-                    if !coord_set.insert(Pos(x, y)) {
-                        overlap_set.insert(Pos(x, y));
-                    }
-                }
-            }
+            covered
+                .pieces()
+                .iter()
+                .filter_map(|p| p.intersection(&c.area))
+                .for_each(|i| overlaps.insert(i));
+            covered.insert(c.area);
         }
 
-        let mut no_overlap: Option<usize> = None;
-        for (id, c) in self.claims.iter().enumerate() {
-            let Pos(i, j) = c.width;
-            let Pos(k, l) = c.height;
-            let mut overlap: bool = false;
-
-            for x in i..j {
-                for y in k..l {
-                    if overlap_set.contains(&Pos(x, y)) {
-                        overlap = true
-                    }
-                }
-            }
-            if !overlap {
-                no_overlap = Some(id + 1)
-            }
-        }
+        let no_overlap: Option<usize> = self
+            .claims
+            .iter()
+            .position(|c| overlaps.pieces().iter().all(|p| !p.overlaps(&c.area)))
+            .map(|id| id + 1);
 
-        (overlap_set.len(), no_overlap)
+        (overlaps.volume(), no_overlap)
     }
 }
 
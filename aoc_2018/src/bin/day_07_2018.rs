@@ -8,6 +8,7 @@ use nom::Parser;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::str::FromStr;
+use util::graph::{Graph, NodeMap};
 
 struct Instructions {
     steps: Vec<(char, char)>,
@@ -29,38 +30,19 @@ impl Instructions {
     }
 
     fn execution_order(&self) -> String {
-        //Build a map of "blockers"
-        let (mut step_set, mut blockers): (FxHashSet<char>, FxHashMap<char, FxHashSet<char>>) =
-            self.steps_and_blockers();
-
-        let mut min_queue: BinaryHeap<Reverse<char>> = BinaryHeap::new();
-        let mut result: Vec<char> = Vec::new();
-        while !step_set.is_empty() {
-            //Add nodes in set but not in map in the queue
-            let free: Vec<char> = step_set
-                .iter()
-                .filter(|s| !blockers.keys().contains(s))
-                .copied()
-                .collect();
-
-            //Remove those nodes from set
-            for f in free {
-                step_set.remove(&f);
-                min_queue.push(Reverse(f));
-            }
-
-            //Apply the min element from the queue
-            let min = min_queue.pop().unwrap().0;
-            result.push(min);
-
-            //Remove applied element from blockers and clean empty blockers
-            for (_, v) in blockers.iter_mut() {
-                v.remove(&min);
-            }
-            blockers.retain(|_, v| !v.is_empty());
+        let mut names: NodeMap<char> = NodeMap::new();
+        let mut graph: Graph = Graph::default();
+        for &(from, to) in self.steps.iter() {
+            graph.add_edge(names.id(from), names.id(to));
         }
 
-        result.into_iter().collect()
+        //Available steps are done in alphabetical order
+        graph
+            .topological_sort_by_key(|node| *names.name(node))
+            .unwrap()
+            .into_iter()
+            .map(|node| *names.name(node))
+            .collect()
     }
 
     fn execution_order_slow(&self, nb_workers: usize, added_time: usize) -> (String, usize) {
@@ -1,4 +1,5 @@
-use util::coord::{Pos, Pos3};
+use util::coord::Pos;
+use util::vector::Vector;
 
 const SIZE: usize = 300;
 
@@ -73,15 +74,15 @@ impl Fuel {
         (best_pos, best)
     }
 
-    fn best_block_all_sizes(&self) -> (Pos3, isize) {
+    fn best_block_all_sizes(&self) -> (Vector<usize, 3>, isize) {
         let mut best: isize = isize::MIN;
-        let mut best_pos: Pos3 = Pos3(0, 0, 0);
+        let mut best_pos: Vector<usize, 3> = Vector([0, 0, 0]);
 
         for i in 2..=SIZE {
             let (Pos(x, y), b) = self.best_block_of_size(i);
             if b > best {
                 best = b;
-                best_pos = Pos3(x, y, i)
+                best_pos = Vector([x, y, i])
             }
         }
         (best_pos, best)
@@ -95,7 +96,7 @@ fn main() {
     println!(
         "Part1: The 3x3 square with the largest power is in [{x},{y}], with a power of {power}"
     );
-    let (Pos3(x, y, size), power) = fuel.best_block_all_sizes();
+    let (Vector([x, y, size]), power) = fuel.best_block_all_sizes();
     println!("Part2: The square with the largest power is a {size}x{size} square in [{x},{y}], with a power of {power}");
     println!("Computing time: {:?}", now.elapsed());
 }
@@ -133,11 +134,11 @@ mod tests {
     #[test]
     fn part_2_test_1() {
         let fuel: Fuel = Fuel::from(18);
-        assert_eq!(fuel.best_block_all_sizes(), (Pos3(90, 269, 16), 113));
+        assert_eq!(fuel.best_block_all_sizes(), (Vector([90, 269, 16]), 113));
     }
     #[test]
     fn part_2_test_2() {
         let fuel: Fuel = Fuel::from(42);
-        assert_eq!(fuel.best_block_all_sizes(), (Pos3(232, 251, 12), 119));
+        assert_eq!(fuel.best_block_all_sizes(), (Vector([232, 251, 12]), 119));
     }
 }
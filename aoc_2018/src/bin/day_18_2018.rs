@@ -1,8 +1,5 @@
-use fxhash::{FxHashMap, FxHasher};
-use std::hash::{Hash, Hasher};
 use std::str::FromStr;
-use util::coord::PosI;
-
+use util::automaton::{moore, Board};
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
 enum Tile {
     OpenGround,
@@ -30,121 +27,46 @@ impl Tile {
 
 #[derive(Debug, Clone)]
 struct Forest {
-    grid: Vec<Vec<Tile>>,
+    board: Board<Tile>,
+    width: usize,
     minute: usize,
 }
 
 impl Forest {
+    //The board cycles after a while, so the minutes in the cycle can be skipped
     fn minutes_until_loop(&mut self, n: usize) -> usize {
-        let mut score_map: FxHashMap<u64, (usize, usize)> = FxHashMap::default();
-
-        let mut hash = FxHasher::default();
-        self.grid.hash(&mut hash);
-        while !score_map.contains_key(&hash.finish()) {
-            score_map.insert(hash.finish(), (self.minute, self.score()));
-            self.minute();
-
-            hash = FxHasher::default();
-            self.grid.hash(&mut hash);
-        }
-        let last: usize = self.minute;
-        let first: usize = score_map.get(&hash.finish()).copied().unwrap().0;
-        let target: usize = (n - first) % (last - first) + first;
-
-        score_map
-            .into_iter()
-            .find_map(|(_, (m, s))| if m == target { Some(s) } else { None })
-            .unwrap()
+        self.board.run(n - self.minute, Self::rule);
+        self.minute = n;
+        self.score()
     }
 
     fn minutes(&mut self, n: usize) -> usize {
         for _ in 0..n {
-            self.minute();
+            self.board.step(Self::rule);
         }
+        self.minute += n;
         self.score()
     }
 
-    fn minute(&mut self) {
-        let mut new_grid: Vec<Vec<Tile>> = Vec::new();
-
-        let height: isize = self.grid.len() as isize;
-        let width: isize = self.grid[0].len() as isize;
-
-        for y in 0..height {
-            let mut row: Vec<Tile> = Vec::new();
-            for x in 0..width {
-                let pos: PosI = PosI(x, y);
-                let ngbs: Vec<PosI> = pos.neighbours_diag_limit(width, height);
-
-                let new_tile: Tile = match self.grid[y as usize][x as usize] {
-                    Tile::OpenGround => {
-                        let nb_trees: usize = ngbs
-                            .iter()
-                            .map(|&PosI(x, y)| self.grid[y as usize][x as usize])
-                            .filter(|&t| t == Tile::Tree)
-                            .count();
-                        if nb_trees >= 3 {
-                            Tile::Tree
-                        } else {
-                            Tile::OpenGround
-                        }
-                    }
-                    Tile::Tree => {
-                        let nb_yards: usize = ngbs
-                            .iter()
-                            .map(|&PosI(x, y)| self.grid[y as usize][x as usize])
-                            .filter(|&t| t == Tile::LumberYard)
-                            .count();
-                        if nb_yards >= 3 {
-                            Tile::LumberYard
-                        } else {
-                            Tile::Tree
-                        }
-                    }
-                    Tile::LumberYard => {
-                        let nb_yards: usize = ngbs
-                            .iter()
-                            .map(|&PosI(x, y)| self.grid[y as usize][x as usize])
-                            .filter(|&t| t == Tile::LumberYard)
-                            .count();
-                        let nb_trees: usize = ngbs
-                            .iter()
-                            .map(|&PosI(x, y)| self.grid[y as usize][x as usize])
-                            .filter(|&t| t == Tile::Tree)
-                            .count();
-                        if nb_yards >= 1 && nb_trees >= 1 {
-                            Tile::LumberYard
-                        } else {
-                            Tile::OpenGround
-                        }
-                    }
-                };
-                row.push(new_tile);
+    fn rule(&tile: &Tile, ngbs: &[Tile]) -> Tile {
+        let count = |t: Tile| ngbs.iter().filter(|&&n| n == t).count();
+        match tile {
+            Tile::OpenGround if count(Tile::Tree) >= 3 => Tile::Tree,
+            Tile::Tree if count(Tile::LumberYard) >= 3 => Tile::LumberYard,
+            Tile::LumberYard if count(Tile::LumberYard) == 0 || count(Tile::Tree) == 0 => {
+                Tile::OpenGround
             }
-            new_grid.push(row);
+            _ => tile,
         }
-
-        self.grid = new_grid;
-        self.minute += 1;
     }
 
     fn score(&self) -> usize {
-        let wooded: usize = self
-            .grid
-            .iter()
-            .map(|row| row.iter().filter(|&&t| t == Tile::Tree).count())
-            .sum();
-        let yards: usize = self
-            .grid
-            .iter()
-            .map(|row| row.iter().filter(|&&t| t == Tile::LumberYard).count())
-            .sum();
-        wooded * yards
+        self.board.count(&Tile::Tree) * self.board.count(&Tile::LumberYard)
     }
 
     #[allow(dead_code)]
     fn print(&self) {
-        for row in &self.grid {
+        for row in self.board.cells.chunks(self.width) {
             for t in row {
                 print!("{}", t.as_char());
             }
@@ -162,8 +84,13 @@ impl FromStr for Forest {
             .lines()
             .map(|l| l.chars().map(Tile::from_char).collect())
             .collect();
+        let (width, height) = (grid[0].len(), grid.len());
 
-        Ok(Forest { grid, minute: 0 })
+        Ok(Forest {
+            board: Board::from_grid(grid, moore(width, height)),
+            width,
+            minute: 0,
+        })
     }
 }
 
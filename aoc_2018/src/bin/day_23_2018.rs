@@ -1,36 +1,33 @@
-use itertools::Itertools;
 use nom::bytes::complete::tag;
 use nom::character::complete::char;
 use nom::multi::separated_list1;
 use nom::sequence::preceded;
 use nom::IResult;
 use nom::Parser;
-use std::collections::BTreeMap;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::str::FromStr;
 use util::basic_parser::{parse_isize, parse_usize};
-use util::coord::Pos3I;
+use util::cuboid::Cuboid;
+use util::vector::Vector;
+
+//Number of bots reaching the cuboid, then the nearest and smallest cuboids first
+type Candidate = (usize, Reverse<isize>, Reverse<isize>, Cuboid<isize, 3>);
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 struct Nanobot {
-    pos: Pos3I,
+    pos: Vector<isize, 3>,
     radius: usize,
 }
 
 impl Nanobot {
     fn in_range(&self, other: &Nanobot) -> bool {
-        self.radius >= self.pos.distance(other.pos)
-    }
-
-    fn intersect(&self, other: &Nanobot) -> bool {
-        self.radius + other.radius >= self.pos.distance(other.pos)
+        self.radius >= self.pos.manhattan(&other.pos) as usize
     }
 
-    fn distance_to_center(&self) -> (isize, isize) {
-        let center_dist: isize = self.pos.distance(Pos3I(0, 0, 0)) as isize;
-        (
-            center_dist - self.radius as isize,
-            center_dist + self.radius as isize,
-        )
+    //The bot reaches at least one point of the cuboid
+    fn reaches(&self, cuboid: &Cuboid<isize, 3>) -> bool {
+        self.radius >= self.pos.manhattan(&cuboid.closest_point(&self.pos)) as usize
     }
 }
 
@@ -38,11 +35,11 @@ impl FromStr for Nanobot {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_nanobot(s: &str) -> IResult<&str, (Pos3I, usize)> {
+        fn parse_nanobot(s: &str) -> IResult<&str, (Vector<isize, 3>, usize)> {
             let (s, coords) =
                 preceded(tag("pos=<"), separated_list1(char(','), parse_isize)).parse(s)?;
             let (s, radius) = preceded(tag(">, r="), parse_usize).parse(s)?;
-            let pos = Pos3I(coords[0], coords[1], coords[2]);
+            let pos = Vector([coords[0], coords[1], coords[2]]);
             Ok((s, (pos, radius)))
         }
 
@@ -66,51 +63,40 @@ impl EmergencyTeleportation {
         self.bots.iter().filter(|b| strongest.in_range(b)).count()
     }
 
+    //Best-first search on cuboids, the most promising one is bisected until it is a single point
+    //The number of bots reaching a cuboid is an upper bound for every point inside,
+    //and its distance to the origin a lower bound, so the first single point popped is the best
     fn teleport_distance(&self) -> usize {
-        // Getting the list of intersecting bots for each bot
-        let mut m: Vec<(Nanobot, Vec<Nanobot>)> = self
-            .bots
-            .iter()
-            .map(|bot| {
-                let intersect: Vec<Nanobot> = self
-                    .bots
-                    .iter()
-                    .filter(|other| bot.intersect(other))
-                    .copied()
-                    .collect();
-                (*bot, intersect)
-            })
-            .sorted_by(|(_, a), (_, b)| a.len().cmp(&b.len()))
-            .collect();
-
-        // We filter bots not intersecting with all the others, starting with the largest bots
-        let mut near: Vec<Nanobot> = Vec::new();
-
-        while let Some((b, i)) = m.pop() {
-            if near.iter().all(|o| i.contains(o)) {
-                near.push(b);
+        let origin: Vector<isize, 3> = Vector::zero();
+        let score = |cuboid: Cuboid<isize, 3>| -> Candidate {
+            let nb_bots: usize = self.bots.iter().filter(|b| b.reaches(&cuboid)).count();
+            let dist: isize = cuboid.closest_point(&origin).manhattan(&origin);
+            (nb_bots, Reverse(dist), Reverse(cuboid.volume()), cuboid)
+        };
+
+        let min: Vector<isize, 3> = Vector(std::array::from_fn(|i| {
+            self.bots
+                .iter()
+                .map(|b| b.pos[i] - b.radius as isize)
+                .min()
+                .unwrap()
+        }));
+        let max: Vector<isize, 3> = Vector(std::array::from_fn(|i| {
+            self.bots
+                .iter()
+                .map(|b| b.pos[i] + b.radius as isize)
+                .max()
+                .unwrap()
+        }));
+        let mut heap: BinaryHeap<Candidate> = BinaryHeap::from([score(Cuboid::closed(min, max))]);
+
+        while let Some((_, Reverse(dist), Reverse(volume), cuboid)) = heap.pop() {
+            if volume == 1 {
+                return dist as usize;
             }
+            heap.extend(cuboid.bisect().into_iter().map(score));
         }
-
-        // We get the furthest distance attained by the nearest bot
-        // and the nearest distance attained by the farthest bot
-        let mut max_short: isize = isize::MIN;
-        let mut min_long: isize = isize::MAX;
-        let mut map: BTreeMap<isize, isize> = BTreeMap::default();
-        for b in near.iter() {
-            let (short, long) = b.distance_to_center();
-            if short > max_short {
-                max_short = short;
-            }
-            if long < min_long {
-                min_long = long;
-            }
-            *map.entry(short).or_insert(0) += 1;
-            *map.entry(long + 1).or_insert(0) -= 1;
-        }
-
-        //Getting the mean should not work in every cases, but it does in test AND our input
-        (max_short as usize + min_long as usize) / 2
+        unreachable!("The search ends on a single point")
     }
 }
 
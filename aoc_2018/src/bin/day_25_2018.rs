@@ -4,26 +4,28 @@ use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
 use util::basic_parser::parse_isize;
-use util::coord::Pos4I;
+use util::vector::Vector;
 
-const DENSITY: usize = 3;
+const DENSITY: isize = 3;
+
+type Star = Vector<isize, 4>;
 
 #[derive(Debug, Clone)]
 struct StarrySky {
-    stars: Vec<Pos4I>,
+    stars: Vec<Star>,
 }
 
 impl StarrySky {
     fn constellations(&self) -> usize {
-        let mut constellations: Vec<Vec<Pos4I>> = Vec::new();
+        let mut constellations: Vec<Vec<Star>> = Vec::new();
 
         for &star in self.stars.iter() {
             //Partition in constellations that are in range of our star and those that are not
-            let (in_range, mut out_of_range): (Vec<Vec<Pos4I>>, Vec<Vec<Pos4I>>) = constellations
+            let (in_range, mut out_of_range): (Vec<Vec<Star>>, Vec<Vec<Star>>) = constellations
                 .into_iter()
-                .partition(|c| c.iter().any(|s| s.distance(star) <= DENSITY));
+                .partition(|c| c.iter().any(|s| s.manhattan(&star) <= DENSITY));
             //Merge the constellations in range and add our new star to it
-            let mut merge: Vec<Pos4I> = in_range.into_iter().flatten().collect();
+            let mut merge: Vec<Star> = in_range.into_iter().flatten().collect();
             merge.push(star);
             out_of_range.push(merge);
             constellations = out_of_range;
@@ -37,13 +39,13 @@ impl FromStr for StarrySky {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_star(s: &str) -> IResult<&str, Pos4I> {
+        fn parse_star(s: &str) -> IResult<&str, Star> {
             let (s, v) = separated_list1(char(','), parse_isize).parse(s)?;
 
-            Ok((s, Pos4I(v[0], v[1], v[2], v[3])))
+            Ok((s, Vector([v[0], v[1], v[2], v[3]])))
         }
 
-        let stars: Vec<Pos4I> = s.lines().map(|l| parse_star(l).unwrap().1).collect();
+        let stars: Vec<Star> = s.lines().map(|l| parse_star(l).unwrap().1).collect();
 
         Ok(StarrySky { stars })
     }
@@ -31,13 +31,7 @@ impl Position {
     }
 
     fn advance(&mut self) {
-        let PosI(x, y): PosI = match &self.orient {
-            Dir::North => PosI(0, -1),
-            Dir::East => PosI(1, 0),
-            Dir::South => PosI(0, 1),
-            Dir::West => PosI(-1, 0),
-        };
-        self.pos = PosI(self.pos.0 + x, self.pos.1 + y);
+        self.pos = self.orient.step_n(self.pos, 1);
     }
 }
 
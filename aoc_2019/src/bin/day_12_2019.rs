@@ -1,11 +1,11 @@
 use std::cmp::Ordering;
 use std::str::FromStr;
-use util::coord::Pos3I;
+use util::vector::Vector;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct Moon {
-    pos: Pos3I,
-    velocity: Pos3I,
+    pos: Vector<isize, 3>,
+    velocity: Vector<isize, 3>,
 }
 
 impl Moon {
@@ -14,11 +14,11 @@ impl Moon {
     }
 
     fn kinetic_abs(&self) -> isize {
-        self.velocity.0.abs() + self.velocity.1.abs() + self.velocity.2.abs()
+        self.velocity.manhattan(&Vector::zero())
     }
 
     fn potential_abs(&self) -> isize {
-        self.pos.0.abs() + self.pos.1.abs() + self.pos.2.abs()
+        self.pos.manhattan(&Vector::zero())
     }
 
     fn gravity(&mut self, other: &Moon) {
@@ -30,23 +30,15 @@ impl Moon {
             }
         }
 
-        let dx: isize = diff(other.pos.0, self.pos.0);
-        let dy: isize = diff(other.pos.1, self.pos.1);
-        let dz: isize = diff(other.pos.2, self.pos.2);
+        let dx: isize = diff(other.pos[0], self.pos[0]);
+        let dy: isize = diff(other.pos[1], self.pos[1]);
+        let dz: isize = diff(other.pos[2], self.pos[2]);
 
-        self.velocity = Pos3I(
-            self.velocity.0 + dx,
-            self.velocity.1 + dy,
-            self.velocity.2 + dz,
-        );
+        self.velocity = self.velocity + Vector([dx, dy, dz]);
     }
 
     fn velocity(&mut self) {
-        self.pos = Pos3I(
-            self.pos.0 + self.velocity.0,
-            self.pos.1 + self.velocity.1,
-            self.pos.2 + self.velocity.2,
-        );
+        self.pos = self.pos + self.velocity;
     }
 }
 
@@ -58,8 +50,8 @@ impl FromStr for Moon {
         let x: isize = words[1].parse().unwrap();
         let y: isize = words[3].parse().unwrap();
         let z: isize = words[5].parse().unwrap();
-        let pos: Pos3I = Pos3I(x, y, z);
-        let velocity: Pos3I = Pos3I(0, 0, 0);
+        let pos: Vector<isize, 3> = Vector([x, y, z]);
+        let velocity: Vector<isize, 3> = Vector([0, 0, 0]);
         Ok(Self { pos, velocity })
     }
 }
@@ -89,23 +81,26 @@ impl System {
     }
 
     fn step_loop(&mut self) -> usize {
-        let start_pos_x: Vec<isize> = self.moons.iter().map(|moon| moon.pos.0).collect();
-        let start_pos_y: Vec<isize> = self.moons.iter().map(|moon| moon.pos.1).collect();
-        let start_pos_z: Vec<isize> = self.moons.iter().map(|moon| moon.pos.2).collect();
-        let start_vel_x: Vec<isize> = self.moons.iter().map(|moon| moon.velocity.0).collect();
-        let start_vel_y: Vec<isize> = self.moons.iter().map(|moon| moon.velocity.1).collect();
-        let start_vel_z: Vec<isize> = self.moons.iter().map(|moon| moon.velocity.2).collect();
+        let start_pos_x: Vec<isize> = self.moons.iter().map(|moon| moon.pos[0]).collect();
+        let start_pos_y: Vec<isize> = self.moons.iter().map(|moon| moon.pos[1]).collect();
+        let start_pos_z: Vec<isize> = self.moons.iter().map(|moon| moon.pos[2]).collect();
+        let start_vel_x: Vec<isize> = self.moons.iter().map(|moon| moon.velocity[0]).collect();
+        let start_vel_y: Vec<isize> = self.moons.iter().map(|moon| moon.velocity[1]).collect();
+        let start_vel_z: Vec<isize> = self.moons.iter().map(|moon| moon.velocity[2]).collect();
 
         let (mut x_cycle, mut y_cycle, mut z_cycle): (usize, usize, usize) = (0, 0, 0);
 
         let mut i: usize = 0;
         loop {
-            let current_pos_x: Vec<isize> = self.moons.iter().map(|moon| moon.pos.0).collect();
-            let current_pos_y: Vec<isize> = self.moons.iter().map(|moon| moon.pos.1).collect();
-            let current_pos_z: Vec<isize> = self.moons.iter().map(|moon| moon.pos.2).collect();
-            let current_vel_x: Vec<isize> = self.moons.iter().map(|moon| moon.velocity.0).collect();
-            let current_vel_y: Vec<isize> = self.moons.iter().map(|moon| moon.velocity.1).collect();
-            let current_vel_z: Vec<isize> = self.moons.iter().map(|moon| moon.velocity.2).collect();
+            let current_pos_x: Vec<isize> = self.moons.iter().map(|moon| moon.pos[0]).collect();
+            let current_pos_y: Vec<isize> = self.moons.iter().map(|moon| moon.pos[1]).collect();
+            let current_pos_z: Vec<isize> = self.moons.iter().map(|moon| moon.pos[2]).collect();
+            let current_vel_x: Vec<isize> =
+                self.moons.iter().map(|moon| moon.velocity[0]).collect();
+            let current_vel_y: Vec<isize> =
+                self.moons.iter().map(|moon| moon.velocity[1]).collect();
+            let current_vel_z: Vec<isize> =
+                self.moons.iter().map(|moon| moon.velocity[2]).collect();
             if x_cycle == 0 && start_pos_x == current_pos_x && start_vel_x == current_vel_x {
                 x_cycle = i;
             }
@@ -116,11 +111,7 @@ impl System {
                 z_cycle = i;
             }
             if x_cycle != 0 && y_cycle != 0 && z_cycle != 0 {
-                let cycle: usize = util::lcm(
-                    x_cycle as isize,
-                    util::lcm(y_cycle as isize, z_cycle as isize),
-                ) as usize;
-                return cycle;
+                return util::num::lcm_all([x_cycle, y_cycle, z_cycle]);
             }
             self.step();
             i += 1;
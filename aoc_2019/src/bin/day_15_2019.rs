@@ -19,22 +19,14 @@ struct Position {
 
 impl Position {
     fn neighbours(&self) -> Vec<(PosI, Dir)> {
-        let PosI(x, y): PosI = self.pos;
-        vec![
-            (PosI(x, y - 1), Dir::North),
-            (PosI(x + 1, y), Dir::East),
-            (PosI(x, y + 1), Dir::South),
-            (PosI(x - 1, y), Dir::West),
-        ]
+        Dir::ALL
+            .into_iter()
+            .map(|dir| (self.tile_in_dir(&dir), dir))
+            .collect()
     }
 
     fn tile_in_dir(&self, dir: &Dir) -> PosI {
-        match dir {
-            Dir::North => PosI(self.pos.0, self.pos.1 - 1),
-            Dir::South => PosI(self.pos.0, self.pos.1 + 1),
-            Dir::West => PosI(self.pos.0 - 1, self.pos.1),
-            Dir::East => PosI(self.pos.0 + 1, self.pos.1),
-        }
+        dir.step_n(self.pos, 1)
     }
 
     fn move_dir(&mut self, dir: &Dir) {
@@ -139,20 +139,10 @@ impl Scaffolding {
     }
 
     fn robot_path(&self) -> String {
-        fn move_ahead(Pos(x, y): Pos, dir: &Dir) -> Pos {
-            match dir {
-                Dir::North => Pos(x, y - 1),
-                Dir::East => Pos(x + 1, y),
-                Dir::South => Pos(x, y + 1),
-                Dir::West => Pos(x - 1, y),
-            }
-        }
-        fn move_left(pos: Pos, dir: &Dir) -> Pos {
-            move_ahead(pos, &dir.turn_left())
-        }
-        fn move_right(pos: Pos, dir: &Dir) -> Pos {
-            move_ahead(pos, &dir.turn_right())
-        }
+        //Off the top or left edge, there is no scaffold
+        let scaffold = |pos: Pos, dir: Dir| -> Option<Pos> {
+            dir.step(pos).filter(|p| self.scaffolds.contains(p))
+        };
         let mut moving_robot: Robot = self.robot;
         let mut path: Path = Path {
             current: 0,
@@ -160,20 +150,17 @@ impl Scaffolding {
         };
         loop {
             //Move ahead
-            let ahead: Pos = move_ahead(moving_robot.pos, &moving_robot.dir);
-            if self.scaffolds.contains(&ahead) {
+            if let Some(ahead) = scaffold(moving_robot.pos, moving_robot.dir) {
                 path.advance();
                 moving_robot.pos = ahead;
             } else {
                 //Move right
-                let right: Pos = move_right(moving_robot.pos, &moving_robot.dir);
-                if self.scaffolds.contains(&right) {
+                if scaffold(moving_robot.pos, moving_robot.dir.turn_right()).is_some() {
                     path.turn_right();
                     moving_robot.dir = moving_robot.dir.turn_right();
                 } else {
                     //Move left
-                    let left: Pos = move_left(moving_robot.pos, &moving_robot.dir);
-                    if self.scaffolds.contains(&left) {
+                    if scaffold(moving_robot.pos, moving_robot.dir.turn_left()).is_some() {
                         path.turn_left();
                         moving_robot.dir = moving_robot.dir.turn_left();
                     } else {
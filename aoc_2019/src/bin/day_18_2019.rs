@@ -1,23 +1,11 @@
-use itertools::Itertools;
 use std::cmp::Ordering;
-use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
 use std::str::FromStr;
+use util::contraction::Contraction;
 use util::coord::Pos;
 
 type DistMatrix = HashMap<(char, char), (usize, Vec<char>)>;
 
-struct AStarNode {
-    pos: Pos,
-    dist: usize,
-    doors: Vec<char>,
-}
-
-impl AStarNode {
-    fn score(&self, end: Pos) -> usize {
-        self.dist + self.pos.distance(end)
-    }
-}
-
 #[derive(Clone, Eq, PartialEq)]
 struct KeyPath {
     keys: BTreeSet<char>,
@@ -224,10 +212,10 @@ impl Tunnels {
         let se: Pos = Pos(x + 1, y + 1);
 
         //Get the distance matrix for each quarter tunnel
-        let dist_nw: DistMatrix = self.distance_matrix_quarter(nw);
-        let dist_ne: DistMatrix = self.distance_matrix_quarter(ne);
-        let dist_sw: DistMatrix = self.distance_matrix_quarter(sw);
-        let dist_se: DistMatrix = self.distance_matrix_quarter(se);
+        let dist_nw: DistMatrix = self.distance_matrix_from(nw);
+        let dist_ne: DistMatrix = self.distance_matrix_from(ne);
+        let dist_sw: DistMatrix = self.distance_matrix_from(sw);
+        let dist_se: DistMatrix = self.distance_matrix_from(se);
 
         let matrixes: [DistMatrix; 4] = [dist_nw, dist_ne, dist_sw, dist_se];
 
@@ -319,115 +307,54 @@ impl Tunnels {
         None
     }
 
-    fn distance_matrix_quarter(&self, start: Pos) -> DistMatrix {
-        let mut dist_matrix: DistMatrix = self
-            .keys
-            .iter()
-            .filter_map(|(&a, &pos_a)| self.a_star(start, pos_a).map(|pair| (('@', a), pair)))
-            .collect();
-
-        let keys: Vec<char> = dist_matrix.keys().map(|&(_, k)| k).collect();
-
-        let keys_map: HashMap<char, Pos> = self
-            .keys
-            .clone()
-            .into_iter()
-            .filter(|(k, _)| keys.contains(k))
-            .collect();
-
-        let rest_matrix: DistMatrix = keys_map
-            .iter()
-            .cartesian_product(keys_map.iter())
-            .filter_map(|((&a, &pos_a), (&b, &pos_b))| {
-                if a < b {
-                    Some(((a, b), self.a_star(pos_a, pos_b).unwrap()))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        dist_matrix.extend(rest_matrix);
-
-        dist_matrix
-    }
-
     fn distance_matrix(&self) -> DistMatrix {
-        let mut dist_matrix: DistMatrix = self
-            .keys
-            .iter()
-            .filter_map(|(&a, &pos)| self.a_star(self.start, pos).map(|pair| (('@', a), pair)))
-            .collect();
-        let rest_matrix: DistMatrix = self
-            .keys
-            .iter()
-            .cartesian_product(self.keys.iter())
-            .filter_map(|((&a, &pos_a), (&b, &pos_b))| {
-                if a < b {
-                    Some(((a, b), self.a_star(pos_a, pos_b).unwrap()))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        dist_matrix.extend(rest_matrix);
-        dist_matrix
+        self.distance_matrix_from(self.start)
     }
 
-    fn a_star(&self, start: Pos, end: Pos) -> Option<(usize, Vec<char>)> {
-        let start_node: AStarNode = AStarNode {
-            pos: start,
-            dist: 0,
-            doors: Vec::new(),
-        };
-        let mut nodes: Vec<AStarNode> = vec![start_node];
-        let mut visited_pos: HashSet<Pos> = HashSet::from([start]);
-        loop {
-            if let Some(best_node) = nodes.pop() {
-                if best_node.pos == end {
-                    return Some((best_node.dist, best_node.doors));
-                }
-                //Build the new nodes from the current neighbours
-                let new_nodes: Vec<AStarNode> = best_node
-                    .pos
-                    .neighbours()
+    //Steps between the start and every reachable key, and between each pair of them,
+    //along with the doors on the way
+    fn distance_matrix_from(&self, start: Pos) -> DistMatrix {
+        let (max_x, max_y) = (self.grid[0].len(), self.grid.len());
+        let tile = |&Pos(x, y): &Pos| self.grid[y][x];
+        //Doors are points of interest too, so that they get recorded on the way
+        let tunnels: Contraction<Pos> = Contraction::all_pairs(
+            [start],
+            |p: &Pos| {
+                p.neighbours_safe(max_x, max_y)
                     .into_iter()
-                    .filter_map(|p @ Pos(x, y)| {
-                        let c: char = self.grid[y][x];
-                        match c {
-                            '#' => None,
-                            'A'..='Z' => {
-                                let mut doors: Vec<char> = best_node.doors.clone();
-                                doors.push(c);
-                                Some(AStarNode {
-                                    pos: p,
-                                    dist: best_node.dist + 1,
-                                    doors,
-                                })
-                            }
-                            _ => Some(AStarNode {
-                                pos: p,
-                                dist: best_node.dist + 1,
-                                doors: best_node.doors.clone(),
-                            }),
-                        }
-                    })
-                    .collect();
-
-                new_nodes
-                    .into_iter()
-                    //Filter already visited positions
-                    .filter(|node| visited_pos.insert(node.pos))
-                    .for_each(|node| {
-                        let i: usize = nodes.partition_point(|x| x.score(end) > node.score(end));
-                        nodes.insert(i, node);
-                    })
+                    .filter(|p| tile(p) != '#')
+                    .collect::<Vec<Pos>>()
+            },
+            |p| tile(p).is_ascii_alphabetic(),
+        );
+        let name = |id: usize| {
+            if id == 0 {
+                '@'
             } else {
-                //The node cannot reach the end
-                return None;
+                tile(tunnels.nodes.name(id))
             }
-        }
+        };
+
+        (0..tunnels.len())
+            .filter(|&a| a == 0 || name(a).is_ascii_lowercase())
+            .flat_map(|a| {
+                tunnels
+                    .graph
+                    .edges(a)
+                    .iter()
+                    .map(move |&(b, steps)| (a, b, steps))
+            })
+            .filter(|&(a, b, _)| name(b).is_ascii_lowercase() && name(a) < name(b))
+            .map(|(a, b, steps)| {
+                let doors: Vec<char> = tunnels
+                    .through(a, b)
+                    .iter()
+                    .map(tile)
+                    .filter(|c| c.is_ascii_uppercase())
+                    .collect();
+                ((name(a), name(b)), (steps, doors))
+            })
+            .collect()
     }
 
     fn split_start(&mut self) {
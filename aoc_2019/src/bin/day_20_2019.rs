@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{BinaryHeap, HashMap};
 use std::str::FromStr;
+use util::contraction::Contraction;
 use util::coord::Pos;
 
 type Portal = (char, char);
@@ -15,11 +16,6 @@ enum Tile {
     Outer(Portal),
 }
 
-struct BfsNode {
-    pos: Pos,
-    steps: usize,
-}
-
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 struct DijkstraNode {
     tile: Tile,
@@ -193,78 +189,47 @@ impl DonutMaze {
         None
     }
 
+    //All reachable Portals from each portal and the number of steps to do so
     fn distance_matrix(&self) -> HashMap<Tile, Vec<(Tile, usize)>> {
-        let height: usize = self.grid.len();
-        let width: usize = self.grid[0].len();
-
-        let mut matrix: HashMap<Tile, Vec<(Tile, usize)>> = HashMap::new();
-
-        for y in 0..height {
-            for x in 0..width {
-                match self.grid[y][x] {
-                    inner @ Tile::Inner(_) => {
-                        matrix.insert(inner, self.bfs(Pos(x, y)));
-                    }
-                    outer @ Tile::Outer(p) if p != END => {
-                        matrix.insert(outer, self.bfs(Pos(x, y)));
-                    }
-                    _ => (),
-                }
-            }
-        }
-        matrix
-    }
-
-    //Returns the list of all reachable Portals from a given position and the number of steps to do so
-    fn bfs(&self, start: Pos) -> Vec<(Tile, usize)> {
-        let start_node: BfsNode = BfsNode {
-            pos: start,
-            steps: 0,
+        let tile = |&Pos(x, y): &Pos| self.grid[y][x];
+        let portals: Vec<Pos> = (0..self.grid.len())
+            .flat_map(|y| (0..self.grid[0].len()).map(move |x| Pos(x, y)))
+            .filter(|p| match tile(p) {
+                Tile::Inner(_) => true,
+                Tile::Outer(p) => p != END,
+                _ => false,
+            })
+            .collect();
+        //Never try to return to the "AA" start
+        let is_target = |p: &Pos| match tile(p) {
+            Tile::Inner(_) => true,
+            Tile::Outer(p) => p != START,
+            _ => false,
         };
-
-        let mut nodes: Vec<BfsNode> = vec![start_node];
-        let mut visited_pos: HashSet<Pos> = HashSet::from([start]);
-        let mut reachable_portals: Vec<(Tile, usize)> = Vec::new();
-
-        while let Some(curr_node) = nodes.pop() {
-            //If we are on a Portal, we add this portal and the number of steps
-            if curr_node.pos != start {
-                match self.grid[curr_node.pos.1][curr_node.pos.0] {
-                    inner @ Tile::Inner(_) => {
-                        reachable_portals.push((inner, curr_node.steps));
-                        continue;
-                    }
-                    //Never try to return to the "AA" start
-                    outer @ Tile::Outer(p) if p != START => {
-                        reachable_portals.push((outer, curr_node.steps));
-                        continue;
-                    }
-                    _ => (),
-                }
-            }
-            //Build the new nodes from the current neighbours
-            let new_nodes: Vec<BfsNode> = curr_node
-                .pos
-                .neighbours()
-                .into_iter()
-                .filter_map(|p @ Pos(x, y)| {
-                    if let Tile::Wall = self.grid[y][x] {
-                        None
-                    } else if visited_pos.insert(p) {
-                        Some(BfsNode {
-                            pos: p,
-                            steps: curr_node.steps + 1,
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            nodes.extend(new_nodes);
-        }
-        reachable_portals
+        let maze: Contraction<Pos> = Contraction::new(
+            portals,
+            |p: &Pos| {
+                p.neighbours()
+                    .into_iter()
+                    .filter(|p| tile(p) != Tile::Wall)
+                    .collect::<Vec<Pos>>()
+            },
+            is_target,
+        );
+
+        (0..maze.len())
+            .map(|a| {
+                let reachable: Vec<(Tile, usize)> = maze
+                    .graph
+                    .edges(a)
+                    .iter()
+                    .map(|&(b, steps)| (tile(maze.nodes.name(b)), steps))
+                    .collect();
+                (tile(maze.nodes.name(a)), reachable)
+            })
+            .collect()
     }
+
     #[allow(dead_code)]
     fn print(&self) {
         for row in self.grid.iter() {
@@ -1,5 +1,4 @@
-use itertools::Itertools;
-use util::coord::PosI;
+use util::automaton::{line_of_sight, moore, Board};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Seat {
@@ -9,155 +8,62 @@ enum Seat {
 }
 
 struct WaitingArea {
-    seats: Vec<Vec<Seat>>,
+    seats: Board<Seat>,
+    //Occupied seats an occupied seat can stand
+    tolerance: usize,
 }
 
 impl WaitingArea {
-    fn next(&self, first_part: bool) -> Self {
-        let max_x: usize = self.seats[0].len();
-        let max_y: usize = self.seats.len();
-        let mut new_seats = vec![vec![Seat::Floor; max_x]; max_y];
-        for (j, row) in self.seats.iter().enumerate() {
-            for (i, &seat) in row.iter().enumerate() {
-                let nb_occupied =
-                    self.nb_occupied_neighbours(PosI(i as isize, j as isize), first_part);
-
-                let limit: usize = if first_part { 4 } else { 5 };
-
-                new_seats[j][i] = match (seat, nb_occupied) {
-                    (Seat::Floor, _) => Seat::Floor,
-                    (Seat::Empty, 0) => Seat::Occupied,
-                    (Seat::Occupied, n) if n < limit => Seat::Occupied,
-                    _ => Seat::Empty,
-                }
-            }
+    //Seats next to each other
+    fn new(seats: Vec<Vec<Seat>>) -> Self {
+        let (width, height) = (seats[0].len(), seats.len());
+        WaitingArea {
+            seats: Board::from_grid(seats, moore(width, height)),
+            tolerance: 3,
         }
-        WaitingArea { seats: new_seats }
-    }
-    fn nb_occupied_neighbours(&self, node: PosI, stop_at_first: bool) -> usize {
-        let max_x: usize = self.seats[0].len();
-        let max_y: usize = self.seats.len();
-        if stop_at_first {
-            Self::neighbours(node, max_x as isize, max_y as isize)
-                .iter()
-                .map(|&PosI(x, y)| self.seats[y as usize][x as usize])
-                .filter(|&seat| seat == Seat::Occupied)
-                .count()
-        } else {
-            self.distant_neighbours(node, max_x as isize, max_y as isize)
-        }
-    }
-
-    fn distant_neighbours(&self, node: PosI, max_x: isize, max_y: isize) -> usize {
-        let directions: Vec<PosI> = (-1..=1)
-            .cartesian_product(-1..=1)
-            .map(|(x, y)| PosI(x, y))
-            .filter(|&PosI(x, y)| x != 0 || y != 0)
-            .collect();
-        directions
-            .iter()
-            .filter_map(|&PosI(x, y)| {
-                let mut n: PosI = PosI(node.0 + x, node.1 + y);
-                while n.0 >= 0
-                    && n.0 < max_x
-                    && n.1 >= 0
-                    && n.1 < max_y
-                    && self.seats[n.1 as usize][n.0 as usize] == Seat::Floor
-                {
-                    n = PosI(n.0 + x, n.1 + y);
-                }
-                if n.0 >= 0
-                    && n.0 < max_x
-                    && n.1 >= 0
-                    && n.1 < max_y
-                    && self.seats[n.1 as usize][n.0 as usize] == Seat::Occupied
-                {
-                    Some(1)
-                } else {
-                    None
-                }
-            })
-            .count()
-    }
-
-    fn neighbours(node: PosI, max_x: isize, max_y: isize) -> Vec<PosI> {
-        (node.0 - 1..=node.0 + 1)
-            .cartesian_product(node.1 - 1..=node.1 + 1)
-            .map(|(x, y)| PosI(x, y))
-            .filter(|&PosI(x, y)| {
-                x >= 0 && x < max_x && y >= 0 && y < max_y && (x != node.0 || y != node.1)
-            })
-            .collect()
-    }
-
-    fn equal(&self, other: &WaitingArea) -> bool {
-        self.seats.iter().enumerate().all(|(j, row)| {
-            row.iter()
-                .enumerate()
-                .all(|(i, seat)| *seat == other.seats[j][i])
-        })
     }
 
-    fn nb_occupied(&self) -> usize {
-        self.seats
-            .iter()
-            .map(|row| row.iter().filter(|seat| **seat == Seat::Occupied).count())
-            .sum()
+    //The first seat seen in each direction
+    fn new_far_sighted(seats: Vec<Vec<Seat>>) -> Self {
+        let sight: Vec<Vec<usize>> = line_of_sight(&seats, |&s| s == Seat::Floor);
+        WaitingArea {
+            seats: Board::from_grid(seats, sight),
+            tolerance: 4,
+        }
     }
 
-    #[allow(dead_code)]
-    fn print(&self) {
-        for row in self.seats.iter() {
-            for &seat in row.iter() {
-                let c = match seat {
-                    Seat::Floor => '.',
-                    Seat::Empty => 'L',
-                    Seat::Occupied => '#',
-                };
-                print!("{c}");
+    fn equilibrium(&mut self) -> usize {
+        let tolerance: usize = self.tolerance;
+        self.seats.run_until_stable(|&seat, ngbs| {
+            let nb_occupied: usize = ngbs.iter().filter(|&&s| s == Seat::Occupied).count();
+            match (seat, nb_occupied) {
+                (Seat::Empty, 0) => Seat::Occupied,
+                (Seat::Occupied, n) if n > tolerance => Seat::Empty,
+                _ => seat,
             }
-            println!();
-        }
-        println!();
+        });
+        self.seats.count(&Seat::Occupied)
     }
 }
 
 fn main() {
     let s = util::file_as_string("aoc_2020/input/day_11.txt").expect("Cannot open input file");
 
-    let mut waiting_area = WaitingArea {
-        seats: parse_seats(s.clone()),
-    };
-    loop {
-        let new_area = waiting_area.next(true);
-        if waiting_area.equal(&new_area) {
-            break;
-        }
-        waiting_area = new_area;
-    }
+    let mut waiting_area = WaitingArea::new(parse_seats(&s));
     println!(
         "Part1: {} seats are occupied at equilibrium",
-        waiting_area.nb_occupied()
+        waiting_area.equilibrium()
     );
 
     //Part 2
-    let mut waiting_area_2 = WaitingArea {
-        seats: parse_seats(s),
-    };
-    loop {
-        let new_area_2 = waiting_area_2.next(false);
-        if waiting_area_2.equal(&new_area_2) {
-            break;
-        }
-        waiting_area_2 = new_area_2;
-    }
+    let mut waiting_area_2 = WaitingArea::new_far_sighted(parse_seats(&s));
     println!(
         "Part2: {} seats are occupied at equilibrium",
-        waiting_area_2.nb_occupied()
+        waiting_area_2.equilibrium()
     );
 }
 
-fn parse_seats(s: String) -> Vec<Vec<Seat>> {
+fn parse_seats(s: &str) -> Vec<Vec<Seat>> {
     s.lines()
         .map(|s| {
             s.chars()
@@ -186,31 +92,13 @@ L.LLLLL.LL";
 
     #[test]
     fn test_part1() {
-        let mut waiting_area = WaitingArea {
-            seats: parse_seats(TEST_CASE.to_string()),
-        };
-        loop {
-            let new_area = waiting_area.next(true);
-            if waiting_area.equal(&new_area) {
-                break;
-            }
-            waiting_area = new_area;
-        }
-        assert_eq!(waiting_area.nb_occupied(), 37);
+        let mut waiting_area = WaitingArea::new(parse_seats(TEST_CASE));
+        assert_eq!(waiting_area.equilibrium(), 37);
     }
 
     #[test]
     fn test_part2() {
-        let mut waiting_area = WaitingArea {
-            seats: parse_seats(TEST_CASE.to_string()),
-        };
-        loop {
-            let new_area = waiting_area.next(false);
-            if waiting_area.equal(&new_area) {
-                break;
-            }
-            waiting_area = new_area;
-        }
-        assert_eq!(waiting_area.nb_occupied(), 26);
+        let mut waiting_area = WaitingArea::new_far_sighted(parse_seats(TEST_CASE));
+        assert_eq!(waiting_area.equilibrium(), 26);
     }
 }
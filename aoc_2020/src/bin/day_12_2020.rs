@@ -1,123 +1,68 @@
 use std::str::FromStr;
-use util::orientation::Dir;
+use util::coord::PosI;
+use util::orientation::{Dir, Turn};
 
 #[derive(Debug)]
 enum Action {
-    North(isize),
-    South(isize),
-    East(isize),
-    West(isize),
-    Left(isize),
-    Right(isize),
+    Move(Dir, isize),
+    Turn(Turn),
     Forward(isize),
 }
 
 impl FromStr for Action {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let chars: Vec<char> = s.chars().collect();
-        let value: isize = s[1..].parse().unwrap();
-        match chars[0] {
-            'N' => Ok(Action::North(value)),
-            'S' => Ok(Action::South(value)),
-            'E' => Ok(Action::East(value)),
-            'W' => Ok(Action::West(value)),
-            'L' => Ok(Action::Left(value)),
-            'R' => Ok(Action::Right(value)),
-            'F' => Ok(Action::Forward(value)),
-            _ => Err(()),
+        let value = || -> Result<isize, String> {
+            s[1..].parse().map_err(|_| format!("Invalid action [{s}]"))
+        };
+        match s.chars().next() {
+            Some('L') | Some('R') => Ok(Action::Turn(s.parse()?)),
+            Some('F') => Ok(Action::Forward(value()?)),
+            Some(c) => Ok(Action::Move(Dir::from_char(c)?, value()?)),
+            None => Err("Empty action".to_string()),
         }
     }
 }
 
 struct Boat {
-    x: isize,
-    y: isize,
+    pos: PosI,
     dir: Dir,
 }
 
 impl Boat {
     fn move_boat(&mut self, action: &Action) {
         match action {
-            Action::North(n) => self.y += *n,
-            Action::South(n) => self.y -= *n,
-            Action::East(n) => self.x += *n,
-            Action::West(n) => self.x -= *n,
-            Action::Left(n) => {
-                (0..n / 90).for_each(|_| self.rotate_left());
-            }
-            Action::Right(n) => {
-                (0..n / 90).for_each(|_| self.rotate_right());
-            }
-            Action::Forward(n) => {
-                self.move_forward(*n);
-            }
+            Action::Move(dir, n) => self.pos = dir.step_n(self.pos, *n),
+            Action::Turn(turn) => self.dir = self.dir.turn(*turn).unwrap(),
+            Action::Forward(n) => self.pos = self.dir.step_n(self.pos, *n),
         }
     }
 
-    fn move_forward(&mut self, n: isize) {
-        match &self.dir {
-            Dir::North => self.y += n,
-            Dir::South => self.y -= n,
-            Dir::East => self.x += n,
-            Dir::West => self.x -= n,
-        }
-    }
-    fn rotate_left(&mut self) {
-        self.dir = self.dir.turn_left();
-    }
-
-    fn rotate_right(&mut self) {
-        self.dir = self.dir.turn_right();
-    }
-
-    fn dist(&self) -> isize {
-        self.x.abs() + self.y.abs()
+    fn dist(&self) -> usize {
+        self.pos.distance(PosI(0, 0))
     }
 }
 
 struct WaypointBoat {
-    x: isize,
-    y: isize,
-    w_x: isize,
-    w_y: isize,
+    pos: PosI,
+    waypoint: PosI,
 }
 
 impl WaypointBoat {
     fn move_boat(&mut self, action: &Action) {
         match action {
-            Action::North(n) => self.w_y += *n,
-            Action::South(n) => self.w_y -= *n,
-            Action::East(n) => self.w_x += *n,
-            Action::West(n) => self.w_x -= *n,
-            Action::Left(n) => {
-                (0..n / 90).for_each(|_| self.rotate_left());
-            }
-            Action::Right(n) => {
-                (0..n / 90).for_each(|_| self.rotate_right());
-            }
+            Action::Move(dir, n) => self.waypoint = dir.step_n(self.waypoint, *n),
+            Action::Turn(turn) => self.waypoint = turn.rotate(self.waypoint).unwrap(),
             Action::Forward(n) => {
-                self.move_to_waypoint(*n);
+                let PosI(w_x, w_y) = self.waypoint;
+                self.pos = self.pos.add(PosI(w_x * n, w_y * n));
             }
         }
     }
 
-    fn move_to_waypoint(&mut self, times: isize) {
-        self.x += self.w_x * times;
-        self.y += self.w_y * times;
-    }
-
-    fn rotate_left(&mut self) {
-        (self.w_x, self.w_y) = (-self.w_y, self.w_x)
-    }
-
-    fn rotate_right(&mut self) {
-        (self.w_x, self.w_y) = (self.w_y, -self.w_x)
-    }
-
-    fn dist(&self) -> isize {
-        self.x.abs() + self.y.abs()
+    fn dist(&self) -> usize {
+        self.pos.distance(PosI(0, 0))
     }
 }
 
@@ -127,8 +72,7 @@ fn main() {
     let actions: Vec<Action> = s.lines().map(|s| s.parse().unwrap()).collect();
 
     let mut boat = Boat {
-        x: 0,
-        y: 0,
+        pos: PosI(0, 0),
         dir: Dir::East,
     };
     actions.iter().for_each(|ac| {
@@ -139,11 +83,10 @@ fn main() {
         boat.dist()
     );
 
+    //10 units east and 1 unit north, y grows to the south
     let mut wp_boat = WaypointBoat {
-        x: 0,
-        y: 0,
-        w_x: 10,
-        w_y: 1,
+        pos: PosI(0, 0),
+        waypoint: PosI(10, -1),
     };
     actions.iter().for_each(|ac| {
         wp_boat.move_boat(ac);
@@ -1,42 +1,41 @@
 use std::cmp::max;
 use std::collections::HashMap;
 use std::str::FromStr;
-use util::orientation::Dir;
+use util::symmetry::Symmetry;
 
 const TILE_SIZE: usize = 10;
 const MONSTER: &str = "                  # 
 #    ##    ##    ###
  #  #  #  #  #  #   ";
 
+//Sides are numbered 0 for up, 1 for right, 2 for down and 3 for left
 #[derive(Debug, Copy, Clone)]
 struct TileRotation {
     id: usize,
-    flipped: bool,
-    rotation: Dir,
+    symmetry: Symmetry,
 }
 
 impl TileRotation {
+    //The only symmetry bringing those two sides of the tile up and left
     fn from_up_and_left(id: usize, up: usize, left: usize) -> Self {
-        let (flipped, rotation): (bool, Dir) = match (up, left) {
-            (0, 3) => (false, Dir::North),
-            (0, 1) => (true, Dir::North),
-            (1, 0) => (false, Dir::East),
-            (1, 2) => (true, Dir::East),
-            (2, 1) => (false, Dir::South),
-            (2, 3) => (true, Dir::South),
-            (3, 2) => (false, Dir::West),
-            (3, 0) => (true, Dir::West),
-            _ => {
-                println!("A bad position happened: {up} {left}");
-                (false, Dir::North)
-            } //Should not happen
-        };
-
-        Self {
-            id,
-            flipped,
-            rotation,
-        }
+        let symmetry: Symmetry = Symmetry::all()
+            .into_iter()
+            .find(|s| source_side(s, 0) == up && source_side(s, 3) == left)
+            .expect("The up and left sides are not adjacent");
+        Self { id, symmetry }
+    }
+}
+
+//The side of the tile that ends up on "side" once transformed
+fn source_side(symmetry: &Symmetry, side: usize) -> usize {
+    let last: usize = TILE_SIZE - 1;
+    //A pixel of that side that is not a corner
+    let (x, y) = [(1, 0), (last, 1), (1, last), (0, 1)][side];
+    match symmetry.source_pos(x, y, TILE_SIZE, TILE_SIZE) {
+        (_, 0) => 0,
+        (x, _) if x == last => 1,
+        (_, y) if y == last => 2,
+        _ => 3,
     }
 }
 
@@ -73,16 +72,11 @@ impl Tile {
         max(rev, norm)
     }
 
+    //The borders are removed, so the pixel is looked up one row and one column further
     fn pixel_at(&self, rot: &TileRotation, col: usize, row: usize) -> bool {
-        let (x, y) = (col + 1, row + 1);
-        let size = TILE_SIZE - 1;
-        let (x, y) = if rot.flipped { (size - x, y) } else { (x, y) };
-        let (x, y) = match rot.rotation {
-            Dir::North => (x, y),
-            Dir::East => (size - y, x),
-            Dir::South => (size - x, size - y),
-            Dir::West => (y, size - x),
-        };
+        let (x, y) = rot
+            .symmetry
+            .source_pos(col + 1, row + 1, TILE_SIZE, TILE_SIZE);
         self.pixels[y][x]
     }
 }
@@ -123,36 +117,11 @@ impl Monster {
     }
 
     fn all_orientations(&self) -> Vec<Vec<Vec<bool>>> {
-        let mut all_orient: Vec<Vec<Vec<bool>>> = Vec::new();
-        let mut current: Vec<Vec<bool>> = self.grid.clone();
-
-        for _ in 0..4 {
-            all_orient.push(current.clone());
-            all_orient.push(Self::flip(&current));
-            current = Self::rot(&current);
-        }
-        all_orient
-    }
-
-    fn flip(source: &[Vec<bool>]) -> Vec<Vec<bool>> {
-        source
+        Symmetry::all()
             .iter()
-            .map(|line| line.iter().rev().cloned().collect())
+            .map(|sym| sym.view(&self.grid).to_vec())
             .collect()
     }
-
-    fn rot(source: &[Vec<bool>]) -> Vec<Vec<bool>> {
-        let height: usize = source[0].len();
-        let width: usize = source.len();
-        let mut grid: Vec<Vec<bool>> = vec![vec![false; width]; height];
-
-        for (j, row) in source.iter().enumerate() {
-            for (i, &elem) in row.iter().enumerate() {
-                grid[height - i - 1][j] = elem
-            }
-        }
-        grid
-    }
 }
 
 impl FromStr for Monster {
@@ -361,13 +330,7 @@ impl Image {
 
     fn get_edge_with_id(&self, rot: &TileRotation, edge: usize) -> usize {
         let edges: &[usize] = self.all_edges.get(&rot.id).unwrap();
-        let final_edge: usize = if rot.flipped && edge % 2 == 1 {
-            4 - edge
-        } else {
-            edge
-        };
-        let idx: usize = (final_edge + rot.rotation as usize) % 4;
-        edges[idx]
+        edges[source_side(&rot.symmetry, edge)]
     }
 
     fn bottom_edge(&self, x: usize, y: usize) -> Option<(usize, usize)> {
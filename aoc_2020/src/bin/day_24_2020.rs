@@ -1,66 +1,28 @@
 use std::collections::HashSet;
 use std::str::FromStr;
-use util::coord::PosI;
+use util::automaton::sparse_life_step;
+use util::hex::{Hex, Orientation};
 
 const DAYS: usize = 100;
 
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
 struct Tile {
-    coords: PosI,
-}
-
-impl Tile {
-    fn neighbours(&self) -> Vec<Tile> {
-        let moves: Vec<PosI> = vec![
-            PosI(0, 1),
-            PosI(1, 1),
-            PosI(-1, 0),
-            PosI(1, 0),
-            PosI(-1, -1),
-            PosI(0, -1),
-        ];
-        moves
-            .into_iter()
-            .map(|PosI(x, y)| {
-                let coords: PosI = PosI(x + self.coords.0, y + self.coords.1);
-                Tile { coords }
-            })
-            .collect()
-    }
+    coords: Hex,
 }
 
 impl FromStr for Tile {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut x: isize = 0;
-        let mut y: isize = 0;
-        let mut prev: char = 'a';
-        for c in s.chars() {
-            match (c, prev) {
-                ('w', 'n') => y += 1,
-                ('e', 'n') => {
-                    x += 1;
-                    y += 1
-                }
-                ('w', 's') => {
-                    x -= 1;
-                    y -= 1;
-                }
-                ('e', 's') => y -= 1,
-                ('w', _) => x -= 1,
-                ('e', _) => x += 1,
-                _ => (),
-            }
-            prev = c;
-        }
-
-        Ok(Tile { coords: PosI(x, y) })
+        let path = Orientation::Pointy.parse_path(s).map_err(|_| ())?;
+        Ok(Tile {
+            coords: Hex::origin().walk(Orientation::Pointy, &path),
+        })
     }
 }
 
 struct Floor {
-    flipped: HashSet<Tile>,
+    flipped: HashSet<Hex>,
 }
 
 impl Floor {
@@ -75,44 +37,10 @@ impl Floor {
     }
 
     fn day(&mut self) {
-        //The whites are all the neighbours of the blacks minus the black themselves
-        let mut whites: HashSet<Tile> = self
-            .flipped
-            .iter()
-            .flat_map(|black| black.neighbours())
-            .collect();
-        whites = whites.difference(&self.flipped).cloned().collect();
-
-        //Keep black tiles that are not flipped
-        let stays_black: HashSet<Tile> = self
-            .flipped
-            .iter()
-            .filter(|tile| {
-                let nb_white_ngb: usize = tile
-                    .neighbours()
-                    .iter()
-                    .filter(|tile| whites.contains(tile))
-                    .count();
-                nb_white_ngb == 4 || nb_white_ngb == 5
-            })
-            .cloned()
-            .collect();
-
-        //Add flipping white tiles
-        let new_black: HashSet<Tile> = whites
-            .iter()
-            .filter(|tile| {
-                let nb_black_ngb: usize = tile
-                    .neighbours()
-                    .iter()
-                    .filter(|tile| self.flipped.contains(tile))
-                    .count();
-                nb_black_ngb == 2
-            })
-            .cloned()
-            .collect();
-
-        self.flipped = stays_black.union(&new_black).cloned().collect();
+        //A black tile with 0 or more than 2 black neighbours turns white, a white one with 2 turns black
+        self.flipped = sparse_life_step(&self.flipped, Hex::neighbours, |black, n| {
+            n == 2 || (black && n == 1)
+        });
     }
 }
 
@@ -120,12 +48,12 @@ impl FromStr for Floor {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut flipped: HashSet<Tile> = HashSet::new();
+        let mut flipped: HashSet<Hex> = HashSet::new();
         s.lines()
             .map(|l| l.parse().unwrap())
             .for_each(|tile: Tile| {
-                if !flipped.insert(tile) {
-                    flipped.remove(&tile);
+                if !flipped.insert(tile.coords) {
+                    flipped.remove(&tile.coords);
                 }
             });
 
@@ -178,12 +106,12 @@ wseweeenwnesenwwwswnew";
     #[test]
     fn test_tile_1() {
         let tile: Tile = "esew".parse().unwrap();
-        assert_eq!(tile.coords, PosI(0, -1));
+        assert_eq!(tile.coords, Hex::new(0, 1));
     }
     #[test]
     fn test_tile_2() {
         let tile: Tile = "nwwswee".parse().unwrap();
-        assert_eq!(tile.coords, PosI(0, 0));
+        assert_eq!(tile.coords, Hex::origin());
     }
 
     #[test]
@@ -198,4 +126,35 @@ wseweeenwnesenwwwswnew";
         floor.days(DAYS);
         assert_eq!(floor.nb_black(), 2208);
     }
+
+    #[test]
+    fn dense_board_agrees() {
+        use util::automaton::{hex, Board};
+
+        let mut floor: Floor = INPUT.parse().unwrap();
+        //The example tiles are within 3 of the origin and spread by at most one tile a day
+        let (min_q, min_r): (isize, isize) = (-13, -13);
+        let (width, height): (usize, usize) = (27, 27);
+        let mut cells: Vec<bool> = vec![false; width * height];
+        for h in floor.flipped.iter() {
+            cells[(h.r - min_r) as usize * width + (h.q - min_q) as usize] = true;
+        }
+        let mut board: Board<bool> = Board::new(cells, hex(width, height));
+        for _ in 0..10 {
+            board.step(|&black, ngbs| {
+                let n: usize = ngbs.iter().filter(|&&b| b).count();
+                n == 2 || (black && n == 1)
+            });
+        }
+        floor.days(10);
+        let dense: HashSet<Hex> = board
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &black)| black)
+            .map(|(i, _)| Hex::new(min_q + (i % width) as isize, min_r + (i / width) as isize))
+            .collect();
+        assert_eq!(dense.len(), 37);
+        assert_eq!(dense, floor.flipped);
+    }
 }
@@ -1,57 +1,47 @@
 use std::str::FromStr;
-use util::coord::Pos;
+use util::automaton::moore;
 
+//Flashes cascade within a step, so this is not a rule on the previous states as in a Board,
+//only the neighbourhood is shared
 #[derive(Debug, Clone)]
 struct DumboOctopus {
-    octopuses: Vec<Vec<usize>>,
+    energy: Vec<usize>,
+    neighbours: Vec<Vec<usize>>,
 }
 
 impl DumboOctopus {
-    fn reset_flashed(&mut self) -> usize {
-        let mut nb_flashed: usize = 0;
-
-        for x in 0..10 {
-            for y in 0..10 {
-                if self.octopuses[y][x] == 10 {
-                    self.octopuses[y][x] = 0;
-                    nb_flashed += 1;
-                }
+    fn step(&mut self) -> usize {
+        let mut flashing: Vec<usize> = Vec::new();
+        for (i, e) in self.energy.iter_mut().enumerate() {
+            *e += 1;
+            if *e == 10 {
+                flashing.push(i);
             }
         }
-        nb_flashed
-    }
-
-    fn step(&mut self) -> usize {
-        for x in 0..10 {
-            for y in 0..10 {
-                //We only do something if this octopus was not already flashed
-                if self.octopuses[y][x] < 10 {
-                    self.octopuses[y][x] += 1;
-                    //Propagate to neighbours
-                    if self.octopuses[y][x] == 10 {
-                        let mut ngbs: Vec<Pos> = Pos(x, y).neighbours_diag_safe(10, 10);
-                        while let Some(Pos(n_x, n_y)) = ngbs.pop() {
-                            //We also only do something if this octopus was not already flashed
-                            if self.octopuses[n_y][n_x] < 10 {
-                                self.octopuses[n_y][n_x] += 1;
-                                if self.octopuses[n_y][n_x] == 10 {
-                                    ngbs.extend(Pos(n_x, n_y).neighbours_diag_safe(10, 10))
-                                }
-                            }
-                        }
-                    }
+        //Each octopus reaches 10 only once, so it only flashes once
+        while let Some(i) = flashing.pop() {
+            for &n in self.neighbours[i].iter() {
+                self.energy[n] += 1;
+                if self.energy[n] == 10 {
+                    flashing.push(n);
                 }
             }
         }
 
-        self.reset_flashed()
+        let mut nb_flashed: usize = 0;
+        for e in self.energy.iter_mut().filter(|e| **e >= 10) {
+            *e = 0;
+            nb_flashed += 1;
+        }
+        nb_flashed
     }
+
     fn flashing(&mut self) -> (usize, usize) {
         let mut hundred_steps: usize = 0;
         let mut steps: usize = 0;
         let mut nb_flashes: usize = 0;
 
-        while nb_flashes < 100 {
+        while nb_flashes < self.energy.len() {
             nb_flashes = self.step();
             steps += 1;
             if steps <= 100 {
@@ -75,7 +65,11 @@ impl FromStr for DumboOctopus {
                     .collect()
             })
             .collect();
-        Ok(DumboOctopus { octopuses })
+        let neighbours: Vec<Vec<usize>> = moore(octopuses[0].len(), octopuses.len());
+        Ok(DumboOctopus {
+            energy: octopuses.concat(),
+            neighbours,
+        })
     }
 }
 
@@ -1,83 +1,31 @@
-use fxhash::{FxHashMap, FxHashSet};
-use itertools::Itertools;
+use fxhash::FxHashSet;
 use nom::character::complete::char;
 use nom::multi::separated_list1;
 use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
 use util::basic_parser::parse_isize;
-use util::coord::Pos3I;
+use util::rotation::align;
 use util::split_blocks;
+use util::vector::Vector;
+
+type Point = Vector<isize, 3>;
 
 #[derive(Debug, Clone)]
 struct Scanner {
-    beacons: Vec<Pos3I>,
-}
-
-impl Scanner {
-    fn all_rotations(&self) -> Vec<Vec<Pos3I>> {
-        fn rot_x(beacons: &[Pos3I]) -> Vec<Pos3I> {
-            beacons
-                .iter()
-                .map(|&Pos3I(x, y, z)| Pos3I(x, -z, y))
-                .collect()
-        }
-
-        fn rot_y(beacons: &[Pos3I]) -> Vec<Pos3I> {
-            beacons
-                .iter()
-                .map(|&Pos3I(x, y, z)| Pos3I(z, y, -x))
-                .collect()
-        }
-
-        fn rot_z(beacons: &[Pos3I]) -> Vec<Pos3I> {
-            beacons
-                .iter()
-                .map(|&Pos3I(x, y, z)| Pos3I(y, -x, z))
-                .collect()
-        }
-
-        let i = self.beacons.clone();
-        let x = rot_x(&i);
-        let y = rot_y(&i);
-        let z = rot_z(&i);
-        let xx = rot_x(&x);
-        let xy = rot_y(&x);
-        let xz = rot_z(&x);
-        let yx = rot_x(&y);
-        let yy = rot_y(&y);
-        let zy = rot_y(&z);
-        let zz = rot_z(&z);
-        let xxx = rot_x(&xx);
-        let xxy = rot_y(&xx);
-        let xxz = rot_z(&xx);
-        let xyx = rot_x(&xy);
-        let xyy = rot_y(&xy);
-        let xzz = rot_z(&xz);
-        let yxx = rot_x(&yx);
-        let yyy = rot_y(&yy);
-        let zzz = rot_z(&zz);
-        let xxxy = rot_y(&xxx);
-        let xxyx = rot_x(&xxy);
-        let xyxx = rot_x(&xyx);
-        let xyyy = rot_y(&xyy);
-        vec![
-            i, x, y, z, xx, xy, xz, yx, yy, zy, zz, xxx, xxy, xxz, xyx, xyy, xzz, yxx, yyy, zzz,
-            xxxy, xxyx, xyxx, xyyy,
-        ]
-    }
+    beacons: Vec<Point>,
 }
 
 impl FromStr for Scanner {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_pos3i(s: &str) -> IResult<&str, Pos3I> {
+        fn parse_pos3i(s: &str) -> IResult<&str, Point> {
             let (s, v) = separated_list1(char(','), parse_isize).parse(s)?;
 
-            Ok((s, Pos3I(v[0], v[1], v[2])))
+            Ok((s, Vector([v[0], v[1], v[2]])))
         }
-        let beacons: Vec<Pos3I> = s
+        let beacons: Vec<Point> = s
             .lines()
             .skip(1)
             .map(|l| parse_pos3i(l).unwrap().1)
@@ -93,57 +41,32 @@ struct ScannerFleet {
 
 impl ScannerFleet {
     fn solve(&self) -> (usize, usize) {
-        fn compare_beacons(
-            source_beacons: &[Pos3I],
-            target_beacons: &[Pos3I],
-        ) -> Option<(Pos3I, Vec<Pos3I>)> {
-            //Compute all the difference between any 2 beacon
-            //Search for a group of 12 or more differences that are equals. This means it is a good orientation
-            if let Some((target_pos, _)) = source_beacons
-                .iter()
-                .flat_map(|src| target_beacons.iter().map(|tgt| src.sub(*tgt)))
-                .counts()
-                .into_iter()
-                .find(|&(_, c)| c >= 12)
-            {
-                Some((
-                    target_pos,
-                    target_beacons.iter().map(|b| b.add(target_pos)).collect(),
-                ))
-            } else {
-                None
-            }
-        }
-
         let mut found_scanner: Vec<bool> = vec![false; self.scanners.len()];
-        let mut final_positions: Vec<(Pos3I, Vec<Pos3I>)> =
-            vec![(Pos3I(0, 0, 0), Vec::new()); self.scanners.len()];
-        final_positions[0] = (Pos3I(0, 0, 0), self.scanners[0].beacons.clone());
+        let mut final_positions: Vec<(Point, Vec<Point>)> =
+            vec![(Vector([0, 0, 0]), Vec::new()); self.scanners.len()];
+        final_positions[0] = (Vector([0, 0, 0]), self.scanners[0].beacons.clone());
         let mut to_compute: Vec<usize> = vec![0];
 
-        let mut rotations_map: FxHashMap<usize, Vec<Vec<Pos3I>>> = FxHashMap::default();
-
         //End when all scanners have been located
         while found_scanner.iter().any(|&found| !found) {
             let source: usize = to_compute.pop().unwrap();
-            let source_beacons: &[Pos3I] = &final_positions[source].1;
+            let source_beacons: &[Point] = &final_positions[source].1;
 
-            let mut found_pairs: Vec<(usize, (Pos3I, Vec<Pos3I>))> = Vec::new();
+            let mut found_pairs: Vec<(usize, (Point, Vec<Point>))> = Vec::new();
 
             for (i, found) in found_scanner.iter_mut().enumerate() {
                 //Only compare to scanner that are not yet located
                 if !*found {
-                    let rotations = rotations_map
-                        .entry(i)
-                        .or_insert(self.scanners[i].all_rotations());
-                    //Compare the source to all the rotations of the target
-                    if let Some(pair) = rotations
-                        .iter()
-                        .find_map(|target_group| compare_beacons(source_beacons, target_group))
-                    {
+                    //We need 12 common beacons to be sure of the alignment
+                    if let Some(alignment) = align(source_beacons, &self.scanners[i].beacons, 12) {
+                        let beacons: Vec<Point> = self.scanners[i]
+                            .beacons
+                            .iter()
+                            .map(|&b| alignment.apply(b))
+                            .collect();
                         to_compute.push(i);
                         *found = true;
-                        found_pairs.push((i, pair));
+                        found_pairs.push((i, (alignment.translation, beacons)));
                     }
                 }
             }
@@ -153,7 +76,7 @@ impl ScannerFleet {
             }
         }
 
-        let beacons_set: FxHashSet<&Pos3I> = final_positions
+        let beacons_set: FxHashSet<&Point> = final_positions
             .iter()
             .flat_map(|(_, beacons)| beacons)
             .collect();
@@ -163,7 +86,7 @@ impl ScannerFleet {
             .flat_map(|(source, _)| {
                 final_positions
                     .iter()
-                    .map(|(target, _)| source.distance(*target))
+                    .map(|(target, _)| source.manhattan(target) as usize)
             })
             .max()
             .unwrap();
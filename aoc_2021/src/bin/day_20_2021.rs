@@ -1,74 +1,36 @@
-use fxhash::FxHashSet;
-use itertools::Itertools;
-use itertools::MinMaxResult::MinMax;
 use std::str::FromStr;
-use util::coord::PosI;
+use util::automaton::Plane;
 use util::split_blocks;
 
 #[derive(Debug, Clone)]
 struct TrenchImage {
     enhancement_hash: Vec<bool>,
-    pixels: FxHashSet<PosI>,
+    pixels: Plane<bool>,
 }
 
 impl TrenchImage {
+    //The 9 pixels around, in reading order, are the bits of the index in the enhancement
+    //If the enhancement lights a pixel with no lit neighbour, the infinite background flips
     fn enhance(&self) -> (usize, usize) {
-        let mut pixels: FxHashSet<PosI> = self.pixels.clone();
+        let mut pixels: Plane<bool> = self.pixels.clone();
+        let rule = |&pixel: &bool, ngbs: &[bool]| {
+            let index: usize = ngbs[..4]
+                .iter()
+                .chain([&pixel])
+                .chain(&ngbs[4..])
+                .fold(0, |acc, &b| acc * 2 + b as usize);
+            self.enhancement_hash[index]
+        };
 
         let mut two: usize = 0;
-
         for i in 0..50 {
-            let mut new_pixels: FxHashSet<PosI> = FxHashSet::default();
-            if let MinMax(min_x, max_x) = pixels.iter().map(|PosI(x, _)| *x).minmax() {
-                if let MinMax(min_y, max_y) = pixels.iter().map(|PosI(_, y)| *y).minmax() {
-                    for y in min_y - 1..=max_y + 1 {
-                        for x in min_x - 1..=max_x + 1 {
-                            let nine: Vec<bool> = (-1..=1)
-                                .cartesian_product(-1..=1)
-                                .map(|(d_y, d_x)| {
-                                    let new_x = x + d_x;
-                                    let new_y = y + d_y;
-                                    if new_x >= min_x
-                                        && new_x <= max_x
-                                        && new_y >= min_y
-                                        && new_y <= max_y
-                                    {
-                                        pixels.contains(&PosI(x + d_x, y + d_y))
-                                    } else if self.enhancement_hash[0] {
-                                        //The image is scintillating
-                                        //Every two steps, all pixels illuminate
-                                        i % 2 == 1
-                                    } else {
-                                        false
-                                    }
-                                })
-                                .collect();
-                            let index: usize =
-                                nine.into_iter().fold(
-                                    0,
-                                    |acc, b| {
-                                        if b {
-                                            acc * 2 + 1
-                                        } else {
-                                            acc * 2
-                                        }
-                                    },
-                                );
-                            if self.enhancement_hash[index] {
-                                new_pixels.insert(PosI(x, y));
-                            }
-                        }
-                    }
-                }
-            }
-            pixels = new_pixels;
-
+            pixels.step(rule);
             if i == 1 {
-                two = pixels.len();
+                two = pixels.count(&true).unwrap();
             }
         }
 
-        (two, pixels.len())
+        (two, pixels.count(&true).unwrap())
     }
 }
 
@@ -84,22 +46,13 @@ impl FromStr for TrenchImage {
             .chars()
             .map(|c| c == '#')
             .collect();
-        let pixels: FxHashSet<PosI> = blocks[1]
+        let rows: Vec<Vec<bool>> = blocks[1]
             .lines()
-            .enumerate()
-            .flat_map(|(y, l)| {
-                l.chars().enumerate().filter_map(move |(x, c)| {
-                    if c == '#' {
-                        Some(PosI(x as isize, y as isize))
-                    } else {
-                        None
-                    }
-                })
-            })
+            .map(|l| l.chars().map(|c| c == '#').collect())
             .collect();
         Ok(TrenchImage {
             enhancement_hash,
-            pixels,
+            pixels: Plane::new(rows, false),
         })
     }
 }
@@ -4,64 +4,27 @@ use nom::combinator::map;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
 use nom::Parser;
-use std::cmp::{max, min};
 use std::str::FromStr;
 use util::basic_parser::parse_isize;
+use util::cuboid::{Cuboid, CuboidSet};
+use util::interval::Interval;
 
 #[derive(Debug, Copy, Clone)]
 struct RebootStep {
     on: bool,
-    x_range: (isize, isize),
-    y_range: (isize, isize),
-    z_range: (isize, isize),
-}
-
-impl RebootStep {
-    fn volume(&self) -> isize {
-        let vol: isize = (self.x_range.1 - self.x_range.0 + 1)
-            * (self.y_range.1 - self.y_range.0 + 1)
-            * (self.z_range.1 - self.z_range.0 + 1);
-        if self.on {
-            vol
-        } else {
-            -vol
-        }
-    }
-
-    fn combine(&self, r: &RebootStep) -> Vec<RebootStep> {
-        //If any of the 3 dimension don't overlap, the volume won't overlap at all
-        if !RebootStep::overlap(self.x_range, r.x_range)
-            || !RebootStep::overlap(self.y_range, r.y_range)
-            || !RebootStep::overlap(self.z_range, r.z_range)
-        {
-            vec![*self]
-        } else {
-            //Add an "inverse" volume to remove the intersection of those volumes
-            let intersect = RebootStep {
-                on: !self.on,
-                x_range: RebootStep::intersect(self.x_range, r.x_range),
-                y_range: RebootStep::intersect(self.y_range, r.y_range),
-                z_range: RebootStep::intersect(self.z_range, r.z_range),
-            };
-            vec![*self, intersect]
-        }
-    }
-
-    fn overlap(a: (isize, isize), b: (isize, isize)) -> bool {
-        (b.0..=b.1).contains(&a.0) || (a.0..=a.1).contains(&b.0)
-    }
-
-    fn intersect(a: (isize, isize), b: (isize, isize)) -> (isize, isize) {
-        (max(a.0, b.0), min(a.1, b.1))
-    }
+    cuboid: Cuboid<isize, 3>,
 }
 
 impl FromStr for RebootStep {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_range(s: &str) -> IResult<&str, (isize, isize)> {
-            separated_pair(parse_isize, tag(".."), parse_isize).parse(s)
+        fn parse_range(s: &str) -> IResult<&str, Interval<isize>> {
+            map(
+                separated_pair(parse_isize, tag(".."), parse_isize),
+                |(start, end)| Interval::closed(start, end),
+            )
+            .parse(s)
         }
         fn parse_step(s: &str) -> IResult<&str, RebootStep> {
             let (s, on) = map(alpha1, |w| w == "on").parse(s)?;
@@ -73,9 +36,7 @@ impl FromStr for RebootStep {
                 s,
                 RebootStep {
                     on,
-                    x_range,
-                    y_range,
-                    z_range,
+                    cuboid: Cuboid([x_range, y_range, z_range]),
                 },
             ))
         }
@@ -94,30 +55,24 @@ impl ReactorReboot {
         let init_steps: Vec<RebootStep> = self
             .steps
             .iter()
-            .take_while(|step| step.x_range.0.abs() <= 50)
+            .take_while(|step| step.cuboid.0[0].start.abs() <= 50)
             .cloned()
             .collect();
-        Self::fold_operations(&init_steps)
-            .iter()
-            .map(|step| step.volume())
-            .sum()
+        Self::fold_operations(&init_steps).volume()
     }
 
     fn reboot(&self) -> isize {
-        Self::fold_operations(&self.steps)
-            .iter()
-            .map(|step| step.volume())
-            .sum()
+        Self::fold_operations(&self.steps).volume()
     }
 
-    fn fold_operations(v: &[RebootStep]) -> Vec<RebootStep> {
-        v.iter().fold(Vec::new(), |acc, step| {
-            let mut out: Vec<RebootStep> = acc.iter().flat_map(|e| e.combine(step)).collect();
-            //We don't add an "off" Action at the end because all intersection have already been calculated
+    fn fold_operations(v: &[RebootStep]) -> CuboidSet<isize, 3> {
+        v.iter().fold(CuboidSet::new(), |mut set, step| {
             if step.on {
-                out.push(*step)
+                set.insert(step.cuboid);
+            } else {
+                set.remove(&step.cuboid);
             }
-            out
+            set
         })
     }
 }
@@ -13,13 +13,12 @@ impl FromStr for Move {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let words: Vec<&str> = s.split_whitespace().collect();
-        let dir: Dir = match words[0] {
-            "L" => Dir::West,
-            "R" => Dir::East,
-            "U" => Dir::North,
-            "D" => Dir::South,
-            _ => return Err(()), //Should not happen
-        };
+        let dir: Dir = words[0]
+            .chars()
+            .next()
+            .map(Dir::from_char)
+            .ok_or(())?
+            .map_err(|_| ())?;
         let dist: usize = words[1].parse().unwrap();
         Ok(Move { dir, dist })
     }
@@ -36,13 +35,8 @@ impl Rope {
     }
 
     fn move_one(&mut self, dir: &Dir) {
-        let PosI(x, y): PosI = self.knots[0];
-        match dir {
-            Dir::West => self.knots[0] = PosI(x - 1, y),
-            Dir::East => self.knots[0] = PosI(x + 1, y),
-            Dir::North => self.knots[0] = PosI(x, y + 1),
-            Dir::South => self.knots[0] = PosI(x, y - 1),
-        }
+        //Up is y - 1, the tail follows the same way so the visited positions only get mirrored
+        self.knots[0] = dir.step_n(self.knots[0], 1);
         for i in 1..self.knots.len() {
             self.follow_tail(i);
         }
@@ -1,10 +1,12 @@
 use std::cmp::{max, min};
 use std::str::FromStr;
+use util::bitgrid::{BitGrid, BitRow};
 use util::coord::Pos;
 
+const SAND_ENTRY: usize = 500;
 const MARGIN: usize = 10;
 const SECOND_PART_MARGIN: usize = 200;
-const FLOOR_MARGIN: usize = 3;
+const FLOOR_DEPTH: usize = 2;
 
 #[derive(Debug)]
 struct RockPath {
@@ -54,75 +56,80 @@ impl FromStr for RockPath {
     }
 }
 
+//Rock and sand cells, x being shifted by min_x
 struct RockStructure {
-    grid: Vec<Vec<Option<bool>>>,
+    rocks: BitGrid,
+    sand: BitGrid,
     sand_entry: usize,
-    min_x: usize,
-    max_y: usize,
 }
 
 impl RockStructure {
-    fn pour_sand(&mut self) {
-        let mut x = self.sand_entry - self.min_x;
-        let mut y = 0;
-        while (y + 1) < self.max_y {
-            match (
-                self.grid[y + 1][x],     //Mid
-                self.grid[y + 1][x - 1], //Left
-                self.grid[y + 1][x + 1], //Right
-            ) {
-                (None, _, _) => y += 1,
-                (Some(_), None, _) => {
-                    y += 1;
-                    x -= 1;
-                }
-                (Some(_), Some(_), None) => {
-                    y += 1;
-                    x += 1;
-                }
-                _ => {
-                    self.grid[y][x] = Some(true);
-                    break;
-                }
+    fn new(rock_paths: &[RockPath], min_x: usize, max_x: usize, max_y: usize) -> Self {
+        let mut rocks: BitGrid = BitGrid::new(max_x - min_x, max_y);
+        for line in rock_paths.iter().flat_map(|rp| rp.points.windows(2)) {
+            let from_x: usize = min(line[0].0, line[1].0) - min_x;
+            let to_x: usize = max(line[0].0, line[1].0) - min_x;
+            for y in min(line[0].1, line[1].1)..=max(line[0].1, line[1].1) {
+                rocks.row_mut(y).set_range(from_x, to_x + 1);
             }
         }
+        RockStructure {
+            sand: BitGrid::new(rocks.width(), rocks.height()),
+            rocks,
+            sand_entry: SAND_ENTRY - min_x,
+        }
     }
 
-    fn nb_sand(&self) -> usize {
-        self.grid
-            .iter()
-            .map(|row| row.iter().filter(|p| matches!(p, Some(true))).count())
-            .sum()
+    fn is_free(&self, pos: Pos) -> bool {
+        !self.rocks.get(pos) && !self.sand.get(pos)
     }
 
-    fn is_sand_stuck(&self) -> bool {
-        self.grid[0][self.sand_entry - self.min_x].is_some()
+    //False if the sand falls into the abyss
+    fn pour_sand(&mut self) -> bool {
+        let mut pos: Pos = Pos(self.sand_entry, 0);
+        while pos.1 + 1 < self.rocks.height() {
+            let Pos(x, y) = pos;
+            //Down, then down left, then down right
+            match [Pos(x, y + 1), Pos(x - 1, y + 1), Pos(x + 1, y + 1)]
+                .into_iter()
+                .find(|&p| self.is_free(p))
+            {
+                Some(next) => pos = next,
+                None => {
+                    self.sand.set(pos, true);
+                    return true;
+                }
+            }
+        }
+        false
     }
 
-    fn fill_rocks(&mut self, rocks: &[RockPath]) {
-        rocks.iter().for_each(|rock_path| {
-            rock_path.points.windows(2).for_each(|line| {
-                let min_x = min(line[0].0, line[1].0) - self.min_x;
-                let max_x = max(line[0].0, line[1].0) - self.min_x;
-                let min_y = min(line[0].1, line[1].1);
-                let max_y = max(line[0].1, line[1].1);
-                for i in min_x..=max_x {
-                    for j in min_y..=max_y {
-                        self.grid[j][i] = Some(false);
-                    }
-                }
-            })
-        })
+    fn nb_sand(&self) -> usize {
+        self.sand.count_ones()
+    }
+
+    //With the floor right below the last row, the sand ends up in every cell it can reach:
+    //a cell is reached from the three cells above it, unless it is a rock
+    fn nb_sand_above_floor(&self) -> usize {
+        let mut reached: BitRow = BitRow::new(self.rocks.width());
+        reached.set(self.sand_entry, true);
+        let mut nb_sand: usize = reached.count_ones();
+        for y in 1..self.rocks.height() {
+            let spread: BitRow = &reached | &reached.shifted_left(1) | &reached.shifted_right(1);
+            reached = spread & &!self.rocks.row(y);
+            nb_sand += reached.count_ones();
+        }
+        nb_sand
     }
 
     #[allow(dead_code)]
     fn print(&self) {
-        for row in self.grid.iter() {
-            for p in row.iter() {
-                let c: char = match p {
-                    Some(true) => 'o',
-                    Some(false) => '#',
-                    None => '.',
+        for y in 0..self.rocks.height() {
+            for x in 0..self.rocks.width() {
+                let c: char = match (self.rocks.get(Pos(x, y)), self.sand.get(Pos(x, y))) {
+                    (true, _) => '#',
+                    (_, true) => 'o',
+                    _ => '.',
                 };
                 print!("{c}");
             }
@@ -136,51 +143,28 @@ fn main() {
     let s = util::file_as_string("aoc_2022/input/day_14.txt").expect("Cannot open input file");
 
     let rock_paths: Vec<RockPath> = s.lines().map(|l| l.parse().unwrap()).collect();
-    let sand_entry: usize = 500;
-    let min_x = rock_paths.iter().map(|rp| rp.min_x()).min().unwrap() - MARGIN;
-    let max_x = rock_paths.iter().map(|rp| rp.max_x()).max().unwrap() + MARGIN;
-    let max_y = rock_paths.iter().map(|rp| rp.max_y()).max().unwrap() + MARGIN;
-
-    let mut rocks: RockStructure = RockStructure {
-        grid: vec![vec![None; max_x - min_x]; max_y],
-        sand_entry,
-        min_x,
-        max_y,
-    };
-    rocks.fill_rocks(&rock_paths);
-    let mut nb_sand = 0;
-    let mut previous_nb_sand = usize::MAX;
-    while previous_nb_sand != nb_sand {
-        previous_nb_sand = nb_sand;
-        rocks.pour_sand();
-        nb_sand = rocks.nb_sand();
-    }
+    let min_x: usize = rock_paths.iter().map(|rp| rp.min_x()).min().unwrap();
+    let max_x: usize = rock_paths.iter().map(|rp| rp.max_x()).max().unwrap();
+    let max_y: usize = rock_paths.iter().map(|rp| rp.max_y()).max().unwrap();
+
+    let mut rocks: RockStructure =
+        RockStructure::new(&rock_paths, min_x - MARGIN, max_x + MARGIN, max_y + MARGIN);
+    while rocks.pour_sand() {}
     println!(
-        "Part1: The rock structure holds {nb_sand} unit of sand before it falls into the abyss"
+        "Part1: The rock structure holds {} unit of sand before it falls into the abyss",
+        rocks.nb_sand()
     );
 
-    //Part 2
-    let min_x = rock_paths.iter().map(|rp| rp.min_x()).min().unwrap() - SECOND_PART_MARGIN;
-    let max_x = rock_paths.iter().map(|rp| rp.max_x()).max().unwrap() + SECOND_PART_MARGIN;
-    let max_y = rock_paths.iter().map(|rp| rp.max_y()).max().unwrap() + FLOOR_MARGIN;
-
-    let mut rocks: RockStructure = RockStructure {
-        grid: vec![vec![None; max_x - min_x]; max_y],
-        sand_entry,
-        min_x,
-        max_y,
-    };
-    rocks.fill_rocks(&rock_paths);
-    let floor: RockPath = RockPath {
-        points: vec![Pos(min_x, max_y - 1), Pos(max_x - 1, max_y - 1)],
-    };
-    rocks.fill_rocks(&[floor]);
-    while !rocks.is_sand_stuck() {
-        rocks.pour_sand();
-    }
+    //Part 2, the floor is 2 rows below the lowest rock
+    let rocks: RockStructure = RockStructure::new(
+        &rock_paths,
+        min_x - SECOND_PART_MARGIN,
+        max_x + SECOND_PART_MARGIN,
+        max_y + FLOOR_DEPTH,
+    );
     println!(
         "Part2: The rock structure holds {} unit of sand before it blocks the entry",
-        rocks.nb_sand()
+        rocks.nb_sand_above_floor()
     );
     println!("Computing time: {:?}", now.elapsed());
 }
@@ -1,7 +1,7 @@
-use std::cmp::max;
 use std::collections::HashSet;
 use std::str::FromStr;
 use util::coord::PosI;
+use util::interval::{Interval, IntervalSet};
 
 const MIN: isize = 0;
 const MAX: isize = 4_000_000;
@@ -23,15 +23,10 @@ impl Sensor {
         }
     }
 
-    fn covered_range(&self, line: isize) -> Option<PosI> {
+    fn covered_range(&self, line: isize) -> Interval<isize> {
         let x: isize = self.pos.0;
-        let dist_to_line: isize = self.pos.1.abs_diff(line) as isize;
-        if self.max_range >= dist_to_line {
-            let side_range = self.max_range - dist_to_line;
-            Some(PosI(x - side_range, x + side_range + 1))
-        } else {
-            None
-        }
+        let side_range: isize = self.max_range - self.pos.1.abs_diff(line) as isize;
+        Interval::closed(x - side_range, x + side_range)
     }
 
     fn beacon_y(&self) -> isize {
@@ -65,50 +60,17 @@ struct SensorSystem {
 
 impl SensorSystem {
     fn distress_beacon(&self, line: isize, min_x: isize, max_x: isize) -> Option<isize> {
-        let ranges = self.sorted_ranges(line);
-
-        let mut acc_end: isize = isize::MIN;
-        for PosI(r_start, r_end) in ranges {
-            if r_start == (acc_end + 1) && (min_x..=max_x).contains(&acc_end) {
-                return Some(acc_end);
-            }
-            if r_start > acc_end {
-                acc_end = r_end;
-            } else {
-                acc_end = max(acc_end, r_end);
-            }
-        }
-        None
+        self.covered(line)
+            .complement(Interval::closed(min_x, max_x))
+            .min()
     }
 
     fn forbidden_pos(&self, line: isize) -> isize {
-        let ranges = self.sorted_ranges(line);
-
-        let mut cnt: isize = 0;
-        let PosI(mut acc_start, mut acc_end): PosI = PosI(isize::MIN, isize::MIN);
-        for PosI(r_start, r_end) in ranges {
-            if r_start > acc_end {
-                cnt += acc_end - acc_start;
-                (acc_start, acc_end) = (r_start, r_end);
-            } else {
-                acc_end = max(acc_end, r_end);
-            }
-        }
-        cnt += acc_end - acc_start;
-        cnt -= self.nb_beacon_on_line(line) as isize;
-        cnt
+        self.covered(line).len() - self.nb_beacon_on_line(line) as isize
     }
 
-    fn sorted_ranges(&self, line: isize) -> Vec<PosI> {
-        let mut ranges: Vec<PosI> = self
-            .sensors
-            .iter()
-            .flat_map(|s| s.covered_range(line))
-            .collect();
-
-        //Sort the ranges by starting value
-        ranges.sort_unstable_by_key(|pair| (pair.0, pair.1));
-        ranges
+    fn covered(&self, line: isize) -> IntervalSet<isize> {
+        self.sensors.iter().map(|s| s.covered_range(line)).collect()
     }
 
     fn nb_beacon_on_line(&self, line: isize) -> usize {
@@ -1,7 +1,8 @@
 use itertools::Itertools;
 use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::str::FromStr;
+use util::contraction::Contraction;
 
 const START: &str = "AA";
 
@@ -40,31 +41,24 @@ struct Tunnels {
 
 impl Tunnels {
     fn new(valves: HashMap<String, Valve>) -> Self {
-        let mut names: Vec<String> = valves
-            .iter()
-            .filter_map(|(name, valve)| {
-                if valve.flow_rate > 0 {
-                    Some(name.to_string())
-                } else {
-                    None
-                }
-            })
-            .collect();
-        names.insert(0, START.to_string());
-        let flow_rates: Vec<u16> = names
-            .iter()
-            .map(|name| valves.get(name).unwrap().flow_rate)
+        //Only the start and the valves worth opening are kept, with the distances between them
+        let tunnels: Contraction<&str> = Contraction::all_pairs(
+            [START],
+            |&v: &&str| valves[v].neighbours.iter().map(|n| n.as_str()),
+            |&v| valves[v].flow_rate > 0,
+        );
+        let names: Vec<String> = (0..tunnels.len())
+            .map(|i| tunnels.nodes.name(i).to_string())
             .collect();
+        let flow_rates: Vec<u16> = names.iter().map(|name| valves[name].flow_rate).collect();
 
-        let mut dist_matrix: Vec<Vec<u8>> = vec![vec![0; names.len()]; names.len()];
-
-        for i in 0..names.len() {
-            for j in i + 1..names.len() {
-                let d: u8 = Self::dist(&valves, &names[i], &names[j]);
-                dist_matrix[i][j] = d;
-                dist_matrix[j][i] = d;
-            }
-        }
+        let dist_matrix: Vec<Vec<u8>> = (0..names.len())
+            .map(|i| {
+                (0..names.len())
+                    .map(|j| tunnels.steps(i, j).unwrap_or(0) as u8)
+                    .collect()
+            })
+            .collect();
 
         Self {
             names,
@@ -219,25 +213,6 @@ impl Tunnels {
         }
         max_released
     }
-
-    fn dist(valves: &HashMap<String, Valve>, start_valve: &str, end_valve: &str) -> u8 {
-        let start: String = valves.get(start_valve).map(|v| v.name.to_string()).unwrap();
-        let mut visited: HashSet<String> = HashSet::new();
-        visited.insert(start.clone());
-        let mut current: Vec<String> = vec![start];
-        let mut dist: u8 = 0;
-        loop {
-            current = current
-                .iter()
-                .flat_map(|v| valves.get(v).unwrap().neighbours.clone())
-                .filter(|ngb| visited.insert(ngb.clone()))
-                .collect();
-            dist += 1;
-            if current.contains(&end_valve.to_string()) {
-                return dist;
-            }
-        }
-    }
 }
 
 impl FromStr for Tunnels {
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 use std::str::FromStr;
-use util::coord::Pos3;
+use util::vector::Vector;
 
 const MAX_COORD: usize = 25;
 
@@ -16,7 +16,7 @@ impl Lava {
             for (j, row) in slice.iter().enumerate() {
                 for (k, &drop) in row.iter().enumerate() {
                     surface += if drop {
-                        6 - self.nb_neighbours(Pos3(i, j, k))
+                        6 - self.nb_neighbours(Vector([i, j, k]))
                     } else {
                         0
                     }
@@ -28,17 +28,17 @@ impl Lava {
 
     fn outer_surface(&self) -> usize {
         //Fill the structure with steam every where it can access
-        let mut steam: HashSet<Pos3> = HashSet::new();
-        steam.insert(Pos3(0, 0, 0));
+        let mut steam: HashSet<Vector<usize, 3>> = HashSet::new();
+        steam.insert(Vector([0, 0, 0]));
 
-        let mut current: Vec<Pos3> = vec![Pos3(0, 0, 0)];
+        let mut current: Vec<Vector<usize, 3>> = vec![Vector([0, 0, 0])];
         while !current.is_empty() {
             current = current
                 .into_iter()
                 .flat_map(|drop| {
-                    let new_drops: Vec<Pos3> = Self::neighbours(drop)
+                    let new_drops: Vec<Vector<usize, 3>> = Self::neighbours(drop)
                         .into_iter()
-                        .filter(|&Pos3(x, y, z)| !self.droplets[x][y][z])
+                        .filter(|&Vector([x, y, z])| !self.droplets[x][y][z])
                         .collect();
                     new_drops
                 })
@@ -53,7 +53,7 @@ impl Lava {
             for (j, row) in slice.iter().enumerate() {
                 for (k, &drop) in row.iter().enumerate() {
                     outer_surface += if drop {
-                        let ngbs = Self::neighbours(Pos3(i, j, k));
+                        let ngbs = Self::neighbours(Vector([i, j, k]));
                         let nb_steam = ngbs.iter().filter(|drop| steam.contains(*drop)).count();
                         6 - ngbs.len() + nb_steam
                     } else {
@@ -65,32 +65,32 @@ impl Lava {
         outer_surface
     }
 
-    fn nb_neighbours(&self, drop: Pos3) -> usize {
-        let ngbs: Vec<Pos3> = Self::neighbours(drop);
+    fn nb_neighbours(&self, drop: Vector<usize, 3>) -> usize {
+        let ngbs: Vec<Vector<usize, 3>> = Self::neighbours(drop);
         ngbs.iter()
-            .filter(|Pos3(i, j, k)| self.droplets[*i][*j][*k])
+            .filter(|Vector([i, j, k])| self.droplets[*i][*j][*k])
             .count()
     }
 
-    fn neighbours(Pos3(x, y, z): Pos3) -> Vec<Pos3> {
-        let mut ngbs: Vec<Pos3> = Vec::new();
+    fn neighbours(Vector([x, y, z]): Vector<usize, 3>) -> Vec<Vector<usize, 3>> {
+        let mut ngbs: Vec<Vector<usize, 3>> = Vec::new();
         if x > 0 {
-            ngbs.push(Pos3(x - 1, y, z));
+            ngbs.push(Vector([x - 1, y, z]));
         }
         if x + 1 < MAX_COORD {
-            ngbs.push(Pos3(x + 1, y, z));
+            ngbs.push(Vector([x + 1, y, z]));
         }
         if y > 0 {
-            ngbs.push(Pos3(x, y - 1, z));
+            ngbs.push(Vector([x, y - 1, z]));
         }
         if y + 1 < MAX_COORD {
-            ngbs.push(Pos3(x, y + 1, z));
+            ngbs.push(Vector([x, y + 1, z]));
         }
         if z > 0 {
-            ngbs.push(Pos3(x, y, z - 1));
+            ngbs.push(Vector([x, y, z - 1]));
         }
         if z + 1 < MAX_COORD {
-            ngbs.push(Pos3(x, y, z + 1));
+            ngbs.push(Vector([x, y, z + 1]));
         }
         ngbs
     }
@@ -100,14 +100,14 @@ impl FromStr for Lava {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let droplets: Vec<Pos3> = s
+        let droplets: Vec<Vector<usize, 3>> = s
             .lines()
             .map(|l| {
                 let ns: Vec<&str> = l.split(',').collect();
                 let x: usize = ns[0].parse().unwrap();
                 let y: usize = ns[1].parse().unwrap();
                 let z: usize = ns[2].parse().unwrap();
-                Pos3(x, y, z)
+                Vector([x, y, z])
             })
             .collect();
 
@@ -115,7 +115,7 @@ impl FromStr for Lava {
             droplets: [[[false; MAX_COORD]; MAX_COORD]; MAX_COORD],
         };
 
-        droplets.iter().for_each(|&Pos3(x, y, z)| {
+        droplets.iter().for_each(|&Vector([x, y, z])| {
             lava.droplets[x][y][z] = true;
         });
         Ok(lava)
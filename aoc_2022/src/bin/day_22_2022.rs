@@ -1,6 +1,7 @@
 use itertools::Itertools;
 use std::str::FromStr;
 use util::coord::Pos;
+use util::cube_net::CubeNet;
 use util::orientation::Dir;
 
 const HEIGHT: usize = 200;
@@ -19,6 +20,7 @@ struct Labyrinth {
     pos: Pos,
     dir: Dir,
     ops: Vec<Op>,
+    cube: CubeNet,
 }
 
 impl Labyrinth {
@@ -44,37 +46,13 @@ impl Labyrinth {
     }
 
     fn advance_one_in_cube(&mut self) {
-        /*
-           Cube pattern (50x50x50):
-           .AB
-           .C.
-           DE.
-           F..
-        */
-        let (x, y) = (self.pos.0, self.pos.1);
-        let (n_x, n_y, n_orient) = match (x, y, &self.dir) {
-            (51..=100, 1, Dir::North) => (1, x + 100, Dir::East), //A to F
-            (101..=150, 1, Dir::North) => (x - 100, 200, Dir::North), //B to F
-            (51, 1..=50, Dir::West) => (1, 151 - y, Dir::East),   //A to D
-            (150, 1..=50, Dir::East) => (100, 151 - y, Dir::West), //B to E
-            (101..=150, 50, Dir::South) => (100, x - 50, Dir::West), //B to C
-            (51, 51..=100, Dir::West) => (y - 50, 101, Dir::South), //C to D
-            (100, 51..=100, Dir::East) => (y + 50, 50, Dir::North), //C to B
-            (1..=50, 101, Dir::North) => (51, x + 50, Dir::East), //D to C
-            (1, 101..=150, Dir::West) => (51, 151 - y, Dir::East), //D to A
-            (100, 101..=150, Dir::East) => (150, 151 - y, Dir::West), //E to B
-            (51..=100, 150, Dir::South) => (50, x + 100, Dir::West), //E to F
-            (1, 151..=200, Dir::West) => (y - 100, 1, Dir::South), //F to A
-            (50, 151..=200, Dir::East) => (y - 100, 150, Dir::North), //F to E
-            (1..=50, 200, Dir::South) => (x + 100, 1, Dir::South), //F to B
-            (_, _, Dir::North) => (x, y - 1, self.dir),
-            (_, _, Dir::East) => (x + 1, y, self.dir),
-            (_, _, Dir::South) => (x, y + 1, self.dir),
-            (_, _, Dir::West) => (x - 1, y, self.dir),
-        };
+        //The net works on a 0-indexed map
+        let (Pos(n_x, n_y), n_orient) = self
+            .cube
+            .step(Pos(self.pos.0 - 1, self.pos.1 - 1), self.dir);
 
-        if let Some(false) = self.grid[n_y][n_x] {
-            self.pos = Pos(n_x, n_y);
+        if let Some(false) = self.grid[n_y + 1][n_x + 1] {
+            self.pos = Pos(n_x + 1, n_y + 1);
             self.dir = n_orient;
         }
         //Else we're stuck in a wall. Don't move
@@ -175,6 +153,13 @@ impl FromStr for Labyrinth {
             });
         });
 
+        //Fold the map into a cube
+        let cells: Vec<Vec<bool>> = grid[1..]
+            .iter()
+            .map(|row| row[1..].iter().map(|opt| opt.is_some()).collect())
+            .collect();
+        let cube: CubeNet = CubeNet::new(&cells).map_err(|_| ())?;
+
         //Find starting pos
         let start_x: usize = grid[1].iter().position(|opt| opt.is_some()).unwrap();
 
@@ -183,6 +168,7 @@ impl FromStr for Labyrinth {
             pos: Pos(start_x, 1),
             dir: Dir::East,
             ops,
+            cube,
         })
     }
 }
@@ -228,5 +214,10 @@ mod tests {
         assert_eq!(6032, laby.score());
     }
 
-    //Part 2 should be 5031
+    #[test]
+    fn part_2() {
+        let mut laby: Labyrinth = INPUT.parse().unwrap();
+        laby.move_all(true);
+        assert_eq!(5031, laby.score());
+    }
 }
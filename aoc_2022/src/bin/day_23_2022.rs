@@ -1,14 +1,34 @@
-use itertools::Itertools;
-use std::collections::hash_map::Entry::Vacant;
-use std::collections::HashMap;
 use std::str::FromStr;
+use util::bitgrid::{BitGrid, BitRow};
 use util::coord::Pos;
 use util::orientation::Dir;
 
 const MARGIN: usize = 55;
 
+//The elves of a row that propose to move in each direction
+#[derive(Clone)]
+struct Moves {
+    north: BitRow,
+    south: BitRow,
+    west: BitRow,
+    east: BitRow,
+}
+
+impl Moves {
+    fn none(width: usize) -> Self {
+        let empty: BitRow = BitRow::new(width);
+        Moves {
+            north: empty.clone(),
+            south: empty.clone(),
+            west: empty.clone(),
+            east: empty,
+        }
+    }
+}
+
+//The elves are bits, a whole row of elves is moved at once
 struct Grove {
-    grid: Vec<Vec<bool>>,
+    elves: BitGrid,
     order: Vec<Dir>,
 }
 
@@ -19,145 +39,77 @@ impl Grove {
         }
     }
 
-    fn round(&mut self) {
-        let mut target_map: HashMap<Pos, Pos> = HashMap::new();
-        //Find target for each elves
-        for (j, row) in self.grid.iter().enumerate() {
-            for (i, &b) in row.iter().enumerate() {
-                if b {
-                    let pos = Pos(i, j);
-                    if let Some(target) = self.target(&pos) {
-                        if let Vacant(e) = target_map.entry(target) {
-                            e.insert(pos);
-                        } else {
-                            target_map.remove(&target);
-                        }
-                    }
-                }
+    //Returns false if no elf moved
+    fn round(&mut self) -> bool {
+        let (width, height) = (self.elves.width(), self.elves.height());
+        let empty: BitRow = BitRow::new(width);
+        let row = |y: usize| -> &BitRow { self.elves.rows().get(y).unwrap_or(&empty) };
+        //The elf at x, or one on its left or its right
+        let spread = |r: &BitRow| r | &r.shifted_left(1) | &r.shifted_right(1);
+
+        let mut staying: Vec<BitRow> = Vec::with_capacity(height);
+        let mut moves: Vec<Moves> = Vec::with_capacity(height);
+        for y in 0..height {
+            let (above, here, below) = (row(y.wrapping_sub(1)), row(y), row(y + 1));
+            let column: BitRow = above | here | below;
+            let free_north: BitRow = !&spread(above);
+            let free_south: BitRow = !&spread(below);
+            let free_west: BitRow = !&column.shifted_right(1);
+            let free_east: BitRow = !&column.shifted_left(1);
+            let alone: BitRow = here & &free_north & &free_south & &free_west & &free_east;
+
+            let mut remaining: BitRow = here & &!&alone;
+            let mut m: Moves = Moves::none(width);
+            for dir in &self.order {
+                let (free, proposed) = match dir {
+                    Dir::North => (&free_north, &mut m.north),
+                    Dir::South => (&free_south, &mut m.south),
+                    Dir::West => (&free_west, &mut m.west),
+                    Dir::East => (&free_east, &mut m.east),
+                };
+                *proposed = &remaining & free;
+                remaining &= &!&*proposed;
             }
+            staying.push(remaining | &alone);
+            moves.push(m);
         }
-        //Apply not-conflicting targets
-        target_map.into_iter().for_each(|(target, pos)| {
-            self.grid[pos.1][pos.0] = false;
-            self.grid[target.1][target.0] = true;
-        });
 
+        //Only elves coming from opposite sides can aim at the same tile, they both stay then
+        let no_moves: Moves = Moves::none(width);
+        let moves_at = |y: usize| moves.get(y).unwrap_or(&no_moves);
+        let new_rows: Vec<BitRow> = (0..height)
+            .map(|y| {
+                let m: &Moves = moves_at(y);
+                let from_south: &BitRow = &moves_at(y + 1).north;
+                let from_north: &BitRow = &moves_at(y.wrapping_sub(1)).south;
+                let from_west: BitRow = m.east.shifted_right(1);
+                let from_east: BitRow = m.west.shifted_left(1);
+                let blocked: BitRow = (&m.north & &moves_at(y.wrapping_sub(2)).south)
+                    | &(&m.south & &moves_at(y + 2).north)
+                    | &(&m.east & &m.west.shifted_left(2))
+                    | &(&m.west & &m.east.shifted_right(2));
+                (from_south ^ from_north) | &(&from_west ^ &from_east) | &staying[y] | &blocked
+            })
+            .collect();
+
+        let moved: bool = new_rows.as_slice() != self.elves.rows();
+        self.elves.set_rows(new_rows);
         //Rotate order of directions
         self.order.rotate_left(1);
-    }
-
-    fn neighbours(&self, &Pos(x, y): &Pos) -> Vec<bool> {
-        (-1..=1)
-            .cartesian_product(-1..=1)
-            .filter(|(i, j)| *i != 0 || *j != 0)
-            .map(|(i, j)| (((x as isize + i) as usize), (y as isize + j) as usize))
-            .map(|(i, j)| self.grid[j][i])
-            .collect()
-    }
-
-    fn target(&self, pos @ &Pos(x, y): &Pos) -> Option<Pos> {
-        let ngbs: Vec<bool> = self.neighbours(pos);
-        if ngbs.iter().all(|b| !*b) {
-            return None;
-        }
-        /*
-        Neighbours :
-          035
-          1.6
-          247
-         */
-        self.order.iter().find_map(|dir| match dir {
-            Dir::North => {
-                if !ngbs[0] && !ngbs[3] && !ngbs[5] {
-                    Some(Pos(x, y - 1))
-                } else {
-                    None
-                }
-            }
-            Dir::South => {
-                if !ngbs[2] && !ngbs[4] && !ngbs[7] {
-                    Some(Pos(x, y + 1))
-                } else {
-                    None
-                }
-            }
-            Dir::West => {
-                if !ngbs[0] && !ngbs[1] && !ngbs[2] {
-                    Some(Pos(x - 1, y))
-                } else {
-                    None
-                }
-            }
-            Dir::East => {
-                if !ngbs[5] && !ngbs[6] && !ngbs[7] {
-                    Some(Pos(x + 1, y))
-                } else {
-                    None
-                }
-            }
-        })
-    }
-
-    fn rectangle_limits(&self) -> (Pos, Pos) {
-        let top_y: usize = self
-            .grid
-            .iter()
-            .position(|row| row.iter().any(|b| *b))
-            .unwrap();
-        let bot_y: usize = self
-            .grid
-            .iter()
-            .rposition(|row| row.iter().any(|b| *b))
-            .unwrap();
-
-        let mut top_x: usize = 0;
-        'outer: loop {
-            for j in top_y..=bot_y {
-                if self.grid[j][top_x] {
-                    break 'outer;
-                }
-            }
-            top_x += 1;
-        }
-
-        let mut bot_x: usize = self.grid.len() - 1;
-        'outer: loop {
-            for j in top_y..=bot_y {
-                if self.grid[j][bot_x] {
-                    break 'outer;
-                }
-            }
-            bot_x -= 1;
-        }
-        (Pos(top_x, top_y), Pos(bot_x, bot_y))
+        moved
     }
 
     fn score(&self) -> usize {
-        let (top_left, bot_right): (Pos, Pos) = self.rectangle_limits();
-        self.grid[top_left.1..=bot_right.1]
-            .iter()
-            .map(|row| {
-                row[top_left.0..=bot_right.0]
-                    .iter()
-                    .filter(|b| !**b)
-                    .count()
-            })
-            .sum()
-    }
-
-    fn equal(&self, other: &[Vec<bool>]) -> bool {
-        self.grid
-            .iter()
-            .enumerate()
-            .all(|(j, row)| row.iter().enumerate().all(|(i, b)| other[j][i] == *b))
+        let (Pos(left, top), Pos(right, bottom)) = self.elves.bounds().unwrap();
+        (right - left + 1) * (bottom - top + 1) - self.elves.count_ones()
     }
 
     #[allow(dead_code)]
     fn print(&self) {
         println!("Printing Grove:");
-        for row in self.grid.iter() {
-            for &b in row.iter() {
-                let c = if b { '#' } else { '.' };
+        for y in 0..self.elves.height() {
+            for x in 0..self.elves.width() {
+                let c = if self.elves.get(Pos(x, y)) { '#' } else { '.' };
                 print!("{c}");
             }
             println!();
@@ -172,17 +124,17 @@ impl FromStr for Grove {
         let lines: Vec<&str> = s.lines().collect();
         let size = lines.len() + 2 * MARGIN;
 
-        let mut grid: Vec<Vec<bool>> = vec![vec![false; size]; size];
+        let mut elves: BitGrid = BitGrid::new(size, size);
         for (j, row) in lines.into_iter().enumerate() {
             for (i, c) in row.chars().enumerate() {
                 if c == '#' {
-                    grid[j + MARGIN][i + MARGIN] = true;
+                    elves.set(Pos(i + MARGIN, j + MARGIN), true);
                 }
             }
         }
 
         let order: Vec<Dir> = vec![Dir::North, Dir::South, Dir::West, Dir::East];
-        Ok(Grove { grid, order })
+        Ok(Grove { elves, order })
     }
 }
 
@@ -196,11 +148,8 @@ fn main() {
         grove.score()
     );
 
-    let mut grid: Vec<Vec<bool>> = vec![vec![false; grove.grid.len()]; grove.grid.len()];
-    let mut nb_round = 10;
-    while !grove.equal(&grid) {
-        grid.clone_from(&grove.grid);
-        grove.round();
+    let mut nb_round = 11;
+    while grove.round() {
         nb_round += 1;
     }
     println!("Part2: After round {nb_round}, the elves are not moving anymore");
@@ -229,11 +178,8 @@ mod tests {
     #[test]
     fn part_2() {
         let mut grove: Grove = INPUT.parse().unwrap();
-        let mut grid: Vec<Vec<bool>> = vec![vec![false; grove.grid.len()]; grove.grid.len()];
-        let mut nb_round = 0;
-        while !grove.equal(&grid) {
-            grid = grove.grid.clone();
-            grove.round();
+        let mut nb_round = 1;
+        while grove.round() {
             nb_round += 1;
         }
         assert_eq!(nb_round, 20);
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 use std::str::FromStr;
-use util::coord::Pos;
+use util::coord::{Pos, PosI};
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 struct Blizzards {
@@ -24,23 +24,32 @@ impl Blizzards {
         }
     }
 
-    fn northern_free(&self, &Pos(x, y): &Pos, time: usize) -> bool {
-        let v: usize = (y - 1 + time) % self.height + 1;
+    //Position at time 0 of a blizzard going in direction "dir" that is on "pos" at "time"
+    //Blizzards move on the inner torus, whose coordinates start at 1
+    fn origin(&self, &Pos(x, y): &Pos, PosI(dx, dy): PosI, time: usize) -> Pos {
+        let t: isize = time as isize;
+        let Pos(i, j) =
+            Pos(x - 1, y - 1).add_torus(PosI(-dx * t, -dy * t), self.width, self.height);
+        Pos(i + 1, j + 1)
+    }
+
+    fn northern_free(&self, pos: &Pos, time: usize) -> bool {
+        let Pos(x, v) = self.origin(pos, PosI(0, -1), time);
         !self.northern[x].contains(&v)
     }
 
-    fn southern_free(&self, &Pos(x, y): &Pos, time: usize) -> bool {
-        let v: usize = (y + self.height * time - 1 - time) % self.height + 1;
+    fn southern_free(&self, pos: &Pos, time: usize) -> bool {
+        let Pos(x, v) = self.origin(pos, PosI(0, 1), time);
         !self.southern[x].contains(&v)
     }
 
-    fn western_free(&self, &Pos(x, y): &Pos, time: usize) -> bool {
-        let v: usize = (x - 1 + time) % self.width + 1;
+    fn western_free(&self, pos: &Pos, time: usize) -> bool {
+        let Pos(v, y) = self.origin(pos, PosI(-1, 0), time);
         !self.western[y].contains(&v)
     }
 
-    fn eastern_free(&self, &Pos(x, y): &Pos, time: usize) -> bool {
-        let v: usize = (x + self.width * time - 1 - time) % self.width + 1;
+    fn eastern_free(&self, pos: &Pos, time: usize) -> bool {
+        let Pos(v, y) = self.origin(pos, PosI(1, 0), time);
         !self.eastern[y].contains(&v)
     }
 }
@@ -104,13 +113,8 @@ impl Baasin {
         } else if x == 1 && y == 1 && backwards {
             vec![Pos(1, 0)]
         } else {
-            let candidates: Vec<Pos> = vec![
-                Pos(x - 1, y),
-                Pos(x, y - 1),
-                *pos,
-                Pos(x + 1, y),
-                Pos(x, y + 1),
-            ];
+            let mut candidates: Vec<Pos> = pos.neighbours();
+            candidates.push(*pos);
             candidates
                 .into_iter()
                 .filter(|&Pos(i, j)| i > 0 && i <= self.width && j > 0 && j <= self.height)
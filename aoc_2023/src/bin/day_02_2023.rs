@@ -8,31 +8,30 @@ use nom_permutation::permutation_opt;
 use std::cmp::max;
 use std::str::FromStr;
 use util::basic_parser::{parse_usize, title};
-use util::coord::Pos3;
+use util::vector::Vector;
 
-const MAX: Pos3 = Pos3(12, 13, 14);
+const MAX: Vector<usize, 3> = Vector([12, 13, 14]);
 
 struct Game {
-    draws: Vec<Pos3>,
+    draws: Vec<Vector<usize, 3>>,
 }
 
 impl Game {
-    fn is_possible(&self, Pos3(r, g, b): Pos3) -> bool {
+    fn is_possible(&self, Vector([r, g, b]): Vector<usize, 3>) -> bool {
         self.draws
             .iter()
-            .all(|&Pos3(i, j, k)| i <= r && j <= g && k <= b)
+            .all(|&Vector([i, j, k])| i <= r && j <= g && k <= b)
     }
 
-    fn min_possible_set(&self) -> Pos3 {
-        self.draws
-            .iter()
-            .fold(Pos3(0, 0, 0), |Pos3(r, g, b), &Pos3(i, j, k)| {
-                Pos3(max(r, i), max(g, j), max(b, k))
-            })
+    fn min_possible_set(&self) -> Vector<usize, 3> {
+        self.draws.iter().fold(
+            Vector([0, 0, 0]),
+            |Vector([r, g, b]), &Vector([i, j, k])| Vector([max(r, i), max(g, j), max(b, k)]),
+        )
     }
 
     fn power(&self) -> usize {
-        let Pos3(r, g, b) = self.min_possible_set();
+        let Vector([r, g, b]) = self.min_possible_set();
         r * g * b
     }
 }
@@ -41,7 +40,7 @@ impl FromStr for Game {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_draw(s: &str) -> IResult<&str, Pos3> {
+        fn parse_draw(s: &str) -> IResult<&str, Vector<usize, 3>> {
             fn parse_red(s: &str) -> IResult<&str, usize> {
                 terminated(parse_usize, pair(tag(" red"), opt(tag(", ")))).parse(s)
             }
@@ -52,12 +51,12 @@ impl FromStr for Game {
                 terminated(parse_usize, pair(tag(" blue"), opt(tag(", ")))).parse(s)
             }
             let (s, (r, g, b)) = permutation_opt((parse_red, parse_green, parse_blue))(s)?;
-            Ok((s, Pos3(r.unwrap_or(0), g.unwrap_or(0), b.unwrap_or(0))))
+            Ok((s, Vector([r.unwrap_or(0), g.unwrap_or(0), b.unwrap_or(0)])))
         }
-        fn parse_draws(s: &str) -> IResult<&str, Vec<Pos3>> {
+        fn parse_draws(s: &str) -> IResult<&str, Vec<Vector<usize, 3>>> {
             preceded(title, separated_list1(tag("; "), parse_draw)).parse(s)
         }
-        let draws: Vec<Pos3> = parse_draws(s).unwrap().1;
+        let draws: Vec<Vector<usize, 3>> = parse_draws(s).unwrap().1;
 
         Ok(Game { draws })
     }
@@ -67,7 +66,7 @@ struct Conundrum {
 }
 
 impl Conundrum {
-    fn possible_games(&self, max: Pos3) -> usize {
+    fn possible_games(&self, max: Vector<usize, 3>) -> usize {
         self.games
             .iter()
             .enumerate()
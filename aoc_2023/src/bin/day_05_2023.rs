@@ -2,122 +2,26 @@ use nom::sequence::preceded;
 use nom::Parser;
 use std::str::FromStr;
 use util::basic_parser::{title, usize_list};
-use util::coord::Pos;
+use util::interval::{Interval, IntervalSet, OffsetMap};
 use util::split_blocks;
 
-struct AlmanacRange {
-    source: usize,
-    destination: usize,
-    range: usize,
-}
-
-impl AlmanacRange {
-    fn dest(&self, seed: usize) -> Option<usize> {
-        let s: usize = self.source;
-        if (s..(s + self.range)).contains(&seed) {
-            Some(self.destination + (seed - s))
-        } else {
-            None
-        }
-    }
-
-    //Returns an optional transformed range if the input range overlaps with the AlmanacRange
-    //Return 0, 1 or 2 ranges in a Vec for parts not overlapping with the AlmanacRange
-    fn dest_range(&self, pos: Pos) -> (Option<Pos>, Vec<Pos>) {
-        let dest: usize = self.destination;
-        match (pos.0, pos.1, self.source, self.source + self.range) {
-            //All range is outside (either inferior or superior)
-            (start, end, s, e) if end < s || start >= e => (None, vec![pos]),
-            //ALl range is inside
-            (start, end, s, e) if start >= s && end <= e => {
-                (Some(Pos(dest + (start - s), dest + (end - s))), Vec::new())
-            }
-            //Part before, part inside
-            (start, end, s, e) if start < s && end <= e => {
-                (Some(Pos(dest, dest + (end - s))), vec![Pos(start, s)])
-            }
-            //Part inside, part after
-            (start, end, s, e) if start >= s && end > e => (
-                Some(Pos(dest + (start - s), dest + (e - s))),
-                vec![Pos(e, end)],
-            ),
-            //Part before, part inside, part after
-            (start, end, s, e) if start < s && end > e => (
-                Some(Pos(dest, dest + (e - s))),
-                vec![Pos(start, s), Pos(e, end)],
-            ),
-            //Should never happen
-            _ => (None, Vec::new()),
-        }
-    }
-}
-
-impl FromStr for AlmanacRange {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values: Vec<usize> = usize_list(s).unwrap().1;
-        Ok(AlmanacRange {
-            source: values[1],
-            destination: values[0],
-            range: values[2],
-        })
-    }
-}
 struct AlmanacMap {
-    ranges: Vec<AlmanacRange>,
+    map: OffsetMap<usize>,
 }
 
-impl AlmanacMap {
-    fn dest(&self, seed: usize) -> usize {
-        let opt: Option<usize> =
-            self.ranges.iter().fold(
-                None,
-                |acc, range| {
-                    if acc.is_some() {
-                        acc
-                    } else {
-                        range.dest(seed)
-                    }
-                },
-            );
-
-        opt.unwrap_or(seed)
-    }
-
-    fn dest_vec(&self, ranges: Vec<Pos>) -> Vec<Pos> {
-        ranges
-            .into_iter()
-            .flat_map(|range| self.dest_range(range))
-            .collect()
-    }
-
-    fn dest_range(&self, seed_range: Pos) -> Vec<Pos> {
-        let (mut to_do, mut done): (Vec<Pos>, Vec<Pos>) = self.ranges.iter().fold(
-            (vec![seed_range], Vec::new()),
-            |(todo_acc, mut done_acc), range| {
-                let mut new_todo: Vec<Pos> = Vec::new();
-                for todo in todo_acc {
-                    let (done_opt, mut not_done) = range.dest_range(todo);
-                    if let Some(done) = done_opt {
-                        done_acc.push(done);
-                    }
-                    new_todo.append(&mut not_done)
-                }
-                (new_todo, done_acc)
-            },
-        );
-
-        to_do.append(&mut done);
-        to_do
-    }
-}
 impl FromStr for AlmanacMap {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let ranges: Vec<AlmanacRange> = s.lines().skip(1).map(|l| l.parse().unwrap()).collect();
-        Ok(AlmanacMap { ranges })
+        let map: OffsetMap<usize> = s
+            .lines()
+            .skip(1)
+            .map(|l| {
+                let values: Vec<usize> = usize_list(l).unwrap().1;
+                (Interval::with_len(values[1], values[2]), values[0])
+            })
+            .collect();
+        Ok(AlmanacMap { map })
     }
 }
 
@@ -128,11 +32,7 @@ struct Almanac {
 
 impl Almanac {
     fn apply_maps(&self, seed: usize) -> usize {
-        let mut loc: usize = seed;
-        for m in self.maps.iter() {
-            loc = m.dest(loc);
-        }
-        loc
+        self.maps.iter().fold(seed, |loc, m| m.map.map_value(loc))
     }
     fn lowest_location(&self) -> usize {
         self.seeds
@@ -142,20 +42,15 @@ impl Almanac {
             .min()
             .unwrap()
     }
-    fn apply_range_maps(&self, ranges: Vec<Pos>) -> Vec<Pos> {
-        let mut locs: Vec<Pos> = ranges;
-        for m in self.maps.iter() {
-            locs = m.dest_vec(locs);
-        }
-        locs
-    }
-
     fn lowest_range_location(&self) -> usize {
-        self.seeds
+        let seeds: IntervalSet<usize> = self
+            .seeds
             .chunks(2)
-            .map(|chunk| vec![Pos(chunk[0], chunk[0] + chunk[1])])
-            .map(|v| self.apply_range_maps(v))
-            .flat_map(|v| v.into_iter().map(|pos| pos.0).collect::<Vec<usize>>())
+            .map(|chunk| Interval::with_len(chunk[0], chunk[1]))
+            .collect();
+        self.maps
+            .iter()
+            .fold(seeds, |locs, m| m.map.map_set(&locs))
             .min()
             .unwrap()
     }
@@ -5,8 +5,9 @@ use nom::IResult;
 use nom::Parser;
 use std::collections::HashMap;
 use std::str::FromStr;
+use util::num::lcm_all;
 use util::orientation::Dir;
-use util::{lcm, split_blocks};
+use util::split_blocks;
 
 struct Wasteland {
     dirs: Vec<Dir>,
@@ -70,7 +71,7 @@ impl Wasteland {
             steps += 1;
         }
         //Each cycle is equal to the first distance, so we can apply LCM immediately
-        dist_to_z.iter().fold(1, |acc, &n| lcm(acc, n as isize)) as usize
+        lcm_all(dist_to_z)
     }
 }
 
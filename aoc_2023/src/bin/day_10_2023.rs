@@ -2,6 +2,7 @@ use std::collections::HashSet;
 use std::str::FromStr;
 use util::coord::PosI;
 use util::orientation::Dir;
+use util::polygon::interior_points;
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 enum Pipe {
@@ -30,14 +31,17 @@ struct PipeMaze {
 }
 
 impl PipeMaze {
-    fn pipes_in_loop(&self, start: PosI) -> HashSet<PosI> {
-        let mut pipes_in_loop: HashSet<PosI> = HashSet::from([start]);
+    //The pipes of the loop, in the order we walk through them
+    fn pipes_in_loop(&self, start: PosI) -> Vec<PosI> {
+        let mut seen: HashSet<PosI> = HashSet::from([start]);
+        let mut pipes_in_loop: Vec<PosI> = vec![start];
         let mut current: Option<PosI> = Some(start);
         while let Some(curr) = current {
             let ngbs = curr.neighbours();
             current = ngbs
                 .into_iter()
-                .find(|&ngb| self.connects_to(curr, ngb) && pipes_in_loop.insert(ngb));
+                .find(|&ngb| self.connects_to(curr, ngb) && seen.insert(ngb));
+            pipes_in_loop.extend(current);
         }
         pipes_in_loop
     }
@@ -58,29 +62,6 @@ impl PipeMaze {
             .unwrap()
     }
 
-    fn pipe_from_start(&self, start: PosI) -> Pipe {
-        let PosI(x, y) = start;
-        let up: PosI = PosI(x, y - 1);
-        let down: PosI = PosI(x, y + 1);
-        let left: PosI = PosI(x - 1, y);
-        let right: PosI = PosI(x + 1, y);
-
-        match (
-            self.connects_to(up, start),
-            self.connects_to(down, start),
-            self.connects_to(left, start),
-            self.connects_to(right, start),
-        ) {
-            (true, true, false, false) => Pipe::Join(Dir::North, Dir::South),
-            (false, false, true, true) => Pipe::Join(Dir::West, Dir::East),
-            (true, false, true, false) => Pipe::Join(Dir::North, Dir::West),
-            (true, false, false, true) => Pipe::Join(Dir::North, Dir::East),
-            (false, true, true, false) => Pipe::Join(Dir::South, Dir::West),
-            (false, true, false, true) => Pipe::Join(Dir::South, Dir::East),
-            _ => panic!("Impossible pipe"),
-        }
-    }
-
     fn connects_to(&self, from: PosI, to: PosI) -> bool {
         let PosI(x, y) = from;
         if x >= 0 && x < self.pipes[0].len() as isize && y >= 0 && y < self.pipes.len() as isize {
@@ -108,129 +89,12 @@ impl PipeMaze {
     }
 
     fn compute(&self) -> (usize, usize) {
-        let pipes_in_loop: HashSet<PosI> = self.pipes_in_loop(self.start());
-        //Find the most top/left 'F' in the loop and start going East following the loop.
-        //From there, we know "inside" is to our right
-        let first_f: PosI = self
-            .pipes
-            .iter()
-            .enumerate()
-            .find_map(|(y, row)| {
-                row.iter().enumerate().find_map(|(x, _)| {
-                    let pos: PosI = PosI(x as isize, y as isize);
-                    if pipes_in_loop.contains(&pos) {
-                        Some(pos)
-                    } else {
-                        None
-                    }
-                })
-            })
-            .unwrap();
-
-        //Find all the "inside" tiles directly touching the loop (we touch them with our right hand)
-        let mut curr: PosI = PosI(first_f.0 + 1, first_f.1);
-        let mut dir = Dir::West;
-        let mut touching_inside: HashSet<PosI> = HashSet::new();
-
-        while curr != first_f {
-            let (new_curr, new_dir, insides) = self.next_and_inside_neighbours(curr, dir);
-            insides.into_iter().for_each(|p| {
-                if !pipes_in_loop.contains(&p) {
-                    touching_inside.insert(p);
-                }
-            });
-            curr = new_curr;
-            dir = new_dir;
-        }
-
-        //We have to count all the inside tiles not touching the loop too
-        let mut to_flood: Vec<PosI> = touching_inside.into_iter().collect();
-        let mut flooded: HashSet<PosI> = HashSet::new();
-
-        while let Some(p) = to_flood.pop() {
-            flooded.insert(p);
-            p.neighbours().into_iter().for_each(|ngb| {
-                if !pipes_in_loop.contains(&ngb) && !flooded.contains(&ngb) {
-                    to_flood.push(ngb)
-                }
-            })
-        }
-
-        (pipes_in_loop.len() / 2, flooded.len())
-    }
-
-    fn next_and_inside_neighbours(
-        &self,
-        PosI(x, y): PosI,
-        from_dir: Dir,
-    ) -> (PosI, Dir, Vec<PosI>) {
-        let pipe: Pipe = self.pipes[y as usize][x as usize];
-
-        let pipe: Pipe = if pipe == Pipe::Start {
-            self.pipe_from_start(PosI(x, y))
-        } else {
-            pipe
-        };
-
-        match (pipe, from_dir) {
-            //Horizontal pipes
-            (Pipe::Join(Dir::West, Dir::East), Dir::West) => {
-                (PosI(x + 1, y), Dir::West, vec![PosI(x, y + 1)])
-            }
-            (Pipe::Join(Dir::West, Dir::East), Dir::East) => {
-                (PosI(x - 1, y), Dir::East, vec![PosI(x, y - 1)])
-            }
-            //Vertical pipes
-            (Pipe::Join(Dir::North, Dir::South), Dir::North) => {
-                (PosI(x, y + 1), Dir::North, vec![PosI(x - 1, y)])
-            }
-            (Pipe::Join(Dir::North, Dir::South), Dir::South) => {
-                (PosI(x, y - 1), Dir::South, vec![PosI(x + 1, y)])
-            }
-            //F pipes
-            (Pipe::Join(Dir::South, Dir::East), Dir::South) => {
-                (PosI(x + 1, y), Dir::West, Vec::new())
-            }
-            (Pipe::Join(Dir::South, Dir::East), Dir::East) => (
-                PosI(x, y + 1),
-                Dir::North,
-                vec![PosI(x, y - 1), PosI(x - 1, y)],
-            ),
-            //7 pipes
-            (Pipe::Join(Dir::South, Dir::West), Dir::South) => (
-                PosI(x - 1, y),
-                Dir::East,
-                vec![PosI(x, y - 1), PosI(x + 1, y)],
-            ),
-            (Pipe::Join(Dir::South, Dir::West), Dir::West) => {
-                (PosI(x, y + 1), Dir::North, Vec::new())
-            }
-            //L pipes
-            (Pipe::Join(Dir::North, Dir::East), Dir::North) => (
-                PosI(x + 1, y),
-                Dir::West,
-                vec![PosI(x, y + 1), PosI(x - 1, y)],
-            ),
-            (Pipe::Join(Dir::North, Dir::East), Dir::East) => {
-                (PosI(x, y - 1), Dir::South, Vec::new())
-            }
-            //J pipes
-            (Pipe::Join(Dir::North, Dir::West), Dir::North) => {
-                (PosI(x - 1, y), Dir::East, Vec::new())
-            }
-
-            (Pipe::Join(Dir::North, Dir::West), Dir::West) => (
-                PosI(x, y - 1),
-                Dir::South,
-                vec![PosI(x, y + 1), PosI(x + 1, y)],
-            ),
-            _ => panic!(
-                "Impossible, {:?} cannot reach {:?} at {:?}",
-                from_dir,
-                pipe,
-                PosI(x, y)
-            ),
-        }
+        let pipes_in_loop: Vec<PosI> = self.pipes_in_loop(self.start());
+        //Every pipe of the loop is on the boundary, Pick's theorem gives the enclosed tiles
+        (
+            pipes_in_loop.len() / 2,
+            interior_points(&pipes_in_loop) as usize,
+        )
     }
 }
 
@@ -7,6 +7,7 @@ use std::str::FromStr;
 use util::basic_parser::{from_hex, parse_usize};
 use util::coord::PosI;
 use util::orientation::Dir;
+use util::polygon::{dig_plan, lattice_points};
 
 #[derive(Copy, Clone, Debug)]
 struct Trench {
@@ -24,17 +25,6 @@ impl Trench {
             (self.size, self.dir)
         }
     }
-
-    fn dig_to(&self, PosI(x, y): PosI, big: bool) -> PosI {
-        let (l, d): (usize, Dir) = self.size_and_dir(big);
-
-        match d {
-            Dir::North => PosI(x, y - l as isize),
-            Dir::East => PosI(x + l as isize, y),
-            Dir::South => PosI(x, y + l as isize),
-            Dir::West => PosI(x - l as isize, y),
-        }
-    }
 }
 
 impl FromStr for Trench {
@@ -84,37 +74,11 @@ struct LavaLagoon {
 
 impl LavaLagoon {
     fn volume(&self, big: bool) -> usize {
-        //Build a vec of all the corner we get to while digging
-        let mut pos: PosI = PosI(0, 0);
-        let mut nodes: Vec<PosI> = vec![pos];
-
-        for tr in self.trenches.iter() {
-            pos = tr.dig_to(pos, big);
-            nodes.push(pos);
-        }
-
-        //Use shoelace formula to compute the "inside" area
-        let shoelace: usize = nodes
-            .windows(2)
-            .map(|pair| pair[0].0 * pair[1].1 - pair[0].1 * pair[1].0)
-            .sum::<isize>()
-            .unsigned_abs()
-            / 2;
-
-        // We have 2n+5 points in "nodes"
-        // n "inside" corners, n+4 "outside" corners and the starting point (0,0) twice
-        // An outside corner adds 3/4, an inside one adds 1/4, so we have n+3 added area from the corners
-        let corners_area: usize = (nodes.len() - 5) / 2 + 3;
-
-        //For each side, the added area is (length-1)/2
-        let sides_area: usize = self
-            .trenches
-            .iter()
-            .map(|tr| tr.size_and_dir(big).0 - 1)
-            .sum::<usize>()
-            / 2;
-
-        shoelace + corners_area + sides_area
+        let vertices: Vec<PosI> = dig_plan(self.trenches.iter().map(|tr| {
+            let (size, dir) = tr.size_and_dir(big);
+            (dir, size)
+        }));
+        lattice_points(&vertices) as usize
     }
 }
 
@@ -6,9 +6,10 @@ use nom::sequence::{delimited, preceded, terminated};
 use nom::IResult;
 use nom::Parser;
 use std::collections::HashMap;
-use std::ops::Range;
 use std::str::FromStr;
 use util::basic_parser::parse_usize;
+use util::dot::{Dot, Shape};
+use util::interval::Interval;
 use util::split_blocks;
 
 #[derive(Copy, Clone, Debug)]
@@ -29,6 +30,15 @@ impl Category {
             _ => Err(format!("Unknown Category [{c}]")),
         }
     }
+
+    fn to_char(self) -> char {
+        match self {
+            Category::Xcool => 'x',
+            Category::Music => 'm',
+            Category::Aero => 'a',
+            Category::Shiny => 's',
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -38,6 +48,16 @@ enum RuleResult {
     SendTo(String),
 }
 
+impl RuleResult {
+    fn target(&self) -> &str {
+        match self {
+            RuleResult::Accept => "A",
+            RuleResult::Reject => "R",
+            RuleResult::SendTo(id) => id,
+        }
+    }
+}
+
 impl FromStr for RuleResult {
     type Err = ();
 
@@ -80,15 +100,21 @@ impl PartRule {
         }
     }
 
+    fn condition(&self) -> Option<String> {
+        let c: Category = self.cat?;
+        let op: char = if self.sup { '>' } else { '<' };
+        Some(format!("{}{op}{}", c.to_char(), self.value))
+    }
+
     //Returns a (result, range) for the part passing the test  or if there is no condition
     //Also returns an optional range if the test failed
-    fn apply_range(&self, part: PartRange) -> (RuleResult, PartRange, Option<PartRange>) {
+    fn apply_range(&self, part: PartRange) -> (RuleResult, Option<PartRange>, Option<PartRange>) {
         if let Some(c) = &self.cat {
             let (good, bad) = part.split(*c, self.sup, self.value);
-            (self.result.clone(), good, Some(bad))
+            (self.result.clone(), good, bad)
         } else {
             //No test, all the range applies the RuleResult
-            (self.result.clone(), part, None)
+            (self.result.clone(), Some(part), None)
         }
     }
 }
@@ -159,19 +185,35 @@ impl Workflow {
         let mut remaining: Option<PartRange> = Some(range);
         for rule in self.tests.iter() {
             if let Some(remain) = remaining {
-                let (r, d, opt): (RuleResult, PartRange, Option<PartRange>) =
+                let (r, d, opt): (RuleResult, Option<PartRange>, Option<PartRange>) =
                     rule.apply_range(remain);
 
-                match r {
-                    RuleResult::Accept => done.push(d),
-                    RuleResult::Reject => (), //Nothing, already rejected
-                    RuleResult::SendTo(id) => todo.push((id, d)),
+                match (r, d) {
+                    (RuleResult::Accept, Some(d)) => done.push(d),
+                    (RuleResult::SendTo(id), Some(d)) => todo.push((id, d)),
+                    _ => (), //Nothing, already rejected or nothing passed the test
                 }
                 remaining = opt;
             }
         }
         (done, todo)
     }
+
+    //One edge per rule, labelled with its condition
+    fn add_to_dot(&self, dot: &mut Dot) {
+        let shape: Shape = if self.id == "in" {
+            Shape::Diamond
+        } else {
+            Shape::Box
+        };
+        dot.node(&self.id, &self.id, shape);
+        for rule in &self.tests {
+            match rule.condition() {
+                Some(condition) => dot.labelled_edge(&self.id, rule.result.target(), &condition),
+                None => dot.edge(&self.id, rule.result.target()),
+            };
+        }
+    }
 }
 
 impl FromStr for Workflow {
@@ -205,10 +247,10 @@ impl FromStr for Workflow {
 
 #[derive(Clone, Debug)]
 struct PartRange {
-    x: Range<usize>,
-    m: Range<usize>,
-    a: Range<usize>,
-    s: Range<usize>,
+    x: Interval<usize>,
+    m: Interval<usize>,
+    a: Interval<usize>,
+    s: Interval<usize>,
 }
 
 impl PartRange {
@@ -216,46 +258,46 @@ impl PartRange {
         self.x.len() * self.m.len() * self.a.len() * self.s.len()
     }
 
-    //Return 2 ranges
+    fn cat(&self, c: Category) -> Interval<usize> {
+        match c {
+            Category::Xcool => self.x,
+            Category::Music => self.m,
+            Category::Aero => self.a,
+            Category::Shiny => self.s,
+        }
+    }
+
+    fn cat_mut(&mut self, c: Category) -> &mut Interval<usize> {
+        match c {
+            Category::Xcool => &mut self.x,
+            Category::Music => &mut self.m,
+            Category::Aero => &mut self.a,
+            Category::Shiny => &mut self.s,
+        }
+    }
+
+    //Return 2 optional ranges
     //The left one applies the condition, the right one does not
-    fn split(&self, c: Category, sup: bool, value: usize) -> (PartRange, PartRange) {
-        let mut good = self.clone();
-        let mut bad = self.clone();
-        match (c, sup) {
-            (Category::Xcool, true) => {
-                good.x.start = value + 1;
-                bad.x.end = value + 1;
-            }
-            (Category::Xcool, false) => {
-                good.x.end = value;
-                bad.x.start = value;
-            }
-            (Category::Music, true) => {
-                good.m.start = value + 1;
-                bad.m.end = value + 1;
-            }
-            (Category::Music, false) => {
-                good.m.end = value;
-                bad.m.start = value;
-            }
-            (Category::Aero, true) => {
-                good.a.start = value + 1;
-                bad.a.end = value + 1;
-            }
-            (Category::Aero, false) => {
-                good.a.end = value;
-                bad.a.start = value;
-            }
-            (Category::Shiny, true) => {
-                good.s.start = value + 1;
-                bad.s.end = value + 1;
-            }
-            (Category::Shiny, false) => {
-                good.s.end = value;
-                bad.s.start = value;
-            }
+    fn split(
+        &self,
+        c: Category,
+        sup: bool,
+        value: usize,
+    ) -> (Option<PartRange>, Option<PartRange>) {
+        let at: usize = if sup { value + 1 } else { value };
+        let (below, above) = self.cat(c).split_at(at);
+        let with = |inter: Option<Interval<usize>>| {
+            inter.map(|inter| {
+                let mut range = self.clone();
+                *range.cat_mut(c) = inter;
+                range
+            })
+        };
+        if sup {
+            (with(above), with(below))
+        } else {
+            (with(below), with(above))
         }
-        (good, bad)
     }
 }
 
@@ -297,10 +339,10 @@ struct XmasSorter {
 impl XmasSorter {
     fn valid_parts(&self) -> usize {
         let start_range: PartRange = PartRange {
-            x: 1..4001,
-            m: 1..4001,
-            a: 1..4001,
-            s: 1..4001,
+            x: Interval::closed(1, 4000),
+            m: Interval::closed(1, 4000),
+            a: Interval::closed(1, 4000),
+            s: Interval::closed(1, 4000),
         };
 
         let mut valid_ranges: Vec<PartRange> = Vec::new();
@@ -333,6 +375,17 @@ impl XmasSorter {
 
         sum
     }
+
+    fn to_dot(&self) -> String {
+        let mut dot: Dot = Dot::directed();
+        dot.node("A", "A", Shape::DoubleCircle)
+            .node("R", "R", Shape::Circle);
+        let mut ids: Vec<&String> = self.workflows.keys().collect();
+        ids.sort();
+        ids.into_iter()
+            .for_each(|id| self.workflows[id].add_to_dot(&mut dot));
+        dot.to_string()
+    }
 }
 
 impl FromStr for XmasSorter {
@@ -358,6 +411,10 @@ fn main() {
     let now = std::time::Instant::now();
     let s = util::file_as_string("aoc_2023/input/day_19.txt").expect("Cannot open input file");
     let sorter: XmasSorter = s.parse().unwrap();
+    if util::dot::requested() {
+        print!("{}", sorter.to_dot());
+        return;
+    }
     println!(
         "Part1: The sum of the ratings of the accepted part is {}",
         sorter.ratings()
@@ -398,6 +455,13 @@ hdj{m>838:A,pv}
         assert_eq!(sorter.ratings(), 19114);
     }
     #[test]
+    fn dot_view() {
+        let sorter: XmasSorter = EXAMPLE_1.parse().unwrap();
+        let dot: String = sorter.to_dot();
+        assert!(dot.contains("\"in\" -> \"px\" [label=\"s<1351\"];"));
+        assert!(dot.contains("\"in\" -> \"qqz\";"));
+    }
+    #[test]
     fn part_2() {
         let sorter: XmasSorter = EXAMPLE_1.parse().unwrap();
         assert_eq!(sorter.valid_parts(), 167409079868000);
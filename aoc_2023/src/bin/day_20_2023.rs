@@ -7,6 +7,7 @@ use nom::IResult;
 use nom::Parser;
 use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
+use util::dot::{Dot, Shape};
 
 #[derive(Clone, Debug)]
 struct Signal {
@@ -31,6 +32,14 @@ impl ModuleType {
             _ => panic!("Invalid Module Type [{opt_c:?}]"),
         }
     }
+
+    fn shape(&self) -> Shape {
+        match self {
+            ModuleType::Broadcast => Shape::Diamond,
+            ModuleType::FlipFlop => Shape::Box,
+            ModuleType::Conjunction => Shape::Trapezium,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -148,6 +157,24 @@ impl PulseSystem {
 
         low_count * high_count
     }
+
+    //Flip-flops are boxes, conjunctions trapeziums, and the untyped outputs plain text
+    fn to_dot(&self) -> String {
+        let mut dot: Dot = Dot::directed();
+        let mut modules: Vec<&Module> = self.modules.values().collect();
+        modules.sort_by(|a, b| a.id.cmp(&b.id));
+        for module in modules {
+            dot.node(&module.id, &module.id, module.mod_type.shape());
+            for dest in &module.dest {
+                if !self.modules.contains_key(dest) {
+                    dot.node(dest, dest, Shape::Plaintext);
+                }
+                dot.edge(&module.id, dest);
+            }
+        }
+        dot.to_string()
+    }
+
     fn reset(&mut self) {
         //Set all flip_flop to off
         self.modules.values_mut().for_each(|m| m.on = false);
@@ -241,6 +268,10 @@ fn main() {
     let now = std::time::Instant::now();
     let s = util::file_as_string("aoc_2023/input/day_20.txt").expect("Cannot open input file");
     let mut system: PulseSystem = s.parse().unwrap();
+    if util::dot::requested() {
+        print!("{}", system.to_dot());
+        return;
+    }
     println!(
         "Part1: After pushing the button 1000 times, the product is {}",
         system.push_times(1000)
@@ -279,4 +310,11 @@ mod tests {
         let mut system: PulseSystem = EXAMPLE_2.parse().unwrap();
         assert_eq!(system.push_times(1000), 11687500);
     }
+    #[test]
+    fn dot_view() {
+        let system: PulseSystem = EXAMPLE_2.parse().unwrap();
+        let dot: String = system.to_dot();
+        assert!(dot.contains("\"con\" [label=\"con\", shape=trapezium];"));
+        assert!(dot.contains("\"output\" [label=\"output\", shape=plaintext];"));
+    }
 }
@@ -5,12 +5,12 @@ use nom::Parser;
 use std::collections::HashSet;
 use std::str::FromStr;
 use util::basic_parser::parse_pos3;
-use util::coord::Pos3;
+use util::vector::Vector;
 
 #[derive(Copy, Clone, Debug)]
 struct Brick {
-    start: Pos3,
-    end: Pos3,
+    start: Vector<usize, 3>,
+    end: Vector<usize, 3>,
 }
 
 impl Brick {
@@ -18,25 +18,25 @@ impl Brick {
         let other_parts = other.parts();
         self.parts()
             .iter()
-            .any(|&Pos3(x, y, z)| other_parts.contains(&Pos3(x, y, z + 1)))
+            .any(|&Vector([x, y, z])| other_parts.contains(&Vector([x, y, z + 1])))
     }
 
     fn lower(&self) -> Brick {
-        let Pos3(x, y, z): Pos3 = self.start;
-        let Pos3(i, j, k): Pos3 = self.end;
+        let Vector([x, y, z]): Vector<usize, 3> = self.start;
+        let Vector([i, j, k]): Vector<usize, 3> = self.end;
 
         Brick {
-            start: Pos3(x, y, z - 1),
-            end: Pos3(i, j, k - 1),
+            start: Vector([x, y, z - 1]),
+            end: Vector([i, j, k - 1]),
         }
     }
 
-    fn parts(&self) -> Vec<Pos3> {
-        let mut parts: Vec<Pos3> = Vec::new();
-        for z in self.start.2..=self.end.2 {
-            for y in self.start.1..=self.end.1 {
-                for x in self.start.0..=self.end.0 {
-                    parts.push(Pos3(x, y, z))
+    fn parts(&self) -> Vec<Vector<usize, 3>> {
+        let mut parts: Vec<Vector<usize, 3>> = Vec::new();
+        for z in self.start[2]..=self.end[2] {
+            for y in self.start[1]..=self.end[1] {
+                for x in self.start[0]..=self.end[0] {
+                    parts.push(Vector([x, y, z]))
                 }
             }
         }
@@ -142,9 +142,9 @@ impl BrickPile {
     fn stack_bricks(&self) -> Vec<Brick> {
         let mut sorted_pile = self.pile.clone();
         //Sort by lowest altitude
-        sorted_pile.sort_by(|a, b| a.start.2.cmp(&b.start.2));
+        sorted_pile.sort_by(|a, b| a.start[2].cmp(&b.start[2]));
 
-        let mut all_parts: HashSet<Pos3> = HashSet::new();
+        let mut all_parts: HashSet<Vector<usize, 3>> = HashSet::new();
 
         let mut final_pile: Vec<Brick> = Vec::new();
         for b in sorted_pile.iter() {
@@ -152,7 +152,7 @@ impl BrickPile {
             let mut parts = b.parts();
             while !parts
                 .iter()
-                .any(|&Pos3(x, y, z)| z == 1 || all_parts.contains(&Pos3(x, y, z - 1)))
+                .any(|&Vector([x, y, z])| z == 1 || all_parts.contains(&Vector([x, y, z - 1])))
             {
                 br = br.lower();
                 parts = br.parts();
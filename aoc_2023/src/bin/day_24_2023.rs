@@ -7,7 +7,8 @@ use nom::Parser;
 use std::cmp::Ordering;
 use std::str::FromStr;
 use util::basic_parser::parse_isize;
-use util::coord::{Pos3I, PosI};
+use util::coord::PosI;
+use util::vector::Vector;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 struct HailStone2D {
@@ -102,29 +103,29 @@ impl HailStone2D {
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 struct HailStone {
-    pos: Pos3I,
-    vel: Pos3I,
+    pos: Vector<isize, 3>,
+    vel: Vector<isize, 3>,
 }
 
 impl HailStone {
     fn as_xy(&self) -> HailStone2D {
         HailStone2D {
-            pos: PosI(self.pos.0, self.pos.1),
-            vel: PosI(self.vel.0, self.vel.1),
+            pos: PosI(self.pos[0], self.pos[1]),
+            vel: PosI(self.vel[0], self.vel[1]),
         }
     }
 
     fn as_xz(&self) -> HailStone2D {
         HailStone2D {
-            pos: PosI(self.pos.0, self.pos.2),
-            vel: PosI(self.vel.0, self.vel.2),
+            pos: PosI(self.pos[0], self.pos[2]),
+            vel: PosI(self.vel[0], self.vel[2]),
         }
     }
 
     fn as_yz(&self) -> HailStone2D {
         HailStone2D {
-            pos: PosI(self.pos.1, self.pos.2),
-            vel: PosI(self.vel.1, self.vel.2),
+            pos: PosI(self.pos[1], self.pos[2]),
+            vel: PosI(self.vel[1], self.vel[2]),
         }
     }
 }
@@ -133,9 +134,9 @@ impl FromStr for HailStone {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_pos3i(s: &str) -> IResult<&str, Pos3I> {
+        fn parse_pos3i(s: &str) -> IResult<&str, Vector<isize, 3>> {
             let (s, coords) = separated_list1(tag(", "), parse_isize).parse(s)?;
-            Ok((s, Pos3I(coords[0], coords[1], coords[2])))
+            Ok((s, Vector([coords[0], coords[1], coords[2]])))
         }
         fn parse_hailstone(s: &str) -> IResult<&str, HailStone> {
             let (s, (pos, vel)) = separated_pair(parse_pos3i, tag(" @ "), parse_pos3i).parse(s)?;
@@ -156,8 +157,8 @@ impl HailCloud {
             .stones
             .iter()
             .map(|s| HailStone2D {
-                pos: PosI(s.pos.0, s.pos.1),
-                vel: PosI(s.vel.0, s.vel.1),
+                pos: PosI(s.pos[0], s.pos[1]),
+                vel: PosI(s.vel[0], s.vel[1]),
             })
             .collect();
         let mut nb_intersect: usize = 0;
@@ -2,6 +2,7 @@ use fxhash::FxHashSet;
 use rayon::prelude::*;
 use std::collections::BTreeSet;
 use std::str::FromStr;
+use util::bitgrid::BitGrid;
 use util::coord::Pos;
 use util::orientation::Dir;
 
@@ -53,21 +54,17 @@ impl Patrol {
         }
     }
 
-    fn next_pos(Pos(x, y): Pos, dir: Dir, Pos(obs_x, obs_y): Pos) -> (Pos, Dir) {
-        match dir {
-            Dir::North => (Pos(x, obs_y + 1), Dir::East),
-            Dir::East => (Pos(obs_x - 1, y), Dir::South),
-            Dir::South => (Pos(x, obs_y - 1), Dir::West),
-            Dir::West => (Pos(obs_x + 1, y), Dir::North),
-        }
+    //The obstacle is on the guard row or column, the guard stops right before it and turns
+    fn next_pos(dir: Dir, obstacle: Pos) -> (Pos, Dir) {
+        (dir.half_turn().step(obstacle).unwrap(), dir.turn_right())
     }
 
-    fn new_visited_pos(Pos(x, y): Pos, dir: Dir, Pos(n_x, n_y): Pos) -> Vec<Pos> {
-        match dir {
-            Dir::North => (n_y..=y).map(|h| Pos(x, h)).collect(),
-            Dir::East => (x..=n_x).map(|h| Pos(h, y)).collect(),
-            Dir::South => (y..=n_y).map(|h| Pos(x, h)).collect(),
-            Dir::West => (n_x..=x).map(|h| Pos(h, y)).collect(),
+    //Marks the tiles from the position to the next one, a whole row segment at once
+    fn visit(visited: &mut BitGrid, Pos(x, y): Pos, Pos(n_x, n_y): Pos) {
+        if y == n_y {
+            visited.row_mut(y).set_range(x.min(n_x), x.max(n_x) + 1);
+        } else {
+            (y.min(n_y)..=y.max(n_y)).for_each(|h| visited.set(Pos(x, h), true));
         }
     }
 
@@ -80,26 +77,24 @@ impl Patrol {
         }
     }
 
-    fn visited(&self) -> FxHashSet<Pos> {
+    fn visited(&self) -> BitGrid {
         let mut pos: Pos = self.pos;
         let mut dir: Dir = self.dir;
-        let mut visited: FxHashSet<Pos> = FxHashSet::default();
-        visited.insert(pos);
+        let mut visited: BitGrid = BitGrid::new(self.max_x, self.max_y);
+        visited.set(pos, true);
 
         while let Some(obs) = self.next_obstacle(pos, dir) {
             //Compute next position
-            let (next_pos, next_dir) = Patrol::next_pos(pos, dir, obs);
+            let (next_pos, next_dir) = Patrol::next_pos(dir, obs);
             //Visit all position between old position and new one
-            let new_visited = Patrol::new_visited_pos(pos, dir, next_pos);
-            visited.extend(new_visited);
+            Patrol::visit(&mut visited, pos, next_pos);
             //Move to next pos
             pos = next_pos;
             dir = next_dir;
         }
         //Visit all position between old position and getting out
         let out: Pos = self.last_pos_in(pos, dir);
-        let new_visited = Patrol::new_visited_pos(pos, dir, out);
-        visited.extend(new_visited);
+        Patrol::visit(&mut visited, pos, out);
         visited
     }
 
@@ -110,7 +105,7 @@ impl Patrol {
 
         while let Some(obs) = self.next_obstacle(pos, dir) {
             //Compute next position
-            let (next_pos, next_dir) = Patrol::next_pos(pos, dir, obs);
+            let (next_pos, next_dir) = Patrol::next_pos(dir, obs);
             //Check if we're in a loop
             if !visited.insert((next_pos, next_dir)) {
                 return true;
@@ -123,7 +118,7 @@ impl Patrol {
     }
 
     fn solve(&self) -> (usize, usize) {
-        let visited: FxHashSet<Pos> = self.visited();
+        let visited: Vec<Pos> = self.visited().ones().collect();
         let nb_loop: usize = visited
             .par_iter()
             .filter(|&&p| self.with_new_obstacle(p).is_loop())
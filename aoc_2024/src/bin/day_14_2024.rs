@@ -17,10 +17,8 @@ struct Robot {
 
 impl Robot {
     fn motion(&mut self, max_x: isize, max_y: isize, times: isize) {
-        // We add a constant to avoid negative coordinates
-        let new_x = (self.pos.0 + times * (self.vel.0 + max_x)) % max_x;
-        let new_y = (self.pos.1 + times * (self.vel.1 + max_y)) % max_y;
-        self.pos = PosI(new_x, new_y)
+        let delta: PosI = PosI(times * self.vel.0, times * self.vel.1);
+        self.pos = self.pos.add_torus(delta, max_x, max_y)
     }
 }
 
@@ -30,13 +30,9 @@ struct WideWarehouse {
 }
 
 impl WideWarehouse {
-    fn pos_in_front(Pos(x, y): Pos, dir: Dir) -> Pos {
-        match dir {
-            Dir::North => Pos(x, y - 1),
-            Dir::East => Pos(x + 1, y),
-            Dir::South => Pos(x, y + 1),
-            Dir::West => Pos(x - 1, y),
-        }
+    //The warehouse is surrounded by walls, the robot never steps off the grid
+    fn pos_in_front(pos: Pos, dir: Dir) -> Pos {
+        dir.step(pos).unwrap()
     }
 
     fn move_box(grid: &mut [Vec<WideTile>], Pos(x, y): Pos, dir: Dir) {
@@ -191,13 +187,9 @@ struct Warehouse {
     robot_moves: Vec<Dir>,
 }
 impl Warehouse {
-    fn pos_in_front(Pos(x, y): Pos, dir: Dir) -> Pos {
-        match dir {
-            Dir::North => Pos(x, y - 1),
-            Dir::East => Pos(x + 1, y),
-            Dir::South => Pos(x, y + 1),
-            Dir::West => Pos(x - 1, y),
-        }
+    //The warehouse is surrounded by walls, the robot never steps off the grid
+    fn pos_in_front(pos: Pos, dir: Dir) -> Pos {
+        dir.step(pos).unwrap()
     }
 
     fn gps_coordinates(&self) -> usize {
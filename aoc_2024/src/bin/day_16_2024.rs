@@ -26,14 +26,6 @@ impl ReindeerNode {
             };
             h + pos.distance(end)
         }
-        fn ahead(Pos(x, y): Pos, dir: Dir) -> Pos {
-            match dir {
-                Dir::North => Pos(x, y - 1),
-                Dir::East => Pos(x + 1, y),
-                Dir::South => Pos(x, y + 1),
-                Dir::West => Pos(x - 1, y),
-            }
-        }
         vec![
             (self.dir, 1),
             (self.dir.turn_left(), 1001),
@@ -41,7 +33,7 @@ impl ReindeerNode {
         ]
         .into_iter()
         .map(|(dir, add_score)| {
-            let ahead_pos = ahead(self.pos, dir);
+            let ahead_pos = dir.step(self.pos).unwrap();
             let mut ahead_visited: FxHashSet<Pos> = self.visited.clone();
             ahead_visited.insert(ahead_pos);
             ReindeerNode {
@@ -1,102 +1,31 @@
+use itertools::Itertools;
+use nom::character::complete::{alpha1, char};
+use nom::sequence::separated_pair;
+use nom::IResult;
 use nom::Parser;
 use std::str::FromStr;
-
-use fxhash::{FxHashMap, FxHashSet};
-use itertools::Itertools;
-use nom::character::complete::anychar;
-use nom::multi::count;
-use nom::{character::complete::char, sequence::separated_pair, IResult};
+use util::graph::{Graph, NodeMap};
 
 struct LanParty {
-    pairs: Vec<(usize, usize)>,
-    connections: FxHashMap<usize, FxHashSet<usize>>,
+    computers: NodeMap<String>,
+    links: Graph,
 }
 
 impl LanParty {
     fn t_triplets(&self) -> usize {
-        let triplets: FxHashSet<Vec<usize>> = self
-            .pairs
+        self.links
+            .triangles()
             .iter()
-            .flat_map(|(a, b)| {
-                if (a / 26) == 19 || (b / 26) == 19 {
-                    let a_candidates: &FxHashSet<usize> = self.connections.get(a).unwrap();
-                    let b_candidates: &FxHashSet<usize> = self.connections.get(b).unwrap();
-                    let inter = a_candidates.intersection(b_candidates);
-                    inter
-                        .map(|c| {
-                            let mut v = vec![*a, *b, *c];
-                            v.sort();
-                            v
-                        })
-                        .collect()
-                } else {
-                    Vec::new()
-                }
-            })
-            .collect();
-        triplets.len()
+            .filter(|t| t.iter().any(|&c| self.computers.name(c).starts_with('t')))
+            .count()
     }
 
     fn biggest_lan(&self) -> String {
-        fn bron_kerbosch(
-            clique: FxHashSet<usize>,
-            mut pot: FxHashSet<usize>,
-            mut ex: FxHashSet<usize>,
-            connections: &FxHashMap<usize, FxHashSet<usize>>,
-            biggest_lan: &mut FxHashSet<usize>,
-        ) {
-            if biggest_lan.len() > (clique.len() + pot.iter().len()) {
-                //We already have a bigger clique than the potential biggest, prune this branch
-                return;
-            }
-            if pot.is_empty() && ex.is_empty() && clique.len() > biggest_lan.len() {
-                *biggest_lan = clique.clone();
-            }
-            // Choose a pivot with the maximum degree in P ∪ X
-            let pivot: Option<usize> = pot
-                .union(&ex)
-                .max_by_key(|&v| connections.get(v).map_or(0, |neighbors| neighbors.len()))
-                .copied();
-
-            if let Some(pivot_vertex) = pivot {
-                let neighbors: FxHashSet<usize> =
-                    connections.get(&pivot_vertex).cloned().unwrap_or_default();
-                let candidates: Vec<usize> = pot.difference(&neighbors).copied().collect();
-
-                for v in candidates {
-                    let mut new_clique: FxHashSet<usize> = clique.clone();
-                    new_clique.insert(v);
-                    let ngbs: &FxHashSet<usize> = connections.get(&v).unwrap();
-                    let new_pot: FxHashSet<usize> = pot.intersection(ngbs).copied().collect();
-                    let new_ex: FxHashSet<usize> = ex.intersection(ngbs).copied().collect();
-                    bron_kerbosch(new_clique, new_pot, new_ex, connections, biggest_lan);
-                    pot.remove(&v);
-                    ex.insert(v);
-                }
-            }
-        }
-        let mut biggest_lan: FxHashSet<usize> = FxHashSet::default();
-        let clique: FxHashSet<usize> = FxHashSet::default();
-        let potential: FxHashSet<usize> = self.connections.keys().copied().collect();
-        let excluded: FxHashSet<usize> = FxHashSet::default();
-        bron_kerbosch(
-            clique,
-            potential,
-            excluded,
-            &self.connections,
-            &mut biggest_lan,
-        );
-
-        biggest_lan
+        self.links
+            .max_clique()
             .into_iter()
+            .map(|c| self.computers.name(c))
             .sorted()
-            .map(|n| {
-                format!(
-                    "{}{}",
-                    ((n / 26) as u8 + b'a') as char,
-                    ((n % 26) as u8 + b'a') as char
-                )
-            })
             .join(",")
     }
 }
@@ -105,28 +34,18 @@ impl FromStr for LanParty {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_pair(s: &str) -> IResult<&str, (usize, usize)> {
-            let (s, (a, b)) =
-                separated_pair(count(anychar, 2), char('-'), count(anychar, 2)).parse(s)?;
-            Ok((s, (to_usize(&a), to_usize(&b))))
+        fn parse_pair(s: &str) -> IResult<&str, (&str, &str)> {
+            separated_pair(alpha1, char('-'), alpha1).parse(s)
         }
 
-        fn to_usize(chars: &[char]) -> usize {
-            (chars[0] as usize - 'a' as usize) * 26 + chars[1] as usize - 'a' as usize
+        let mut computers: NodeMap<String> = NodeMap::new();
+        let mut links: Graph = Graph::default();
+        for l in s.lines() {
+            let (a, b) = parse_pair(l).unwrap().1;
+            links.add_undirected_edge(computers.id(a.to_string()), computers.id(b.to_string()));
         }
 
-        let mut connections: FxHashMap<usize, FxHashSet<usize>> = FxHashMap::default();
-        let pairs: Vec<(usize, usize)> = s
-            .lines()
-            .map(|l| {
-                let (a, b) = parse_pair(l).unwrap().1;
-                connections.entry(a).or_default().insert(b);
-                connections.entry(b).or_default().insert(a);
-                (a, b)
-            })
-            .collect();
-
-        Ok(LanParty { pairs, connections })
+        Ok(LanParty { computers, links })
     }
 }
 
@@ -2,14 +2,13 @@ use nom::IResult;
 use nom::Parser;
 use nom::character::char;
 use nom::sequence::separated_pair;
-use std::cmp::{max, min};
-use std::ops::RangeInclusive;
 use std::str::FromStr;
 use util::basic_parser::parse_usize;
+use util::interval::{Interval, IntervalSet};
 use util::split_blocks;
 
 struct Cafeteria {
-    fresh_ranges: Vec<RangeInclusive<usize>>,
+    fresh_ranges: IntervalSet<usize>,
     ingredients: Vec<usize>,
 }
 
@@ -17,32 +16,12 @@ impl Cafeteria {
     fn nb_fresh_ingredients(&self) -> usize {
         self.ingredients
             .iter()
-            .filter(|i| self.fresh_ranges.iter().any(|r| r.contains(i)))
+            .filter(|&&i| self.fresh_ranges.contains(i))
             .count()
     }
 
     fn nb_fresh_ids(&self) -> usize {
-        let mut final_ranges: Vec<RangeInclusive<usize>> = Vec::new();
-        for r in &self.fresh_ranges {
-            let mut merged_ranges: Vec<RangeInclusive<usize>> = Vec::new();
-            let mut current: RangeInclusive<usize> = r.clone();
-
-            while let Some(other) = final_ranges.pop() {
-                // The range are disjoint, store the other range
-                if current.start() > other.end() || current.end() < other.start() {
-                    merged_ranges.push(other);
-                } else {
-                    // Merging the ranges
-                    current =
-                        min(*current.start(), *other.start())..=max(*current.end(), *other.end());
-                }
-            }
-            //At the end, put your new built range in the new ranges
-            merged_ranges.push(current);
-
-            final_ranges = merged_ranges;
-        }
-        final_ranges.iter().map(|r| r.end() - r.start() + 1).sum()
+        self.fresh_ranges.len()
     }
 }
 
@@ -50,13 +29,13 @@ impl FromStr for Cafeteria {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_range(s: &str) -> IResult<&str, RangeInclusive<usize>> {
+        fn parse_range(s: &str) -> IResult<&str, Interval<usize>> {
             let (s, (l, r)) = separated_pair(parse_usize, char('-'), parse_usize).parse(s)?;
-            Ok((s, l..=r))
+            Ok((s, Interval::closed(l, r)))
         }
 
         let blocks = split_blocks(s);
-        let fresh_ranges: Vec<RangeInclusive<usize>> = blocks[0]
+        let fresh_ranges: IntervalSet<usize> = blocks[0]
             .lines()
             .map(|l| parse_range(l).unwrap().1)
             .collect();
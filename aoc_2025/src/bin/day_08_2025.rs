@@ -3,7 +3,8 @@ use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::str::FromStr;
 use util::basic_parser::parse_pos3;
-use util::coord::Pos3;
+use util::graph::UnionFind;
+use util::vector::Vector;
 
 #[derive(Debug, Eq, PartialEq)]
 struct Junction {
@@ -13,8 +14,8 @@ struct Junction {
 }
 
 impl Junction {
-    fn from_pos(pos: &[(usize, &Pos3)]) -> Junction {
-        let dist: usize = pos[0].1.distance_squared(*pos[1].1);
+    fn from_pos(pos: &[(usize, &Vector<usize, 3>)]) -> Junction {
+        let dist: usize = pos[0].1.distance_squared(pos[1].1);
         Junction {
             a: pos[0].0,
             b: pos[1].0,
@@ -34,55 +35,9 @@ impl PartialOrd for Junction {
         Some(self.cmp(other))
     }
 }
-struct DisjointSet {
-    parents: Vec<usize>,
-    sizes: Vec<usize>,
-    nb_sets: usize,
-}
-
-impl DisjointSet {
-    fn new(nb_sets: usize) -> DisjointSet {
-        let parents: Vec<usize> = (0..nb_sets).collect();
-        let sizes: Vec<usize> = vec![1; nb_sets];
-        DisjointSet {
-            parents,
-            sizes,
-            nb_sets,
-        }
-    }
-
-    fn join(&mut self, a: usize, b: usize) {
-        let prt_a: usize = self.parents[a];
-        let prt_b: usize = self.parents[b];
-
-        let (tgt_grp, src_grp) = match prt_a.cmp(&prt_b) {
-            Ordering::Less => (prt_a, prt_b),
-            Ordering::Greater => (prt_b, prt_a),
-            Ordering::Equal => return, // Nothing to do
-        };
-
-        for idx in self.parents.iter_mut() {
-            if *idx == src_grp {
-                *idx = tgt_grp
-            }
-        }
-
-        self.sizes[tgt_grp] += self.sizes[src_grp];
-        self.sizes[src_grp] = 0;
-        self.nb_sets -= 1;
-    }
-
-    fn top_3(&self) -> usize {
-        self.sizes.iter().sorted_unstable().rev().take(3).product()
-    }
-
-    fn is_complete(&self) -> bool {
-        self.nb_sets == 1
-    }
-}
 
 struct Playground {
-    boxes: Vec<Pos3>,
+    boxes: Vec<Vector<usize, 3>>,
 }
 
 impl Playground {
@@ -96,22 +51,28 @@ impl Playground {
             .map(|pair| Junction::from_pos(&pair))
             .collect();
 
-        let mut set: DisjointSet = DisjointSet::new(self.boxes.len());
+        let mut set: UnionFind = UnionFind::new(self.boxes.len());
         // Apply the first nb_junctions junctions
         for _ in 0..nb_junctions {
             let junction = queue.pop().unwrap();
-            set.join(junction.a, junction.b);
+            set.union(junction.a, junction.b);
         }
 
-        let largest_circuit_product: usize = set.top_3();
+        let largest_circuit_product: usize = set
+            .set_sizes()
+            .into_iter()
+            .sorted_unstable()
+            .rev()
+            .take(3)
+            .product();
 
         // Apply the remaining junctions until we link all boxes
         while let Some(junction) = queue.pop() {
-            set.join(junction.a, junction.b);
-            if set.is_complete() {
+            set.union(junction.a, junction.b);
+            if set.nb_sets() == 1 {
                 return (
                     largest_circuit_product,
-                    self.boxes[junction.a].0 * self.boxes[junction.b].0,
+                    self.boxes[junction.a][0] * self.boxes[junction.b][0],
                 );
             }
         }
@@ -123,7 +84,7 @@ impl FromStr for Playground {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let boxes: Vec<Pos3> = s.lines().map(|l| parse_pos3(l).unwrap().1).collect();
+        let boxes: Vec<Vector<usize, 3>> = s.lines().map(|l| parse_pos3(l).unwrap().1).collect();
 
         Ok(Playground { boxes })
     }
@@ -1,75 +1,7 @@
-use std::ops::{Range, RangeInclusive};
 use std::str::FromStr;
 use util::basic_parser::parse_pos;
-use util::coord::Pos;
-
-#[derive(Copy, Clone, Debug)]
-struct PolygonSide {
-    vertical: bool,
-    rank: usize,
-    range: (usize, usize),
-}
-
-impl PolygonSide {
-    fn from_pair(Pos(x_a, y_a): Pos, Pos(x_b, y_b): Pos) -> PolygonSide {
-        if x_a == x_b {
-            let range = if y_a > y_b { (y_b, y_a) } else { (y_a, y_b) };
-            PolygonSide {
-                vertical: true,
-                rank: x_a,
-                range,
-            }
-        } else {
-            let range = if x_a > x_b { (x_b, x_a) } else { (x_a, x_b) };
-            PolygonSide {
-                vertical: false,
-                rank: y_a,
-                range,
-            }
-        }
-    }
-
-    fn full_range(&self) -> RangeInclusive<usize> {
-        self.range.0..=self.range.1
-    }
-
-    fn semi_range(&self) -> Range<usize> {
-        self.range.0..self.range.1
-    }
-
-    fn exclusive_range(&self) -> RangeInclusive<usize> {
-        (self.range.0 + 1)..=(self.range.1 - 1)
-    }
-
-    fn contains(&self, Pos(x, y): Pos) -> bool {
-        if self.vertical {
-            x == self.rank && self.full_range().contains(&y)
-        } else {
-            y == self.rank && self.full_range().contains(&x)
-        }
-    }
-
-    fn is_before(&self, Pos(x, y): Pos) -> bool {
-        if self.vertical {
-            x > self.rank && self.semi_range().contains(&y)
-        } else {
-            y > self.rank && self.semi_range().contains(&x)
-        }
-    }
-
-    fn crosses(&self, other: PolygonSide, lower: bool) -> bool {
-        // The sides have to cross anywhere but on either of the extremities of OTHER
-        // The self side must go inside the rectangle to be considered crossing
-        let going_inside: bool = if lower {
-            self.range.0 < other.rank
-        } else {
-            self.range.1 > other.rank
-        };
-        other.exclusive_range().contains(&self.rank)
-            && self.full_range().contains(&other.rank)
-            && going_inside
-    }
-}
+use util::coord::{Pos, PosI};
+use util::polygon::RectilinearPolygon;
 
 struct MovieTheater {
     tiles: Vec<Pos>,
@@ -77,58 +9,16 @@ struct MovieTheater {
 
 impl MovieTheater {
     fn largest_rectangles(&self) -> (usize, usize) {
-        fn is_inside(pos: Pos, rows: &[PolygonSide], cols: &[PolygonSide]) -> bool {
-            let mut cross_rows: usize = 0;
-            let mut cross_cols: usize = 0;
-
-            for r in rows {
-                if r.contains(pos) {
-                    return true;
-                } else if r.is_before(pos) {
-                    cross_rows += 1;
-                }
-            }
-
-            for c in cols {
-                if c.contains(pos) {
-                    return true;
-                } else if c.is_before(pos) {
-                    cross_cols += 1;
-                }
-            }
-            cross_cols % 2 == 1 && cross_rows % 2 == 1
-        }
-
-        fn crosses_side(
-            side: PolygonSide,
-            rows: &[PolygonSide],
-            cols: &[PolygonSide],
-            lower: bool,
-        ) -> bool {
-            if side.vertical {
-                rows.iter().any(|r| r.crosses(side, lower))
-            } else {
-                cols.iter().any(|c| c.crosses(side, lower))
-            }
-        }
+        let vertices: Vec<PosI> = self
+            .tiles
+            .iter()
+            .map(|&Pos(x, y)| PosI(x as isize, y as isize))
+            .collect();
+        let polygon: RectilinearPolygon = RectilinearPolygon::new(&vertices);
 
         let mut largest_rectangle = 0;
         let mut largest_tiled_rectangle = 0;
 
-        // Build polygon sides
-        let mut rows: Vec<PolygonSide> = Vec::new();
-        let mut cols: Vec<PolygonSide> = Vec::new();
-        let mut tiles_looped: Vec<Pos> = self.tiles.clone();
-        tiles_looped.push(self.tiles[0]);
-        tiles_looped.windows(2).for_each(|win| {
-            let side: PolygonSide = PolygonSide::from_pair(win[0], win[1]);
-            if side.vertical {
-                cols.push(side);
-            } else {
-                rows.push(side);
-            }
-        });
-
         for (i, &tile) in self.tiles.iter().enumerate() {
             for j in (i + 1)..self.tiles.len() {
                 let other: Pos = self.tiles[j];
@@ -137,43 +27,7 @@ impl MovieTheater {
                     largest_rectangle = area;
                 }
 
-                // Check if rectangle is tiled
-                // First check both other corners are inside
-                let a: Pos = Pos(tile.0, other.1); // 'a' is on the same row as 'other'
-                let b: Pos = Pos(other.0, tile.1); // 'b' is on the same row as 'tile'
-
-                // Check no polygon side cross any of the 4 rectangle side
-                let (small_y, big_y) = if tile.1 > other.1 {
-                    (
-                        PolygonSide::from_pair(other, a),
-                        PolygonSide::from_pair(tile, b),
-                    )
-                } else {
-                    (
-                        PolygonSide::from_pair(tile, b),
-                        PolygonSide::from_pair(other, a),
-                    )
-                };
-
-                let (small_x, big_x) = if tile.0 > other.0 {
-                    (
-                        PolygonSide::from_pair(other, b),
-                        PolygonSide::from_pair(tile, a),
-                    )
-                } else {
-                    (
-                        PolygonSide::from_pair(tile, a),
-                        PolygonSide::from_pair(other, b),
-                    )
-                };
-
-                if area > largest_tiled_rectangle
-                    && is_inside(a, &rows, &cols)
-                    && is_inside(b, &rows, &cols)
-                    && !crosses_side(small_y, &rows, &cols, false)
-                    && !crosses_side(big_y, &rows, &cols, true)
-                    && !crosses_side(small_x, &rows, &cols, false)
-                    && !crosses_side(big_x, &rows, &cols, true)
+                if area > largest_tiled_rectangle && polygon.contains_rect(vertices[i], vertices[j])
                 {
                     largest_tiled_rectangle = area;
                 }
@@ -0,0 +1,101 @@
+use crate::chinese_remainders::{mod_inverse, mod_mul, modulo};
+
+//The function f(x) = a*x + b [modulus]
+//All operations go through mod_mul, so any modulus fitting in an i128 is safe
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct AffineMap {
+    a: i128,
+    b: i128,
+    modulus: i128,
+}
+
+impl AffineMap {
+    pub fn new(a: i128, b: i128, modulus: i128) -> Self {
+        AffineMap {
+            a: modulo(a, modulus),
+            b: modulo(b, modulus),
+            modulus,
+        }
+    }
+
+    pub fn identity(modulus: i128) -> Self {
+        AffineMap::new(1, 0, modulus)
+    }
+
+    pub fn coefficients(&self) -> (i128, i128) {
+        (self.a, self.b)
+    }
+
+    pub fn apply(&self, x: i128) -> i128 {
+        let ax: i128 = mod_mul(self.a, x, self.modulus);
+        modulo(ax - self.modulus + self.b, self.modulus)
+    }
+
+    //Apply self first, then other
+    pub fn then(&self, other: &AffineMap) -> AffineMap {
+        AffineMap {
+            a: mod_mul(other.a, self.a, self.modulus),
+            b: other.apply(self.b),
+            modulus: self.modulus,
+        }
+    }
+
+    //Only exists when a is invertible modulo the modulus
+    pub fn inverse(&self) -> Option<AffineMap> {
+        let inv: i128 = mod_inverse(self.a, self.modulus)?;
+        Some(AffineMap {
+            a: inv,
+            b: modulo(-mod_mul(inv, self.b, self.modulus), self.modulus),
+            modulus: self.modulus,
+        })
+    }
+
+    //The map applied k times, computed by squaring
+    pub fn pow(&self, mut k: u128) -> AffineMap {
+        let mut acc: AffineMap = AffineMap::identity(self.modulus);
+        let mut base: AffineMap = *self;
+        while k > 0 {
+            if k & 1 == 1 {
+                acc = acc.then(&base);
+            }
+            base = base.then(&base);
+            k >>= 1;
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose_and_invert() {
+        let f: AffineMap = AffineMap::new(3, 4, 11);
+        let g: AffineMap = AffineMap::new(-2, 7, 11);
+        assert_eq!(f.then(&g).apply(5), g.apply(f.apply(5)));
+        let inv: AffineMap = f.inverse().unwrap();
+        assert_eq!(inv.apply(f.apply(9)), 9);
+        assert_eq!(AffineMap::new(2, 0, 10).inverse(), None);
+    }
+
+    #[test]
+    fn repeated_application() {
+        let f: AffineMap = AffineMap::new(16807, 0, 2147483647);
+        let mut x: i128 = 65;
+        for _ in 0..5 {
+            x = f.apply(x);
+        }
+        assert_eq!(f.pow(5).apply(65), x);
+        assert_eq!(x, 1352636452);
+    }
+
+    #[test]
+    fn large_modulus() {
+        let m: i128 = i128::MAX;
+        let f: AffineMap = AffineMap::new(m - 1, m - 1, m);
+        //f(x) = -x - 1, so f twice is the identity
+        assert_eq!(f.pow(2), AffineMap::identity(m));
+        assert_eq!(f.apply(m - 1), 0);
+    }
+}
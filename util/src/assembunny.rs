@@ -0,0 +1,248 @@
+use crate::basic_parser::parse_isize;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, one_of};
+use nom::combinator::map;
+use nom::sequence::{preceded, separated_pair};
+use nom::IResult;
+use nom::Parser;
+use std::str::FromStr;
+
+pub const NB_REGS: usize = 4;
+
+#[derive(Debug, Copy, Clone)]
+enum Operand {
+    Reg(usize),
+    Val(isize),
+}
+
+impl Operand {
+    fn value(&self, regs: &[isize; NB_REGS]) -> isize {
+        match *self {
+            Operand::Reg(r) => regs[r],
+            Operand::Val(v) => v,
+        }
+    }
+}
+
+//Targets are kept as operands, a toggled instruction can end up writing to a value
+//In this case, the instruction is invalid and skipped
+#[derive(Debug, Copy, Clone)]
+enum Op {
+    Copy(Operand, Operand),
+    Increment(Operand),
+    Decrement(Operand),
+    JumpIfNotZero(Operand, Operand),
+    Toggle(Operand),
+    Out(Operand),
+}
+
+impl Op {
+    fn toggled(&self) -> Op {
+        match *self {
+            Op::Increment(x) => Op::Decrement(x),
+            Op::Decrement(x) | Op::Toggle(x) | Op::Out(x) => Op::Increment(x),
+            Op::JumpIfNotZero(x, y) => Op::Copy(x, y),
+            Op::Copy(x, y) => Op::JumpIfNotZero(x, y),
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn parse_operand(s: &str) -> IResult<&str, Operand> {
+            alt((
+                map(parse_isize, Operand::Val),
+                map(one_of("abcd"), |c| Operand::Reg((c as u8 - b'a') as usize)),
+            ))
+            .parse(s)
+        }
+        fn parse_pair(s: &str) -> IResult<&str, (Operand, Operand)> {
+            separated_pair(parse_operand, char(' '), parse_operand).parse(s)
+        }
+
+        alt((
+            map(preceded(tag("cpy "), parse_pair), |(x, y)| Op::Copy(x, y)),
+            map(preceded(tag("inc "), parse_operand), Op::Increment),
+            map(preceded(tag("dec "), parse_operand), Op::Decrement),
+            map(preceded(tag("jnz "), parse_pair), |(x, y)| {
+                Op::JumpIfNotZero(x, y)
+            }),
+            map(preceded(tag("tgl "), parse_operand), Op::Toggle),
+            map(preceded(tag("out "), parse_operand), Op::Out),
+        ))
+        .parse(s)
+        .map(|(_, op)| op)
+        .map_err(|_| ())
+    }
+}
+
+//Loops found by the compiler, run in a single step instead of one pass per unit
+#[derive(Debug, Copy, Clone)]
+enum Fused {
+    //"inc x, dec y, jnz y -2" in any order of the first two: x += y, y = 0
+    Add(usize, usize),
+    //"cpy s y" then the add loop of y into x, "dec z, jnz z -5": x += s * z, y = 0, z = 0
+    Mul(usize, Operand, usize, usize),
+}
+
+impl Fused {
+    fn len(&self) -> isize {
+        match self {
+            Fused::Add(..) => 3,
+            Fused::Mul(..) => 6,
+        }
+    }
+
+    //The loops only end when the counters are positive, otherwise the plain ops are run
+    fn apply(&self, regs: &mut [isize; NB_REGS]) -> bool {
+        match *self {
+            Fused::Add(x, y) if regs[y] > 0 => {
+                regs[x] += regs[y];
+                regs[y] = 0;
+            }
+            Fused::Mul(x, s, y, z) if s.value(regs) > 0 && regs[z] > 0 => {
+                regs[x] += s.value(regs) * regs[z];
+                regs[y] = 0;
+                regs[z] = 0;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+fn add_loop(ops: &[Op]) -> Option<(usize, usize)> {
+    match ops {
+        [Op::Increment(Operand::Reg(x)), Op::Decrement(Operand::Reg(y)), Op::JumpIfNotZero(Operand::Reg(j), Operand::Val(-2)), ..]
+        | [Op::Decrement(Operand::Reg(y)), Op::Increment(Operand::Reg(x)), Op::JumpIfNotZero(Operand::Reg(j), Operand::Val(-2)), ..]
+            if x != y && y == j =>
+        {
+            Some((*x, *y))
+        }
+        _ => None,
+    }
+}
+
+fn mul_loop(ops: &[Op]) -> Option<Fused> {
+    let [Op::Copy(s, Operand::Reg(y)), .., Op::Decrement(Operand::Reg(z)), Op::JumpIfNotZero(Operand::Reg(j), Operand::Val(-5))] =
+        ops.get(..6)?
+    else {
+        return None;
+    };
+    let (x, add_y) = add_loop(&ops[1..4])?;
+    let distinct: bool = *y == add_y && z == j && x != *z && *y != *z;
+    let source_kept: bool = !matches!(s, Operand::Reg(r) if [x, *y, *z].contains(r));
+    (distinct && source_kept).then_some(Fused::Mul(x, *s, *y, *z))
+}
+
+//The fused loop starting at each instruction, recomputed when a toggle changes the program
+//Jumps into the middle of a loop still land on the plain instructions
+fn compile(ops: &[Op]) -> Vec<Option<Fused>> {
+    (0..ops.len())
+        .map(|i| mul_loop(&ops[i..]).or_else(|| add_loop(&ops[i..]).map(|(x, y)| Fused::Add(x, y))))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Assembunny {
+    ops: Vec<Op>,
+}
+
+impl Assembunny {
+    //Run the program until it halts and return the final registers
+    pub fn run(&self, regs: [isize; NB_REGS]) -> [isize; NB_REGS] {
+        let mut regs: [isize; NB_REGS] = regs;
+        self.execute(&mut regs, usize::MAX);
+        regs
+    }
+
+    //Run the program until it halts or has emitted "len" values
+    pub fn signal(&self, regs: [isize; NB_REGS], len: usize) -> Vec<isize> {
+        let mut regs: [isize; NB_REGS] = regs;
+        self.execute(&mut regs, len)
+    }
+
+    fn execute(&self, regs: &mut [isize; NB_REGS], max_out: usize) -> Vec<isize> {
+        //Toggling modifies the program, so we work on a copy
+        let mut ops: Vec<Op> = self.ops.clone();
+        let mut fused: Vec<Option<Fused>> = compile(&ops);
+        let mut out: Vec<isize> = Vec::new();
+        let mut idx: isize = 0;
+
+        while out.len() < max_out && idx >= 0 && (idx as usize) < ops.len() {
+            if let Some(f) = fused[idx as usize] {
+                if f.apply(regs) {
+                    idx += f.len();
+                    continue;
+                }
+            }
+            match ops[idx as usize] {
+                Op::Copy(x, Operand::Reg(r)) => regs[r] = x.value(regs),
+                Op::Increment(Operand::Reg(r)) => regs[r] += 1,
+                Op::Decrement(Operand::Reg(r)) => regs[r] -= 1,
+                Op::JumpIfNotZero(x, y) if x.value(regs) != 0 => idx += y.value(regs) - 1,
+                Op::Toggle(x) => {
+                    let target: isize = idx + x.value(regs);
+                    if target >= 0 && (target as usize) < ops.len() {
+                        ops[target as usize] = ops[target as usize].toggled();
+                        fused = compile(&ops);
+                    }
+                }
+                Op::Out(x) => out.push(x.value(regs)),
+                //Invalid instructions, created by toggling, are skipped
+                _ => (),
+            }
+            idx += 1;
+        }
+        out
+    }
+}
+
+impl FromStr for Assembunny {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ops: Vec<Op> = s.lines().map(|l| l.parse()).collect::<Result<_, _>>()?;
+        Ok(Assembunny { ops })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_program() {
+        let program: Assembunny = "cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a"
+            .parse()
+            .unwrap();
+        assert_eq!(program.run([0; NB_REGS])[0], 3);
+    }
+
+    #[test]
+    fn fused_loops() {
+        //a += b * d through the nested loop, then c += a through the add loop
+        let program: Assembunny =
+            "cpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5\ndec a\ninc c\njnz a -2"
+                .parse()
+                .unwrap();
+        assert!(matches!(
+            compile(&program.ops)[..],
+            [
+                Some(Fused::Mul(0, Operand::Reg(1), 2, 3)),
+                Some(Fused::Add(0, 2)),
+                ..,
+                Some(Fused::Add(2, 0)),
+                None,
+                None
+            ]
+        ));
+        assert_eq!(program.run([1, 6, 0, 7]), [0, 6, 43, 0]);
+        //Toggling the "jnz" into an invalid "cpy" breaks the loop, it runs only once
+        let program: Assembunny = "cpy 3 b\ntgl b\ninc a\ndec b\njnz b -2".parse().unwrap();
+        assert_eq!(program.run([0; NB_REGS]), [1, 2, 0, 0]);
+    }
+}
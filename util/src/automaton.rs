@@ -0,0 +1,459 @@
+use fxhash::FxHashMap;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+//Neighbourhoods of a dense board give the neighbour indices of each cell
+//On a grid of the given width, the cell (x, y) has the index y * width + x
+
+//The 8 directions, in reading order
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+fn grid_neighbours<F>(width: usize, height: usize, ngb: F) -> Vec<Vec<usize>>
+where
+    F: Fn(isize, isize, isize, isize) -> Option<usize>,
+{
+    (0..width * height)
+        .map(|i| {
+            let (x, y) = ((i % width) as isize, (i / width) as isize);
+            DIRECTIONS
+                .iter()
+                .filter_map(|&(dx, dy)| ngb(x, y, dx, dy))
+                .collect()
+        })
+        .collect()
+}
+
+fn inside(width: usize, height: usize, x: isize, y: isize) -> Option<usize> {
+    (x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height)
+        .then(|| y as usize * width + x as usize)
+}
+
+//The 8 surrounding cells, in reading order
+pub fn moore(width: usize, height: usize) -> Vec<Vec<usize>> {
+    grid_neighbours(width, height, |x, y, dx, dy| {
+        inside(width, height, x + dx, y + dy)
+    })
+}
+
+//The 4 orthogonal cells, in reading order
+pub fn von_neumann(width: usize, height: usize) -> Vec<Vec<usize>> {
+    grid_neighbours(width, height, |x, y, dx, dy| {
+        (dx == 0 || dy == 0)
+            .then(|| inside(width, height, x + dx, y + dy))
+            .flatten()
+    })
+}
+
+//The 6 surrounding cells of a hexagonal grid, the cell (x, y) being the axial hex (q, r) = (x, y)
+//The two diagonal cells left out are the ones where both coordinates move the same way
+pub fn hex(width: usize, height: usize) -> Vec<Vec<usize>> {
+    grid_neighbours(width, height, |x, y, dx, dy| {
+        (dx != dy)
+            .then(|| inside(width, height, x + dx, y + dy))
+            .flatten()
+    })
+}
+
+//Square grids of odd size nested in each other, the middle cell of a level holds the next one
+//The cell (x, y) of the level l has the index (l * size + y) * size + x, level 0 is the outermost
+//The 4 orthogonal cells, leaving a level through its sides or entering the inner one through the
+//middle, where the whole facing side is adjacent; the middle cells have no neighbours at all
+pub fn recursive_levels(size: usize, levels: usize) -> Vec<Vec<usize>> {
+    let (side, mid): (isize, isize) = (size as isize, (size / 2) as isize);
+    let index = |l: usize, x: isize, y: isize| (l * size + y as usize) * size + x as usize;
+    (0..levels * size * size)
+        .map(|i| {
+            let (l, x, y) = (
+                i / (size * size),
+                (i % size) as isize,
+                (i / size % size) as isize,
+            );
+            if (x, y) == (mid, mid) {
+                return Vec::new();
+            }
+            let mut ngbs: Vec<usize> = Vec::new();
+            for (dx, dy) in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+                let (n_x, n_y) = (x + dx, y + dy);
+                if n_x < 0 || n_y < 0 || n_x >= side || n_y >= side {
+                    if l > 0 {
+                        ngbs.push(index(l - 1, mid + dx, mid + dy));
+                    }
+                } else if (n_x, n_y) == (mid, mid) {
+                    if l + 1 < levels {
+                        ngbs.extend((0..side).map(|k| match (dx, dy) {
+                            (1, _) => index(l + 1, 0, k),
+                            (-1, _) => index(l + 1, side - 1, k),
+                            (_, 1) => index(l + 1, k, 0),
+                            _ => index(l + 1, k, side - 1),
+                        }));
+                    }
+                } else {
+                    ngbs.push(index(l, n_x, n_y));
+                }
+            }
+            ngbs
+        })
+        .collect()
+}
+
+//In each of the 8 directions, the first cell that is not transparent
+pub fn line_of_sight<T, F: Fn(&T) -> bool>(grid: &[Vec<T>], transparent: F) -> Vec<Vec<usize>> {
+    let (width, height) = (grid[0].len(), grid.len());
+    grid_neighbours(width, height, |x, y, dx, dy| {
+        let (mut x, mut y) = (x + dx, y + dy);
+        while let Some(i) = inside(width, height, x, y) {
+            if !transparent(&grid[i / width][i % width]) {
+                return Some(i);
+            }
+            (x, y) = (x + dx, y + dy);
+        }
+        None
+    })
+}
+
+//A finite board, each cell changes according to its state and the states of its neighbours
+//The neighbourhood can be anything, see moore, von_neumann, hex, recursive_levels and line_of_sight
+#[derive(Clone, Debug)]
+pub struct Board<S> {
+    pub cells: Vec<S>,
+    neighbours: Vec<Vec<usize>>,
+}
+
+impl<S: Clone + Eq> Board<S> {
+    pub fn new(cells: Vec<S>, neighbours: Vec<Vec<usize>>) -> Self {
+        assert_eq!(cells.len(), neighbours.len());
+        Board { cells, neighbours }
+    }
+
+    //The rows are laid out one after the other, to match the grid neighbourhoods
+    pub fn from_grid(grid: Vec<Vec<S>>, neighbours: Vec<Vec<usize>>) -> Self {
+        Self::new(grid.into_iter().flatten().collect(), neighbours)
+    }
+
+    pub fn count(&self, state: &S) -> usize {
+        self.cells.iter().filter(|&c| c == state).count()
+    }
+
+    //"rule" gets the state of the cell and the states of its neighbours, in the neighbourhood order
+    //Returns false if nothing changed
+    pub fn step<R: Fn(&S, &[S]) -> S>(&mut self, rule: R) -> bool {
+        let mut states: Vec<S> = Vec::new();
+        let next: Vec<S> = self
+            .cells
+            .iter()
+            .zip(self.neighbours.iter())
+            .map(|(cell, ngbs)| {
+                states.clear();
+                states.extend(ngbs.iter().map(|&n| self.cells[n].clone()));
+                rule(cell, &states)
+            })
+            .collect();
+        let changed: bool = next != self.cells;
+        self.cells = next;
+        changed
+    }
+
+    //Number of steps that changed the board
+    pub fn run_until_stable<R: Fn(&S, &[S]) -> S>(&mut self, rule: R) -> usize {
+        let mut steps: usize = 0;
+        while self.step(&rule) {
+            steps += 1;
+        }
+        steps
+    }
+}
+
+impl<S: Clone + Eq + Hash> Board<S> {
+    //Runs until the board comes back to an earlier state, where it is left
+    //Returns the step this state first appeared at, and the length of the cycle
+    pub fn find_cycle<R: Fn(&S, &[S]) -> S>(&mut self, rule: R) -> (usize, usize) {
+        let mut seen: FxHashMap<Vec<S>, usize> = FxHashMap::default();
+        seen.insert(self.cells.clone(), 0);
+        let mut step: usize = 0;
+        loop {
+            self.step(&rule);
+            step += 1;
+            if let Some(first) = seen.insert(self.cells.clone(), step) {
+                return (first, step - first);
+            }
+        }
+    }
+
+    //Board after n steps, skipping the repeating part once a cycle is found
+    pub fn run<R: Fn(&S, &[S]) -> S>(&mut self, n: usize, rule: R) {
+        let mut seen: FxHashMap<Vec<S>, usize> = FxHashMap::default();
+        let mut history: Vec<Vec<S>> = Vec::new();
+        for step in 0..n {
+            if let Some(&first) = seen.get(&self.cells) {
+                self.cells = history[first + (n - first) % (step - first)].clone();
+                return;
+            }
+            seen.insert(self.cells.clone(), step);
+            history.push(self.cells.clone());
+            self.step(&rule);
+        }
+    }
+}
+
+//An unbounded grid where every cell outside the stored rectangle is in the background state
+//Each step grows the rectangle by one cell on each side, the background evolves like a cell
+//surrounded by background, so it can flip from one step to the next
+#[derive(Clone, Debug)]
+pub struct Plane<S> {
+    pub rows: Vec<Vec<S>>,
+    pub background: S,
+}
+
+impl<S: Clone + Eq> Plane<S> {
+    pub fn new(rows: Vec<Vec<S>>, background: S) -> Self {
+        Plane { rows, background }
+    }
+
+    fn at(&self, x: isize, y: isize) -> &S {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => self
+                .rows
+                .get(y)
+                .and_then(|row| row.get(x))
+                .unwrap_or(&self.background),
+            _ => &self.background,
+        }
+    }
+
+    //"rule" gets the state of the cell and its 8 neighbours in reading order, like moore
+    pub fn step<R: Fn(&S, &[S]) -> S>(&mut self, rule: R) {
+        let (width, height) = (self.rows[0].len() as isize, self.rows.len() as isize);
+        let mut states: Vec<S> = Vec::with_capacity(DIRECTIONS.len());
+        let rows: Vec<Vec<S>> = (-1..=height)
+            .map(|y| {
+                (-1..=width)
+                    .map(|x| {
+                        states.clear();
+                        states.extend(
+                            DIRECTIONS
+                                .iter()
+                                .map(|&(dx, dy)| self.at(x + dx, y + dy).clone()),
+                        );
+                        rule(self.at(x, y), &states)
+                    })
+                    .collect()
+            })
+            .collect();
+        let around: Vec<S> = vec![self.background.clone(); DIRECTIONS.len()];
+        self.background = rule(&self.background, &around);
+        self.rows = rows;
+    }
+
+    //None if the background has this state, as there are infinitely many of them then
+    pub fn count(&self, state: &S) -> Option<usize> {
+        (self.background != *state).then(|| {
+            self.rows
+                .iter()
+                .map(|row| row.iter().filter(|&c| c == state).count())
+                .sum()
+        })
+    }
+}
+
+//One generation of a Game of Life on an unbounded board, storing only the live cells
+//"rule" tells if a cell is alive next generation, knowing if it is alive now and its number of live neighbours
+pub fn sparse_life_step<T, I, N, R>(alive: &HashSet<T>, neighbours: N, rule: R) -> HashSet<T>
+where
+    T: Copy + Eq + Hash,
+    I: IntoIterator<Item = T>,
+    N: Fn(&T) -> I,
+    R: Fn(bool, usize) -> bool,
+{
+    //Only the live cells and their neighbours can be alive next
+    let mut counts: HashMap<T, usize> = alive.iter().map(|&cell| (cell, 0)).collect();
+    for cell in alive {
+        for ngb in neighbours(cell) {
+            *counts.entry(ngb).or_insert(0) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(cell, n)| rule(alive.contains(cell), *n))
+        .map(|(cell, _)| cell)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord::PosI;
+    use crate::hex::Hex;
+    use crate::test_rng::next_random;
+
+    fn moore_pos(&PosI(x, y): &PosI) -> impl Iterator<Item = PosI> {
+        (-1..=1)
+            .flat_map(move |dx| (-1..=1).map(move |dy| PosI(x + dx, y + dy)))
+            .filter(move |&p| p != PosI(x, y))
+    }
+
+    fn conway(alive: bool, n: usize) -> bool {
+        n == 3 || (alive && n == 2)
+    }
+
+    fn dense_conway(&alive: &bool, ngbs: &[bool]) -> bool {
+        conway(alive, ngbs.iter().filter(|&&b| b).count())
+    }
+
+    #[test]
+    fn blinker() {
+        let horizontal: HashSet<PosI> = [PosI(-1, 0), PosI(0, 0), PosI(1, 0)].into();
+        let vertical: HashSet<PosI> = [PosI(0, -1), PosI(0, 0), PosI(0, 1)].into();
+        let next: HashSet<PosI> = sparse_life_step(&horizontal, moore_pos, conway);
+        assert_eq!(next, vertical);
+        assert_eq!(sparse_life_step(&next, moore_pos, conway), horizontal);
+
+        let mut board: Board<bool> = Board::new(vec![false; 25], moore(5, 5));
+        [11, 12, 13].iter().for_each(|&i| board.cells[i] = true);
+        let start: Vec<bool> = board.cells.clone();
+        assert_eq!(board.find_cycle(dense_conway), (0, 2));
+        assert_eq!(board.cells, start);
+        board.run(1_000_001, dense_conway);
+        assert_eq!(board.count(&true), 3);
+        assert!(board.cells[7] && board.cells[12] && board.cells[17]);
+    }
+
+    #[test]
+    fn dense_against_sparse() {
+        let mut seed: u32 = 7;
+        for _ in 0..10 {
+            let (width, height) = (2 + next_random(&mut seed, 8), 2 + next_random(&mut seed, 8));
+            let grid: Vec<Vec<bool>> = (0..height)
+                .map(|_| (0..width).map(|_| next_random(&mut seed, 3) == 0).collect())
+                .collect();
+            let live = |rows: &[Vec<bool>], shift: isize| -> HashSet<PosI> {
+                rows.iter()
+                    .enumerate()
+                    .flat_map(|(y, row)| {
+                        row.iter()
+                            .enumerate()
+                            .filter(|(_, &b)| b)
+                            .map(move |(x, _)| PosI(x as isize - shift, y as isize - shift))
+                    })
+                    .collect()
+            };
+            let in_board = |p: &PosI| {
+                moore_pos(p).filter(|&PosI(x, y)| {
+                    x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height
+                })
+            };
+
+            let mut board: Board<bool> = Board::from_grid(grid.clone(), moore(width, height));
+            let mut plane: Plane<bool> = Plane::new(grid.clone(), false);
+            let mut bounded: HashSet<PosI> = live(&grid, 0);
+            let mut unbounded: HashSet<PosI> = bounded.clone();
+            for step in 1..=5 {
+                board.step(dense_conway);
+                plane.step(dense_conway);
+                bounded = sparse_life_step(&bounded, in_board, conway);
+                unbounded = sparse_life_step(&unbounded, moore_pos, conway);
+                let rows: Vec<Vec<bool>> = board.cells.chunks(width).map(|r| r.to_vec()).collect();
+                assert_eq!(live(&rows, 0), bounded);
+                assert_eq!(live(&plane.rows, step), unbounded);
+            }
+        }
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        let expected: Vec<Vec<usize>> = vec![
+            vec![1, 3],
+            vec![0, 2, 4],
+            vec![1, 5],
+            vec![0, 4],
+            vec![1, 3, 5],
+            vec![2, 4],
+        ];
+        assert_eq!(von_neumann(3, 2), expected);
+        assert_eq!(moore(2, 2)[0], vec![1, 2, 3]);
+
+        //2020 day 11 examples, the first empty seat sees 8 occupied seats, the second none
+        let parse = |rows: &[&str]| -> Vec<Vec<char>> {
+            rows.iter().map(|l| l.chars().collect()).collect()
+        };
+        let seats: Vec<Vec<char>> = parse(&[
+            ".......#.",
+            "...#.....",
+            ".#.......",
+            ".........",
+            "..#L....#",
+            "....#....",
+            ".........",
+            "#........",
+            "...#.....",
+        ]);
+        let sight: Vec<Vec<usize>> = line_of_sight(&seats, |&c| c == '.');
+        assert_eq!(sight[4 * 9 + 3], vec![19, 12, 7, 38, 44, 63, 75, 49]);
+        let seats: Vec<Vec<char>> = parse(&[
+            ".##.##.", "#.#.#.#", "##...##", "...L...", "##...##", "#.#.#.#", ".##.##.",
+        ]);
+        assert!(line_of_sight(&seats, |&c| c == '.')[3 * 7 + 3].is_empty());
+    }
+
+    #[test]
+    fn hex_and_levels() {
+        let (width, height): (usize, usize) = (4, 3);
+        for (i, ngbs) in hex(width, height).iter().enumerate() {
+            let cell: Hex = Hex::new((i % width) as isize, (i / width) as isize);
+            let mut expected: Vec<usize> = cell
+                .neighbours()
+                .iter()
+                .filter(|h| h.q >= 0 && h.r >= 0 && h.q < width as isize && h.r < height as isize)
+                .map(|h| h.r as usize * width + h.q as usize)
+                .collect();
+            expected.sort();
+            assert_eq!(ngbs, &expected);
+        }
+
+        //2019 day 24 example, on three levels of 5x5
+        let levels: Vec<Vec<usize>> = recursive_levels(5, 3);
+        let at = |l: usize, x: usize, y: usize| (l * 5 + y) * 5 + x;
+        assert!(levels[at(1, 2, 2)].is_empty());
+        assert_eq!(levels[at(0, 0, 0)], vec![at(0, 1, 0), at(0, 0, 1)]);
+        assert_eq!(
+            levels[at(1, 0, 0)],
+            vec![at(0, 2, 1), at(0, 1, 2), at(1, 1, 0), at(1, 0, 1)]
+        );
+        let mut expected: Vec<usize> = vec![at(1, 3, 1), at(1, 4, 2), at(1, 3, 3)];
+        expected.extend((0..5).map(|y| at(2, 4, y)));
+        let mut ngbs: Vec<usize> = levels[at(1, 3, 2)].clone();
+        ngbs.sort();
+        expected.sort();
+        assert_eq!(ngbs, expected);
+        //The innermost level has nothing inside its middle
+        assert_eq!(levels[at(2, 3, 2)].len(), 3);
+    }
+
+    #[test]
+    fn flipping_background() {
+        //Every cell becomes dark when its whole neighbourhood is lit, and lit when it is all dark
+        let rule = |&cell: &bool, ngbs: &[bool]| match ngbs.iter().filter(|&&b| b).count() {
+            0 => true,
+            8 => false,
+            _ => cell,
+        };
+        let mut plane: Plane<bool> = Plane::new(vec![vec![true]], false);
+        plane.step(rule);
+        assert!(plane.background);
+        assert_eq!(plane.count(&true), None);
+        assert_eq!(plane.count(&false), Some(8));
+        //The outer ring was background, so it stays lit, as does the isolated centre
+        plane.step(rule);
+        assert!(!plane.background);
+        assert_eq!(plane.rows.len(), 5);
+        assert_eq!(plane.count(&true), Some(17));
+    }
+}
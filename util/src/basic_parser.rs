@@ -1,4 +1,5 @@
-use crate::coord::{Pos, Pos3};
+use crate::coord::Pos;
+use crate::vector::Vector;
 use nom::bytes::complete::{tag, take_while};
 use nom::character::complete::{char, digit1, space1};
 use nom::combinator::{map_res, opt, recognize};
@@ -36,9 +37,9 @@ pub fn parse_pos(s: &str) -> IResult<&str, Pos> {
     Ok((s, Pos(v[0], v[1])))
 }
 
-pub fn parse_pos3(s: &str) -> IResult<&str, Pos3> {
+pub fn parse_pos3(s: &str) -> IResult<&str, Vector<usize, 3>> {
     let (s, v) = separated_list1(tag(","), parse_usize).parse(s)?;
-    Ok((s, Pos3(v[0], v[1], v[2])))
+    Ok((s, Vector([v[0], v[1], v[2]])))
 }
 
 pub fn from_hex(input: &str) -> Result<usize, std::num::ParseIntError> {
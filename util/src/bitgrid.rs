@@ -0,0 +1,412 @@
+use crate::coord::Pos;
+use crate::vector::Vector;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+const WORD: usize = 64;
+
+//A row of bits packed in 64 bits words, bit x is the bit x % 64 of the word x / 64
+//The bits past the length are always 0, so whole words can be compared and counted
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BitRow {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitRow {
+    pub fn new(len: usize) -> Self {
+        BitRow {
+            words: vec![0; len.div_ceil(WORD)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn get(&self, x: usize) -> bool {
+        assert!(x < self.len, "Bit {x} out of a row of {}", self.len);
+        self.words[x / WORD] >> (x % WORD) & 1 == 1
+    }
+
+    pub fn set(&mut self, x: usize, on: bool) {
+        assert!(x < self.len, "Bit {x} out of a row of {}", self.len);
+        let mask: u64 = 1 << (x % WORD);
+        if on {
+            self.words[x / WORD] |= mask;
+        } else {
+            self.words[x / WORD] &= !mask;
+        }
+    }
+
+    //Turns on the bits from start to end excluded, a word at a time
+    pub fn set_range(&mut self, start: usize, end: usize) {
+        assert!(
+            start <= end && end <= self.len,
+            "Range {start}..{end} out of a row"
+        );
+        if start == end {
+            return;
+        }
+        for w in start / WORD..end.div_ceil(WORD) {
+            let from: usize = start.max(w * WORD) - w * WORD;
+            let to: usize = end.min((w + 1) * WORD) - w * WORD;
+            self.words[w] |= (u64::MAX >> (WORD - (to - from))) << from;
+        }
+    }
+
+    fn clear_tail(&mut self) {
+        if !self.len.is_multiple_of(WORD) {
+            if let Some(last) = self.words.last_mut() {
+                *last &= u64::MAX >> (WORD - self.len % WORD);
+            }
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    pub fn first_one(&self) -> Option<usize> {
+        self.ones().next()
+    }
+
+    pub fn last_one(&self) -> Option<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, &w)| w != 0)
+            .map(|(i, w)| i * WORD + WORD - 1 - w.leading_zeros() as usize)
+    }
+
+    //Positions of the bits that are on, in increasing order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &w)| {
+            let mut word: u64 = w;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit: usize = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * WORD + bit
+                })
+            })
+        })
+    }
+
+    //Bit x moves to x + n, the bits going past the end are lost
+    pub fn shifted_right(&self, n: usize) -> BitRow {
+        let mut shifted: BitRow = BitRow::new(self.len);
+        let (words, bits) = (n / WORD, n % WORD);
+        for i in words..self.words.len() {
+            let src: usize = i - words;
+            shifted.words[i] = self.words[src] << bits;
+            if bits > 0 && src > 0 {
+                shifted.words[i] |= self.words[src - 1] >> (WORD - bits);
+            }
+        }
+        shifted.clear_tail();
+        shifted
+    }
+
+    //Bit x moves to x - n, the bits going before 0 are lost
+    pub fn shifted_left(&self, n: usize) -> BitRow {
+        let mut shifted: BitRow = BitRow::new(self.len);
+        let (words, bits) = (n / WORD, n % WORD);
+        for i in 0..self.words.len().saturating_sub(words) {
+            let src: usize = i + words;
+            shifted.words[i] = self.words[src] >> bits;
+            if bits > 0 && src + 1 < self.words.len() {
+                shifted.words[i] |= self.words[src + 1] << (WORD - bits);
+            }
+        }
+        shifted
+    }
+}
+
+macro_rules! bit_row_op {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident, $sym:tt) => {
+        impl $op_assign<&BitRow> for BitRow {
+            fn $f_assign(&mut self, other: &BitRow) {
+                assert_eq!(self.len, other.len, "Rows of different lengths");
+                self.words
+                    .iter_mut()
+                    .zip(other.words.iter())
+                    .for_each(|(a, b)| *a $sym *b);
+            }
+        }
+
+        impl $op<&BitRow> for &BitRow {
+            type Output = BitRow;
+
+            fn $f(self, other: &BitRow) -> BitRow {
+                let mut row: BitRow = self.clone();
+                row.$f_assign(other);
+                row
+            }
+        }
+
+        impl $op<&BitRow> for BitRow {
+            type Output = BitRow;
+
+            fn $f(mut self, other: &BitRow) -> BitRow {
+                self.$f_assign(other);
+                self
+            }
+        }
+    };
+}
+
+bit_row_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+bit_row_op!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+bit_row_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+
+impl Not for &BitRow {
+    type Output = BitRow;
+
+    fn not(self) -> BitRow {
+        let mut row: BitRow = BitRow {
+            words: self.words.iter().map(|w| !w).collect(),
+            len: self.len,
+        };
+        row.clear_tail();
+        row
+    }
+}
+
+//A width x height grid of bits, one BitRow per line
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BitGrid {
+    rows: Vec<BitRow>,
+    width: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        BitGrid {
+            rows: vec![BitRow::new(width); height],
+            width,
+        }
+    }
+
+    pub fn from_bools(grid: &[Vec<bool>]) -> Self {
+        let mut bits: BitGrid = BitGrid::new(grid.first().map_or(0, |r| r.len()), grid.len());
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &b) in row.iter().enumerate() {
+                bits.set(Pos(x, y), b);
+            }
+        }
+        bits
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get(&self, Pos(x, y): Pos) -> bool {
+        self.rows[y].get(x)
+    }
+
+    pub fn set(&mut self, Pos(x, y): Pos, on: bool) {
+        self.rows[y].set(x, on)
+    }
+
+    pub fn row(&self, y: usize) -> &BitRow {
+        &self.rows[y]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut BitRow {
+        &mut self.rows[y]
+    }
+
+    pub fn rows(&self) -> &[BitRow] {
+        &self.rows
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<BitRow>) {
+        assert!(
+            rows.iter().all(|r| r.len() == self.width),
+            "Rows of different lengths"
+        );
+        self.rows = rows;
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.rows.iter().map(|r| r.count_ones()).sum()
+    }
+
+    pub fn ones(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.ones().map(move |x| Pos(x, y)))
+    }
+
+    //Top left and bottom right corners of the smallest rectangle holding every bit that is on
+    pub fn bounds(&self) -> Option<(Pos, Pos)> {
+        let top: usize = self.rows.iter().position(|r| r.any())?;
+        let bottom: usize = self.rows.iter().rposition(|r| r.any())?;
+        let rows = || self.rows[top..=bottom].iter();
+        let left: usize = rows().filter_map(|r| r.first_one()).min()?;
+        let right: usize = rows().filter_map(|r| r.last_one()).max()?;
+        Some((Pos(left, top), Pos(right, bottom)))
+    }
+}
+
+//A stack of BitGrid layers, z being the layer
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BitGrid3 {
+    layers: Vec<BitGrid>,
+}
+
+impl BitGrid3 {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        BitGrid3 {
+            layers: vec![BitGrid::new(width, height); depth],
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn get(&self, Vector([x, y, z]): Vector<usize, 3>) -> bool {
+        self.layers[z].get(Pos(x, y))
+    }
+
+    pub fn set(&mut self, Vector([x, y, z]): Vector<usize, 3>, on: bool) {
+        self.layers[z].set(Pos(x, y), on)
+    }
+
+    pub fn layer(&self, z: usize) -> &BitGrid {
+        &self.layers[z]
+    }
+
+    pub fn layer_mut(&mut self, z: usize) -> &mut BitGrid {
+        &mut self.layers[z]
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.layers.iter().map(|l| l.count_ones()).sum()
+    }
+
+    pub fn ones(&self) -> impl Iterator<Item = Vector<usize, 3>> + '_ {
+        self.layers
+            .iter()
+            .enumerate()
+            .flat_map(|(z, layer)| layer.ones().map(move |Pos(x, y)| Vector([x, y, z])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::next_random;
+
+    fn random_row(seed: &mut u32, len: usize) -> (BitRow, Vec<bool>) {
+        let bools: Vec<bool> = (0..len).map(|_| next_random(seed, 2) == 0).collect();
+        let mut row: BitRow = BitRow::new(len);
+        bools.iter().enumerate().for_each(|(x, &b)| row.set(x, b));
+        (row, bools)
+    }
+
+    fn to_bools(row: &BitRow) -> Vec<bool> {
+        (0..row.len()).map(|x| row.get(x)).collect()
+    }
+
+    #[test]
+    fn rows_against_bools() {
+        let mut seed: u32 = 13;
+        for _ in 0..100 {
+            let len: usize = 1 + next_random(&mut seed, 200);
+            let (a, bools_a) = random_row(&mut seed, len);
+            let (b, bools_b) = random_row(&mut seed, len);
+            let zip = |f: fn(bool, bool) -> bool| -> Vec<bool> {
+                bools_a
+                    .iter()
+                    .zip(&bools_b)
+                    .map(|(&x, &y)| f(x, y))
+                    .collect()
+            };
+            assert_eq!(to_bools(&(&a & &b)), zip(|x, y| x && y));
+            assert_eq!(to_bools(&(&a | &b)), zip(|x, y| x || y));
+            assert_eq!(to_bools(&(&a ^ &b)), zip(|x, y| x != y));
+            assert_eq!((!&a).count_ones(), bools_a.iter().filter(|&&x| !x).count());
+
+            let n: usize = next_random(&mut seed, 150);
+            let right: Vec<bool> = (0..len).map(|x| x >= n && bools_a[x - n]).collect();
+            let left: Vec<bool> = (0..len).map(|x| x + n < len && bools_a[x + n]).collect();
+            assert_eq!(to_bools(&a.shifted_right(n)), right);
+            assert_eq!(to_bools(&a.shifted_left(n)), left);
+            assert_eq!(
+                a.shifted_right(n).count_ones(),
+                right.iter().filter(|&&x| x).count()
+            );
+
+            let ones: Vec<usize> = (0..len).filter(|&x| bools_a[x]).collect();
+            assert_eq!(a.ones().collect::<Vec<usize>>(), ones);
+            assert_eq!(a.first_one(), ones.first().copied());
+            assert_eq!(a.last_one(), ones.last().copied());
+
+            let start: usize = next_random(&mut seed, len as u32 + 1);
+            let end: usize = start + next_random(&mut seed, (len - start) as u32 + 1);
+            let mut range: BitRow = BitRow::new(len);
+            range.set_range(start, end);
+            assert_eq!(
+                range.ones().collect::<Vec<usize>>(),
+                (start..end).collect::<Vec<usize>>()
+            );
+        }
+    }
+
+    #[test]
+    fn grids() {
+        let mut grid: BitGrid = BitGrid::new(100, 4);
+        assert_eq!(grid.bounds(), None);
+        grid.set(Pos(70, 1), true);
+        grid.set(Pos(3, 2), true);
+        grid.set(Pos(99, 2), true);
+        assert!(grid.get(Pos(70, 1)) && !grid.get(Pos(71, 1)));
+        assert_eq!(grid.count_ones(), 3);
+        assert_eq!(grid.bounds(), Some((Pos(3, 1), Pos(99, 2))));
+        assert_eq!(
+            grid.ones().collect::<Vec<Pos>>(),
+            vec![Pos(70, 1), Pos(3, 2), Pos(99, 2)]
+        );
+        assert_eq!(
+            grid,
+            BitGrid::from_bools(&{
+                let mut bools: Vec<Vec<bool>> = vec![vec![false; 100]; 4];
+                bools[1][70] = true;
+                bools[2][3] = true;
+                bools[2][99] = true;
+                bools
+            })
+        );
+
+        let mut cube: BitGrid3 = BitGrid3::new(3, 3, 3);
+        cube.set(Vector([1, 2, 2]), true);
+        assert!(cube.get(Vector([1, 2, 2])));
+        assert_eq!(cube.layer(2).count_ones(), 1);
+        assert_eq!(
+            cube.ones().collect::<Vec<Vector<usize, 3>>>(),
+            vec![Vector([1, 2, 2])]
+        );
+    }
+}
@@ -1,48 +1,142 @@
+//Raised when an intermediate modulus does not fit in an i128
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Overflow;
+
 pub fn smallest_remainder(div_and_remains: Vec<(i128, i128)>) -> i128 {
-    let product: i128 = div_and_remains.iter().map(|(divisor, _)| divisor).product();
-    let one_solution: i128 = div_and_remains
-        .iter()
-        .map(|&(divisor, remain)| elem(divisor, remain, product))
-        .sum();
-    modulo(one_solution, product)
+    solve_congruences(&div_and_remains)
+        .expect("Product of the divisors overflows")
+        .expect("The congruences have no common solution")
+        .1
 }
 
-pub fn modulo(solution: i128, product: i128) -> i128 {
-    ((solution % product) + product) % product
+//Merge all congruences x = remain [divisor] into a single one, returned as (divisor, remain)
+//The divisors do not need to be pairwise coprime, None is returned if they are inconsistent
+pub fn solve_congruences(
+    div_and_remains: &[(i128, i128)],
+) -> Result<Option<(i128, i128)>, Overflow> {
+    let mut merged: (i128, i128) = (1, 0);
+    for &congruence in div_and_remains {
+        match merge_congruences(merged, congruence)? {
+            Some(c) => merged = c,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(merged))
+}
+
+pub fn merge_congruences(
+    (div_1, remain_1): (i128, i128),
+    (div_2, remain_2): (i128, i128),
+) -> Result<Option<(i128, i128)>, Overflow> {
+    let remain_1: i128 = modulo(remain_1, div_1);
+    let remain_2: i128 = modulo(remain_2, div_2);
+    let (g, u, _) = extended_gcd(div_1, div_2);
+    let diff: i128 = remain_2 - remain_1;
+    if diff % g != 0 {
+        return Ok(None);
+    }
+    let reduced: i128 = div_2 / g;
+    let lcm: i128 = (div_1 / g).checked_mul(div_2).ok_or(Overflow)?;
+    //x = remain_1 + div_1 * t, with t = diff/g * u [div_2/g]
+    let t: i128 = mod_mul(modulo(diff / g, reduced), modulo(u, reduced), reduced);
+    //div_1 * t < lcm and remain_1 < div_1, so no overflow can happen here
+    Ok(Some((lcm, remain_1 + div_1 * t)))
 }
 
-fn elem(divisor: i128, remain: i128, product: i128) -> i128 {
-    let prod = product / divisor;
-    remain * bezout_coeff(divisor, prod) * prod
+pub fn modulo(solution: i128, product: i128) -> i128 {
+    solution.rem_euclid(product)
 }
 
-fn bezout_coeff(divisor: i128, prod: i128) -> i128 {
-    inner_coeff(prod, 1, 0, divisor, 0, 1).1
+//Returns (gcd, u, v) such that a*u + b*v = gcd
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r, mut u, mut v) = (a, 1, 0);
+    let (mut r_prime, mut u_prime, mut v_prime) = (b, 0, 1);
+    while r_prime != 0 {
+        let q: i128 = r / r_prime;
+        (r, r_prime) = (r_prime, r - q * r_prime);
+        (u, u_prime) = (u_prime, u - q * u_prime);
+        (v, v_prime) = (v_prime, v - q * v_prime);
+    }
+    (r, u, v)
 }
 
 pub fn bezout_triplet(a: u128, b: u128) -> (i128, i128, i128) {
-    inner_coeff(a as i128, 1, 0, b as i128, 0, 1)
-}
-
-fn inner_coeff(
-    r: i128,
-    u: i128,
-    v: i128,
-    r_prime: i128,
-    u_prime: i128,
-    v_prime: i128,
-) -> (i128, i128, i128) {
-    if r_prime == 0 {
-        (r, u, v)
+    extended_gcd(a as i128, b as i128)
+}
+
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, u, _) = extended_gcd(modulo(a, modulus), modulus);
+    (g == 1).then(|| modulo(u, modulus))
+}
+
+fn mod_add(a: i128, b: i128, modulus: i128) -> i128 {
+    //a and b are already reduced, compare before adding to never overflow
+    if a >= modulus - b {
+        a - (modulus - b)
     } else {
-        let q = r / r_prime;
-        inner_coeff(
-            r_prime,
-            u_prime,
-            v_prime,
-            r - q * r_prime,
-            u - q * u_prime,
-            v - q * v_prime,
-        )
+        a + b
+    }
+}
+
+pub fn mod_mul(a: i128, b: i128, modulus: i128) -> i128 {
+    let a: i128 = modulo(a, modulus);
+    let mut b: i128 = modulo(b, modulus);
+    if let Some(p) = a.checked_mul(b) {
+        return p % modulus;
+    }
+    //Double and add when the product does not fit
+    let mut acc: i128 = 0;
+    let mut base: i128 = a;
+    while b > 0 {
+        if b & 1 == 1 {
+            acc = mod_add(acc, base, modulus);
+        }
+        base = mod_add(base, base, modulus);
+        b >>= 1;
+    }
+    acc
+}
+
+pub fn mod_pow(base: i128, mut exp: u128, modulus: i128) -> i128 {
+    let mut acc: i128 = modulo(1, modulus);
+    let mut base: i128 = modulo(base, modulus);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mod_mul(acc, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exp >>= 1;
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coprime_divisors() {
+        assert_eq!(smallest_remainder(vec![(3, 2), (5, 3), (7, 2)]), 23);
+    }
+
+    #[test]
+    fn non_coprime_divisors() {
+        assert_eq!(solve_congruences(&[(6, 5), (4, 3)]), Ok(Some((12, 11))));
+        assert_eq!(solve_congruences(&[(6, 5), (4, 2)]), Ok(None));
+    }
+
+    #[test]
+    fn overflowing_divisors() {
+        let big: i128 = (1 << 100) + 1;
+        assert_eq!(solve_congruences(&[(big, 1), (1 << 40, 0)]), Err(Overflow));
+    }
+
+    #[test]
+    fn inverse_and_power() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(4, 10), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        let p: i128 = i128::MAX;
+        assert_eq!(mod_mul(p - 1, p - 1, p), 1);
     }
 }
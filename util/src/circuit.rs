@@ -0,0 +1,490 @@
+use crate::dot::{Dot, Shape};
+use crate::graph::{Graph, NodeMap};
+use fxhash::FxHashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum GateOp {
+    Copy,
+    Not,
+    And,
+    Or,
+    Xor,
+    Lshift,
+    Rshift,
+}
+
+impl FromStr for GateOp {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NOT" => Ok(GateOp::Not),
+            "AND" => Ok(GateOp::And),
+            "OR" => Ok(GateOp::Or),
+            "XOR" => Ok(GateOp::Xor),
+            "LSHIFT" => Ok(GateOp::Lshift),
+            "RSHIFT" => Ok(GateOp::Rshift),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for GateOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: &str = match self {
+            GateOp::Copy => "COPY",
+            GateOp::Not => "NOT",
+            GateOp::And => "AND",
+            GateOp::Or => "OR",
+            GateOp::Xor => "XOR",
+            GateOp::Lshift => "LSHIFT",
+            GateOp::Rshift => "RSHIFT",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Signal {
+    Wire(usize),
+    Value(u64),
+}
+
+//Copy and Not have a single input
+#[derive(Clone, Debug)]
+pub struct Gate {
+    pub op: GateOp,
+    pub inputs: Vec<Signal>,
+    pub output: usize,
+}
+
+impl Gate {
+    fn has_input(&self, wire: usize) -> bool {
+        self.inputs.contains(&Signal::Wire(wire))
+    }
+
+    fn compute(&self, inputs: &[u64], mask: u64) -> u64 {
+        let shift: Option<u32> = inputs.get(1).and_then(|&s| u32::try_from(s).ok());
+        let value: u64 = match self.op {
+            GateOp::Copy => inputs[0],
+            GateOp::Not => !inputs[0],
+            GateOp::And => inputs[0] & inputs[1],
+            GateOp::Or => inputs[0] | inputs[1],
+            GateOp::Xor => inputs[0] ^ inputs[1],
+            GateOp::Lshift => shift.and_then(|s| inputs[0].checked_shl(s)).unwrap_or(0),
+            GateOp::Rshift => shift.and_then(|s| inputs[0].checked_shr(s)).unwrap_or(0),
+        };
+        value & mask
+    }
+}
+
+//A network of gates between named wires, every value is cut to the bit width (64 by default)
+//Parsed from lines like "123 -> x", "NOT x -> y", "x AND y -> z" or "x LSHIFT 2 -> z"
+#[derive(Clone, Debug)]
+pub struct Circuit {
+    pub wires: NodeMap<String>,
+    pub gates: Vec<Gate>,
+    mask: u64,
+    //Wires with a value given from outside, their gate is ignored
+    fixed: FxHashMap<usize, u64>,
+}
+
+//The value of each wire after an evaluation, None when it depends on an unknown wire
+pub struct Values<'a> {
+    circuit: &'a Circuit,
+    values: Vec<Option<u64>>,
+}
+
+impl Values<'_> {
+    pub fn get(&self, wire: &str) -> Option<u64> {
+        self.values[self.circuit.wire(wire)?]
+    }
+
+    //The number with bit i on the wire "prefix" followed by i on two digits, see Circuit::bus
+    pub fn number(&self, prefix: &str) -> Option<u64> {
+        self.circuit
+            .bus(prefix)
+            .iter()
+            .enumerate()
+            .try_fold(0, |n, (i, &wire)| Some(n | (self.values[wire]? & 1) << i))
+    }
+}
+
+//What the adder check found wrong, see Circuit::adder_swaps
+enum AdderFault {
+    Swap(usize, usize),
+    Broken,
+}
+
+impl Circuit {
+    pub fn with_width(mut self, bits: u32) -> Self {
+        self.mask = u64::MAX >> (64 - bits.clamp(1, 64));
+        self
+    }
+
+    pub fn wire(&self, name: &str) -> Option<usize> {
+        self.wires.get(&name.to_string())
+    }
+
+    //Forces the value of a wire, whatever drives it
+    pub fn set(&mut self, wire: &str, value: u64) {
+        let id: usize = self.wires.id(wire.to_string());
+        self.fixed.insert(id, value & self.mask);
+    }
+
+    //The wire goes back to being driven by its gate
+    pub fn unset(&mut self, wire: &str) {
+        if let Some(id) = self.wire(wire) {
+            self.fixed.remove(&id);
+        }
+    }
+
+    //The wires "prefix00", "prefix01"... up to the first missing one, the AoC naming of buses
+    pub fn bus(&self, prefix: &str) -> Vec<usize> {
+        (0..)
+            .map_while(|i| self.wire(&format!("{prefix}{i:02}")))
+            .collect()
+    }
+
+    //Sets the wires of the bus to the bits of n
+    pub fn set_number(&mut self, prefix: &str, n: u64) {
+        for (i, wire) in self.bus(prefix).into_iter().enumerate() {
+            self.fixed.insert(wire, (n >> i) & 1);
+        }
+    }
+
+    fn drivers(&self) -> Vec<Option<usize>> {
+        let mut drivers: Vec<Option<usize>> = vec![None; self.wires.len()];
+        for (g, gate) in self.gates.iter().enumerate() {
+            drivers[gate.output] = Some(g);
+        }
+        drivers
+    }
+
+    //Computes every wire in topological order, None if the gates form a loop
+    pub fn evaluate(&self) -> Option<Values<'_>> {
+        let drivers: Vec<Option<usize>> = self.drivers();
+        let mut graph: Graph = Graph::new(self.wires.len());
+        for gate in &self.gates {
+            if self.fixed.contains_key(&gate.output) {
+                continue;
+            }
+            for &input in &gate.inputs {
+                if let Signal::Wire(wire) = input {
+                    graph.add_edge(wire, gate.output);
+                }
+            }
+        }
+
+        let mut values: Vec<Option<u64>> = vec![None; self.wires.len()];
+        for wire in graph.topological_sort()? {
+            values[wire] = match (self.fixed.get(&wire), drivers[wire]) {
+                (Some(&v), _) => Some(v),
+                (None, Some(g)) => {
+                    let gate: &Gate = &self.gates[g];
+                    let inputs: Option<Vec<u64>> = gate
+                        .inputs
+                        .iter()
+                        .map(|&input| match input {
+                            Signal::Wire(w) => values[w],
+                            Signal::Value(v) => Some(v & self.mask),
+                        })
+                        .collect();
+                    inputs.map(|inputs| gate.compute(&inputs, self.mask))
+                }
+                (None, None) => None,
+            };
+        }
+        Some(Values {
+            circuit: self,
+            values,
+        })
+    }
+
+    //Exchanges the gates driving the two wires, false if one of them has no gate
+    pub fn swap_outputs(&mut self, a: &str, b: &str) -> bool {
+        match (self.wire(a), self.wire(b)) {
+            (Some(a), Some(b)) => self.swap_wires(a, b),
+            _ => false,
+        }
+    }
+
+    fn swap_wires(&mut self, a: usize, b: usize) -> bool {
+        let drivers: Vec<Option<usize>> = self.drivers();
+        let (Some(ga), Some(gb)) = (drivers[a], drivers[b]) else {
+            return false;
+        };
+        self.gates[ga].output = b;
+        self.gates[gb].output = a;
+        true
+    }
+
+    fn find_gate(&self, op: GateOp, a: usize, b: usize) -> Option<&Gate> {
+        self.gates
+            .iter()
+            .find(|g| g.op == op && g.has_input(a) && g.has_input(b))
+    }
+
+    //Output of the gate combining a and b, if there is none a gate with only one of them
+    //tells which of a or b should be swapped with its other input
+    fn expect_gate(&self, op: GateOp, a: usize, b: usize) -> Result<usize, AdderFault> {
+        if let Some(gate) = self.find_gate(op, a, b) {
+            return Ok(gate.output);
+        }
+        let gate: &Gate = self
+            .gates
+            .iter()
+            .find(|g| g.op == op && (g.has_input(a) || g.has_input(b)))
+            .ok_or(AdderFault::Broken)?;
+        let (kept, lost) = if gate.has_input(a) { (a, b) } else { (b, a) };
+        match gate.inputs.iter().find(|&&s| s != Signal::Wire(kept)) {
+            Some(&Signal::Wire(other)) => Err(AdderFault::Swap(lost, other)),
+            _ => Err(AdderFault::Broken),
+        }
+    }
+
+    //Walks the ripple-carry adder bit by bit, up to the first gate that is not where it should be:
+    //z0 = x0 ^ y0, c0 = x0 & y0, then zi = (xi ^ yi) ^ ci-1, ci = (xi & yi) | ((xi ^ yi) & ci-1)
+    //The last z wire, if any, is the last carry
+    fn adder_fault(&self, x: &[usize], y: &[usize], z: &[usize]) -> Option<AdderFault> {
+        let mut carry: Option<usize> = None;
+        for (i, (&xi, &yi)) in x.iter().zip(y).enumerate() {
+            //A missing z wire or input gate cannot be fixed by swapping outputs
+            let (Some(&zi), Some(sum), Some(and)) = (
+                z.get(i),
+                self.find_gate(GateOp::Xor, xi, yi),
+                self.find_gate(GateOp::And, xi, yi),
+            ) else {
+                return Some(AdderFault::Broken);
+            };
+            let (sum, and): (usize, usize) = (sum.output, and.output);
+            let Some(c) = carry else {
+                if sum != zi {
+                    return Some(AdderFault::Swap(sum, zi));
+                }
+                carry = Some(and);
+                continue;
+            };
+            let out: usize = match self.expect_gate(GateOp::Xor, sum, c) {
+                Ok(out) => out,
+                Err(fault) => return Some(fault),
+            };
+            if out != zi {
+                return Some(AdderFault::Swap(out, zi));
+            }
+            let Some(both) = self.find_gate(GateOp::And, sum, c) else {
+                return Some(AdderFault::Broken);
+            };
+            carry = match self.expect_gate(GateOp::Or, and, both.output) {
+                Ok(out) => Some(out),
+                Err(fault) => return Some(fault),
+            };
+        }
+        match (carry, z.get(x.len())) {
+            (Some(c), Some(&last)) if c != last => Some(AdderFault::Swap(c, last)),
+            _ => None,
+        }
+    }
+
+    //Pairs of wires whose gates have to be exchanged to make the circuit add the x and y buses
+    //into the z bus, None if it cannot be fixed this way
+    pub fn adder_swaps(&self, x: &str, y: &str, z: &str) -> Option<Vec<(String, String)>> {
+        let mut circuit: Circuit = self.clone();
+        let (x, y, z) = (self.bus(x), self.bus(y), self.bus(z));
+        if x.len() != y.len() {
+            return None;
+        }
+        let mut swaps: Vec<(String, String)> = Vec::new();
+        while let Some(fault) = circuit.adder_fault(&x, &y, &z) {
+            match fault {
+                AdderFault::Swap(a, b) if swaps.len() < self.gates.len() => {
+                    if !circuit.swap_wires(a, b) {
+                        return None;
+                    }
+                    let name = |w: usize| self.wires.name(w).clone();
+                    swaps.push((name(a), name(b)));
+                }
+                _ => return None,
+            }
+        }
+        Some(swaps)
+    }
+
+    //Graphviz view, one box per gate between the wires
+    pub fn to_dot(&self) -> String {
+        let mut dot: Dot = Dot::directed();
+        for id in 0..self.wires.len() {
+            let name: &str = self.wires.name(id);
+            dot.node(name, name, Shape::Ellipse);
+        }
+        for (g, gate) in self.gates.iter().enumerate() {
+            //Wire names have no spaces, so they cannot clash with these ids
+            let id: String = format!("gate {g}");
+            dot.node(&id, &gate.op.to_string(), Shape::Box);
+            for (i, &input) in gate.inputs.iter().enumerate() {
+                match input {
+                    Signal::Wire(w) => dot.edge(self.wires.name(w), &id),
+                    Signal::Value(v) => {
+                        let value: String = format!("value {g} {i}");
+                        dot.node(&value, &v.to_string(), Shape::Plaintext)
+                            .edge(&value, &id)
+                    }
+                };
+            }
+            dot.edge(&id, self.wires.name(gate.output));
+        }
+        dot.to_string()
+    }
+}
+
+impl FromStr for Circuit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut wires: NodeMap<String> = NodeMap::new();
+        let mut signal = |token: &str| match token.parse::<u64>() {
+            Ok(v) => Signal::Value(v),
+            Err(_) => Signal::Wire(wires.id(token.to_string())),
+        };
+        let mut gates: Vec<Gate> = Vec::new();
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let (left, output) = line.split_once(" -> ").ok_or(())?;
+            let tokens: Vec<&str> = left.split_whitespace().collect();
+            let (op, inputs) = match tokens[..] {
+                [a] => (GateOp::Copy, vec![signal(a)]),
+                ["NOT", a] => (GateOp::Not, vec![signal(a)]),
+                [a, op, b] => (op.parse()?, vec![signal(a), signal(b)]),
+                _ => return Err(()),
+            };
+            let Signal::Wire(output) = signal(output.trim()) else {
+                return Err(());
+            };
+            gates.push(Gate { op, inputs, output });
+        }
+        Ok(Circuit {
+            wires,
+            gates,
+            mask: u64::MAX,
+            fixed: FxHashMap::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::next_random;
+
+    //A correct ripple-carry adder on the given number of bits, the last carry is the last z
+    fn adder(bits: usize) -> String {
+        let carry = |i: usize| match i + 1 == bits {
+            true => format!("z{bits:02}"),
+            false => format!("c{i:02}"),
+        };
+        let mut s: String = format!("x00 XOR y00 -> z00\nx00 AND y00 -> {}\n", carry(0));
+        for i in 1..bits {
+            let (c, p) = (carry(i), carry(i - 1));
+            s += &format!("x{i:02} XOR y{i:02} -> s{i:02}\nx{i:02} AND y{i:02} -> a{i:02}\n");
+            s += &format!("s{i:02} XOR {p} -> z{i:02}\ns{i:02} AND {p} -> b{i:02}\n");
+            s += &format!("a{i:02} OR b{i:02} -> {c}\n");
+        }
+        s
+    }
+
+    #[test]
+    fn signals() {
+        //2015 day 7 example
+        let mut circuit: Circuit = "123 -> x
+456 -> y
+x AND y -> d
+x OR y -> e
+x LSHIFT 2 -> f
+y RSHIFT 2 -> g
+NOT x -> h
+NOT y -> i"
+            .parse::<Circuit>()
+            .unwrap()
+            .with_width(16);
+        let values: Values = circuit.evaluate().unwrap();
+        let expected: [(&str, u64); 8] = [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ];
+        for (wire, value) in expected {
+            assert_eq!(values.get(wire), Some(value));
+        }
+
+        circuit.set("x", 1);
+        assert_eq!(circuit.evaluate().unwrap().get("f"), Some(4));
+        circuit.unset("x");
+        assert_eq!(circuit.evaluate().unwrap().get("f"), Some(492));
+
+        let looping: Circuit = "a -> b\nNOT b -> a".parse().unwrap();
+        assert!(looping.evaluate().is_none());
+    }
+
+    #[test]
+    fn adding() {
+        let mut circuit: Circuit = adder(6).parse::<Circuit>().unwrap().with_width(1);
+        for (x, y) in [(0, 0), (21, 42), (37, 29), (63, 1), (63, 63)] {
+            circuit.set_number("x", x);
+            circuit.set_number("y", y);
+            assert_eq!(circuit.evaluate().unwrap().number("z"), Some(x + y));
+        }
+        assert_eq!(circuit.adder_swaps("x", "y", "z"), Some(vec![]));
+        assert!(circuit
+            .to_dot()
+            .contains("\"gate 0\" [label=\"XOR\", shape=box];"));
+    }
+
+    #[test]
+    fn miswired_adders() {
+        let mut seed: u32 = 17;
+        for _ in 0..30 {
+            let bits: usize = 2 + next_random(&mut seed, 10);
+            let mut circuit: Circuit = adder(bits).parse::<Circuit>().unwrap().with_width(1);
+            let outputs: Vec<String> = circuit
+                .gates
+                .iter()
+                .map(|g| circuit.wires.name(g.output).clone())
+                .collect();
+            let (a, b) = (
+                &outputs[next_random(&mut seed, outputs.len() as u32)],
+                &outputs[next_random(&mut seed, outputs.len() as u32)],
+            );
+            circuit.swap_outputs(a, b);
+            let swaps: Vec<(String, String)> = circuit.adder_swaps("x", "y", "z").unwrap();
+            for (a, b) in &swaps {
+                circuit.swap_outputs(a, b);
+            }
+            assert!(swaps.len() <= 1);
+            assert_eq!(circuit.adder_swaps("x", "y", "z"), Some(vec![]));
+            circuit.set_number("x", (1 << bits) - 1);
+            circuit.set_number("y", 1);
+            assert_eq!(circuit.evaluate().unwrap().number("z"), Some(1 << bits));
+        }
+    }
+
+    #[test]
+    fn broken_adders() {
+        //A missing input gate used to look like a correct adder
+        let without =
+            |line: &str| -> Circuit { adder(4).replace(&format!("{line}\n"), "").parse().unwrap() };
+        assert_eq!(
+            without("x01 AND y01 -> a01").adder_swaps("x", "y", "z"),
+            None
+        );
+        assert_eq!(
+            without("x02 XOR y02 -> s02").adder_swaps("x", "y", "z"),
+            None
+        );
+    }
+}
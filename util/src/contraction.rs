@@ -0,0 +1,270 @@
+use crate::coord::Pos;
+use crate::graph::{Graph, NodeMap};
+use fxhash::{FxHashMap, FxHashSet};
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+//Points of interest of an implicit graph (junctions, keys or doors of a grid maze...),
+//linked by the number of steps of the walks between them
+//The starts are always kept, every point of interest reached is explored in turn
+#[derive(Clone, Debug)]
+pub struct Contraction<N> {
+    pub nodes: NodeMap<N>,
+    pub graph: Graph,
+    //Points of interest met strictly between the ends of each edge, in walking order
+    through: FxHashMap<(usize, usize), Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash> Contraction<N> {
+    //Walks stop at the first point of interest met, so only the adjacent ones are linked
+    //Each exit of a point of interest is walked on its own, parallel corridors give parallel edges
+    pub fn new<S, F, I, P>(starts: S, neighbours: F, is_poi: P) -> Self
+    where
+        S: IntoIterator<Item = N>,
+        F: Fn(&N) -> I,
+        I: IntoIterator<Item = N>,
+        P: Fn(&N) -> bool,
+    {
+        Self::build(starts, neighbours, is_poi, true)
+    }
+
+    //Walks go through the points of interest, so every reachable pair is linked
+    //The points of interest passed on the way are recorded, see "through"
+    pub fn all_pairs<S, F, I, P>(starts: S, neighbours: F, is_poi: P) -> Self
+    where
+        S: IntoIterator<Item = N>,
+        F: Fn(&N) -> I,
+        I: IntoIterator<Item = N>,
+        P: Fn(&N) -> bool,
+    {
+        Self::build(starts, neighbours, is_poi, false)
+    }
+
+    fn build<S, F, I, P>(starts: S, neighbours: F, is_poi: P, stop_at_poi: bool) -> Self
+    where
+        S: IntoIterator<Item = N>,
+        F: Fn(&N) -> I,
+        I: IntoIterator<Item = N>,
+        P: Fn(&N) -> bool,
+    {
+        let mut nodes: NodeMap<N> = NodeMap::new();
+        starts.into_iter().for_each(|s| {
+            nodes.id(s);
+        });
+        let mut edges: Vec<(usize, usize, usize)> = Vec::new();
+        let mut through: FxHashMap<(usize, usize), Vec<N>> = FxHashMap::default();
+
+        //New points of interest get the next ids, so exploring them in id order reaches them all
+        let mut from: usize = 0;
+        while from < nodes.len() {
+            let start: N = nodes.name(from).clone();
+            if stop_at_poi {
+                //Each exit on its own, the walk never comes back through the start
+                for exit in neighbours(&start) {
+                    let mut seen: FxHashSet<N> =
+                        FxHashSet::from_iter([start.clone(), exit.clone()]);
+                    let mut queue: VecDeque<(N, usize)> = VecDeque::from([(exit, 1)]);
+                    while let Some((node, steps)) = queue.pop_front() {
+                        if is_poi(&node) {
+                            edges.push((from, nodes.id(node), steps));
+                            continue;
+                        }
+                        for next in neighbours(&node) {
+                            if seen.insert(next.clone()) {
+                                queue.push_back((next, steps + 1));
+                            }
+                        }
+                    }
+                }
+            } else {
+                //Shortest walks to every point of interest, remembering the ones passed on the way
+                let mut parents: FxHashMap<N, Option<N>> = FxHashMap::default();
+                parents.insert(start.clone(), None);
+                let mut queue: VecDeque<(N, usize)> = VecDeque::from([(start, 0)]);
+                while let Some((node, steps)) = queue.pop_front() {
+                    if steps > 0 && is_poi(&node) {
+                        let to: usize = nodes.id(node.clone());
+                        edges.push((from, to, steps));
+                        let mut on_the_way: Vec<N> = Vec::new();
+                        let mut current: &N = &node;
+                        while let Some(Some(parent)) = parents.get(current) {
+                            if parents[parent].is_some() && is_poi(parent) {
+                                on_the_way.push(parent.clone());
+                            }
+                            current = parent;
+                        }
+                        on_the_way.reverse();
+                        through.insert((from, to), on_the_way);
+                    }
+                    for next in neighbours(&node) {
+                        if !parents.contains_key(&next) {
+                            parents.insert(next.clone(), Some(node.clone()));
+                            queue.push_back((next, steps + 1));
+                        }
+                    }
+                }
+            }
+            from += 1;
+        }
+
+        let mut graph: Graph = Graph::new(nodes.len());
+        edges
+            .into_iter()
+            .for_each(|(from, to, steps)| graph.add_weighted_edge(from, to, steps));
+        Contraction {
+            nodes,
+            graph,
+            through,
+        }
+    }
+
+    pub fn id(&self, node: &N) -> Option<usize> {
+        self.nodes.get(node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    //Steps of the edge from "from" to "to", if they are linked
+    pub fn steps(&self, from: usize, to: usize) -> Option<usize> {
+        self.graph
+            .edges(from)
+            .iter()
+            .filter(|&&(target, _)| target == to)
+            .map(|&(_, w)| w)
+            .min()
+    }
+
+    //Points of interest passed on the edge from "from" to "to", always empty when built with new
+    pub fn through(&self, from: usize, to: usize) -> &[N] {
+        self.through.get(&(from, to)).map_or(&[], |v| v.as_slice())
+    }
+
+    //Shortest number of steps between any two nodes, chaining the edges
+    pub fn distances(&self) -> Vec<Vec<Option<usize>>> {
+        self.graph.all_pairs_distances()
+    }
+}
+
+impl Contraction<Pos> {
+    //Corridors of a grid maze, between the junctions (three open neighbours or more) and the starts
+    pub fn grid_junctions<T, S, F>(grid: &[Vec<T>], starts: S, open: F) -> Self
+    where
+        S: IntoIterator<Item = Pos>,
+        F: Fn(&T) -> bool,
+    {
+        let (max_x, max_y) = (grid[0].len(), grid.len());
+        let is_open = |&Pos(x, y): &Pos| open(&grid[y][x]);
+        let starts: Vec<Pos> = starts.into_iter().collect();
+        let neighbours = |p: &Pos| -> Vec<Pos> {
+            p.neighbours_safe(max_x, max_y)
+                .into_iter()
+                .filter(is_open)
+                .collect()
+        };
+        let is_junction = |p: &Pos| starts.contains(p) || neighbours(p).len() >= 3;
+        Self::new(starts.iter().copied(), neighbours, is_junction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::longest_simple_path;
+
+    fn grid(s: &str) -> Vec<Vec<char>> {
+        s.lines().map(|l| l.chars().collect()).collect()
+    }
+
+    fn find(grid: &[Vec<char>], c: char) -> Pos {
+        grid.iter()
+            .enumerate()
+            .find_map(|(y, row)| row.iter().position(|&t| t == c).map(|x| Pos(x, y)))
+            .unwrap()
+    }
+
+    #[test]
+    fn junctions() {
+        let maze: Vec<Vec<char>> = grid(
+            "#S#######
+#...#...#
+#.#.#.#.#
+#.......#
+#######E#",
+        );
+        let (start, end) = (find(&maze, 'S'), find(&maze, 'E'));
+        let c: Contraction<Pos> = Contraction::grid_junctions(&maze, [start, end], |&t| t != '#');
+        //Start, end, and the junctions at (1, 1), (3, 3), (5, 3) and (7, 3)
+        assert_eq!(c.len(), 6);
+        let (s, e) = (c.id(&start).unwrap(), c.id(&end).unwrap());
+        let j: Vec<usize> = [Pos(1, 1), Pos(3, 3), Pos(5, 3), Pos(7, 3)]
+            .iter()
+            .map(|p| c.id(p).unwrap())
+            .collect();
+        assert_eq!(c.steps(s, j[0]), Some(1));
+        assert_eq!(c.steps(s, j[1]), None);
+        assert_eq!(c.steps(j[0], j[1]), Some(4));
+        assert_eq!(c.steps(j[1], j[2]), Some(2));
+        //Two corridors link these junctions, with 2 and 6 steps
+        assert_eq!(c.steps(j[2], j[3]), Some(2));
+        assert_eq!(c.steps(e, j[3]), Some(1));
+        assert_eq!(c.distances()[s][e], Some(10));
+        assert!(c.through(s, j[0]).is_empty());
+        let mut parallel: Vec<usize> = c
+            .graph
+            .edges(j[2])
+            .iter()
+            .filter(|&&(to, _)| to == j[3])
+            .map(|&(_, w)| w)
+            .collect();
+        parallel.sort();
+        assert_eq!(parallel, vec![2, 6]);
+        //The longest hike takes the 6 steps corridor
+        assert_eq!(longest_simple_path(&c.graph, s, e), Some(14));
+    }
+
+    #[test]
+    fn keys_and_doors() {
+        //2019 day 18 example
+        let maze: Vec<Vec<char>> = grid(
+            "########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################",
+        );
+        let (max_x, max_y) = (maze[0].len(), maze.len());
+        let c: Contraction<Pos> = Contraction::all_pairs(
+            [find(&maze, '@')],
+            |p: &Pos| {
+                p.neighbours_safe(max_x, max_y)
+                    .into_iter()
+                    .filter(|&Pos(x, y)| maze[y][x] != '#')
+                    .collect::<Vec<Pos>>()
+            },
+            |&Pos(x, y)| maze[y][x].is_ascii_alphabetic(),
+        );
+        let key = |k: char| c.id(&find(&maze, k)).unwrap();
+        let at = |p: Pos| maze[p.1][p.0];
+        assert_eq!(c.steps(key('@'), key('a')), Some(2));
+        assert_eq!(c.steps(key('b'), key('c')), Some(10));
+        let passed: Vec<char> = c
+            .through(key('a'), key('f'))
+            .iter()
+            .map(|&p| at(p))
+            .collect();
+        assert_eq!(passed, vec!['A', 'b', 'C', 'e', 'E', 'D']);
+        let passed: Vec<char> = c
+            .through(key('c'), key('d'))
+            .iter()
+            .map(|&p| at(p))
+            .collect();
+        assert!(passed.is_empty());
+        assert_eq!(c.steps(key('c'), key('d')), Some(24));
+    }
+}
@@ -1,17 +1,11 @@
 use itertools::Itertools;
 
+//Grid positions, with the bounded, diagonal and torus neighbours of 2D puzzles
+//Other dimensions use vector::Vector, From converts between them
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Pos(pub usize, pub usize);
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct PosI(pub isize, pub isize);
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Pos3(pub usize, pub usize, pub usize);
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Pos3I(pub isize, pub isize, pub isize);
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Pos4(pub usize, pub usize, pub usize, pub usize);
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Pos4I(pub isize, pub isize, pub isize, pub isize);
 
 impl Pos {
     pub fn distance(&self, Pos(x, y): Pos) -> usize {
@@ -19,52 +13,69 @@ impl Pos {
     }
 
     pub fn neighbours_safe(&self, max_x: usize, max_y: usize) -> Vec<Pos> {
-        let x: isize = self.0 as isize;
-        let y: isize = self.1 as isize;
-        let i = PosI(x, y);
-        i.neighbours()
+        self.neighbours()
             .into_iter()
-            .filter_map(|PosI(a, b)| {
-                if a >= 0 && a < max_x as isize && b >= 0 && b < max_y as isize {
-                    Some(Pos(a as usize, b as usize))
-                } else {
-                    None
-                }
-            })
+            .filter(|&Pos(x, y)| x < max_x && y < max_y)
             .collect()
     }
 
+    //Only the neighbours that can be represented, nothing is returned left of 0
     pub fn neighbours(&self) -> Vec<Pos> {
-        let &Pos(x, y) = self;
-        vec![Pos(x - 1, y), Pos(x + 1, y), Pos(x, y - 1), Pos(x, y + 1)]
+        [PosI(-1, 0), PosI(1, 0), PosI(0, -1), PosI(0, 1)]
+            .into_iter()
+            .filter_map(|d| self.checked_add(d))
+            .collect()
+    }
+
+    pub fn checked_add(&self, PosI(dx, dy): PosI) -> Option<Pos> {
+        Some(Pos(
+            self.0.checked_add_signed(dx)?,
+            self.1.checked_add_signed(dy)?,
+        ))
+    }
+
+    //Moves by the given delta, None if we end up outside of [0, max_x[ x [0, max_y[
+    pub fn add_bounded(&self, delta: PosI, max_x: usize, max_y: usize) -> Option<Pos> {
+        self.checked_add(delta)
+            .filter(|&Pos(x, y)| x < max_x && y < max_y)
+    }
+
+    //Moves by the given delta on a torus of size max_x * max_y
+    pub fn add_torus(&self, delta: PosI, max_x: usize, max_y: usize) -> Pos {
+        let PosI(x, y) = PosI(self.0 as isize, self.1 as isize)
+            .add(delta)
+            .wrap(max_x as isize, max_y as isize);
+        Pos(x as usize, y as usize)
+    }
+
+    pub fn neighbours_torus(&self, max_x: usize, max_y: usize) -> Vec<Pos> {
+        PosI(0, 0)
+            .neighbours()
+            .into_iter()
+            .map(|d| self.add_torus(d, max_x, max_y))
+            .collect()
+    }
+
+    pub fn neighbours_diag_torus(&self, max_x: usize, max_y: usize) -> Vec<Pos> {
+        PosI(0, 0)
+            .neighbours_diag()
+            .into_iter()
+            .map(|d| self.add_torus(d, max_x, max_y))
+            .collect()
     }
 
     pub fn neighbours_diag_safe(&self, max_x: usize, max_y: usize) -> Vec<Pos> {
-        let x: isize = self.0 as isize;
-        let y: isize = self.1 as isize;
-        let i = PosI(x, y);
-        i.neighbours_diag()
+        self.neighbours_diag()
             .into_iter()
-            .filter_map(|PosI(a, b)| {
-                if a >= 0 && a < max_x as isize && b >= 0 && b < max_y as isize {
-                    Some(Pos(a as usize, b as usize))
-                } else {
-                    None
-                }
-            })
+            .filter(|&Pos(x, y)| x < max_x && y < max_y)
             .collect()
     }
 
     pub fn neighbours_diag(&self) -> Vec<Pos> {
-        (-1..=1)
-            .cartesian_product(-1..=1)
-            .filter(|(i, j)| *i != 0 || *j != 0)
-            .map(|(i, j)| {
-                Pos(
-                    (self.0 as isize + i) as usize,
-                    (self.1 as isize + j) as usize,
-                )
-            })
+        PosI(0, 0)
+            .neighbours_diag()
+            .into_iter()
+            .filter_map(|d| self.checked_add(d))
             .collect()
     }
 }
@@ -106,30 +117,39 @@ impl PosI {
     pub fn add(&self, PosI(x, y): PosI) -> PosI {
         PosI(self.0 + x, self.1 + y)
     }
-}
-
-impl Pos3 {
-    pub fn distance_squared(&self, Pos3(x, y, z): Pos3) -> usize {
-        self.0.abs_diff(x).pow(2u32) + self.1.abs_diff(y).pow(2u32) + self.2.abs_diff(z).pow(2u32)
-    }
-}
-
-impl Pos3I {
-    pub fn distance(&self, Pos3I(x, y, z): Pos3I) -> usize {
-        self.0.abs_diff(x) + self.1.abs_diff(y) + self.2.abs_diff(z)
-    }
 
-    pub fn sub(&self, Pos3I(x, y, z): Pos3I) -> Pos3I {
-        Pos3I(self.0 - x, self.1 - y, self.2 - z)
+    //Brings the position back inside [0, max_x[ x [0, max_y[, as on a torus
+    pub fn wrap(&self, max_x: isize, max_y: isize) -> PosI {
+        PosI(self.0.rem_euclid(max_x), self.1.rem_euclid(max_y))
     }
 
-    pub fn add(&self, Pos3I(x, y, z): Pos3I) -> Pos3I {
-        Pos3I(self.0 + x, self.1 + y, self.2 + z)
+    pub fn add_torus(&self, delta: PosI, max_x: isize, max_y: isize) -> PosI {
+        self.add(delta).wrap(max_x, max_y)
     }
 }
 
-impl Pos4I {
-    pub fn distance(&self, Pos4I(w, x, y, z): Pos4I) -> usize {
-        self.0.abs_diff(w) + self.1.abs_diff(x) + self.2.abs_diff(y) + self.3.abs_diff(z)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbours_on_the_edge() {
+        assert_eq!(Pos(0, 0).neighbours(), vec![Pos(1, 0), Pos(0, 1)]);
+        assert_eq!(
+            Pos(0, 3).neighbours_diag(),
+            vec![Pos(0, 2), Pos(0, 4), Pos(1, 2), Pos(1, 3), Pos(1, 4)]
+        );
+        assert_eq!(Pos(2, 0).neighbours_diag_safe(3, 3).len(), 3);
+        assert_eq!(Pos(0, 0).add_bounded(PosI(2, 1), 2, 2), None);
+    }
+
+    #[test]
+    fn neighbours_on_a_torus() {
+        assert_eq!(
+            Pos(0, 0).neighbours_torus(3, 4),
+            vec![Pos(2, 0), Pos(1, 0), Pos(0, 3), Pos(0, 1)]
+        );
+        assert_eq!(Pos(0, 0).neighbours_diag_torus(3, 4).len(), 8);
+        assert_eq!(PosI(2, 4).add_torus(PosI(-7, 9), 11, 7), PosI(6, 6));
     }
 }
@@ -0,0 +1,204 @@
+use crate::coord::Pos;
+use crate::orientation::Dir;
+use crate::vector::Vector;
+use std::collections::VecDeque;
+
+//Coordinates on the cube are doubled, so every cell center stays on integers
+//The cube spans from -size to size on each axis
+type V3 = Vector<isize, 3>;
+
+fn dot(a: V3, b: V3) -> isize {
+    (0..3).map(|i| a[i] * b[i]).sum()
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Face {
+    tile: Pos,
+    normal: V3,
+    right: V3,
+    down: V3,
+}
+
+impl Face {
+    fn delta(&self, dir: Dir) -> V3 {
+        match dir {
+            Dir::North => -self.down,
+            Dir::East => self.right,
+            Dir::South => self.down,
+            Dir::West => -self.right,
+        }
+    }
+
+    //Fold the net to reach the adjacent face in the given direction
+    fn fold(&self, dir: Dir) -> Face {
+        let delta: V3 = self.delta(dir);
+        let (right, down) = match dir {
+            Dir::North | Dir::South => (self.right, -self.normal * dot(delta, self.down)),
+            Dir::East | Dir::West => (-self.normal * dot(delta, self.right), self.down),
+        };
+        Face {
+            tile: self.tile,
+            normal: delta,
+            right,
+            down,
+        }
+    }
+}
+
+//A cube net laid on a 2D map, with the 6 faces folded in 3D
+#[derive(Clone, Debug)]
+pub struct CubeNet {
+    size: usize,
+    faces: Vec<Face>,
+}
+
+impl CubeNet {
+    //"cells" tells which positions of the map belong to the net, rows can be of different lengths
+    pub fn new(cells: &[Vec<bool>]) -> Result<Self, String> {
+        let area: usize = cells.iter().flatten().filter(|&&b| b).count();
+        let size: usize = (1..=area).find(|n| 6 * n * n >= area).unwrap_or(0);
+        if size == 0 || 6 * size * size != area {
+            return Err(format!("An area of {area} cannot be folded into a cube"));
+        }
+        let is_tile = |Pos(fx, fy): Pos| {
+            cells
+                .get(fy * size)
+                .and_then(|row| row.get(fx * size))
+                .copied()
+                .unwrap_or(false)
+        };
+        let start: Pos = Pos(cells[0].iter().position(|&b| b).unwrap() / size, 0);
+
+        let mut faces: Vec<Face> = vec![Face {
+            tile: start,
+            normal: Vector([0, 0, 1]),
+            right: Vector([1, 0, 0]),
+            down: Vector([0, 1, 0]),
+        }];
+        let mut queue: VecDeque<Face> = VecDeque::from([faces[0]]);
+        while let Some(face) = queue.pop_front() {
+            for dir in [Dir::North, Dir::East, Dir::South, Dir::West] {
+                let next_tile: Option<Pos> = match dir {
+                    Dir::North => face.tile.1.checked_sub(1).map(|y| Pos(face.tile.0, y)),
+                    Dir::East => Some(Pos(face.tile.0 + 1, face.tile.1)),
+                    Dir::South => Some(Pos(face.tile.0, face.tile.1 + 1)),
+                    Dir::West => face.tile.0.checked_sub(1).map(|x| Pos(x, face.tile.1)),
+                };
+                let Some(tile) = next_tile.filter(|&t| is_tile(t)) else {
+                    continue;
+                };
+                if faces.iter().all(|f| f.tile != tile) {
+                    let next: Face = Face {
+                        tile,
+                        ..face.fold(dir)
+                    };
+                    faces.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if faces.len() != 6 {
+            return Err(format!("The net has {} faces instead of 6", faces.len()));
+        }
+        if (0..6).any(|i| (i + 1..6).any(|j| faces[i].normal == faces[j].normal)) {
+            return Err("Two faces of the net overlap once folded".to_string());
+        }
+        Ok(CubeNet { size, faces })
+    }
+
+    pub fn face_size(&self) -> usize {
+        self.size
+    }
+
+    //Index of the face containing the position, None if the position is not on the net
+    pub fn face_of(&self, Pos(x, y): Pos) -> Option<usize> {
+        let tile: Pos = Pos(x / self.size, y / self.size);
+        self.faces.iter().position(|f| f.tile == tile)
+    }
+
+    //Move one cell forward, wrapping around the cube when crossing an edge
+    //Returns the new position and the new direction
+    pub fn step(&self, pos: Pos, dir: Dir) -> (Pos, Dir) {
+        let n: usize = self.size;
+        let face: &Face = &self.faces[self.face_of(pos).expect("Position outside of the net")];
+        let (x, y) = (pos.0 % n, pos.1 % n);
+        let inside: Option<(usize, usize)> = match dir {
+            Dir::North => y.checked_sub(1).map(|y| (x, y)),
+            Dir::East => (x + 1 < n).then_some((x + 1, y)),
+            Dir::South => (y + 1 < n).then_some((x, y + 1)),
+            Dir::West => x.checked_sub(1).map(|x| (x, y)),
+        };
+        if let Some((x, y)) = inside {
+            return (Pos(face.tile.0 * n + x, face.tile.1 * n + y), dir);
+        }
+
+        //Cross the edge: the cell keeps its place along the edge, and goes down the side of the cube
+        let n_i: isize = n as isize;
+        let delta: V3 = face.delta(dir);
+        let cell: V3 = face.normal * n_i
+            + face.right * (2 * x as isize + 1 - n_i)
+            + face.down * (2 * y as isize + 1 - n_i);
+        let target: V3 = cell + delta - face.normal;
+        let next: &Face = self.faces.iter().find(|f| f.normal == delta).unwrap();
+        let new_dir: Dir = [Dir::North, Dir::East, Dir::South, Dir::West]
+            .into_iter()
+            .find(|&d| next.delta(d) == -face.normal)
+            .unwrap();
+        let local = |axis: V3| ((dot(target, axis) + n_i - 1) / 2) as usize;
+        (
+            Pos(
+                next.tile.0 * n + local(next.right),
+                next.tile.1 * n + local(next.down),
+            ),
+            new_dir,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(s: &str) -> Vec<Vec<bool>> {
+        s.split('/')
+            .map(|row| row.chars().map(|c| c != '.').collect())
+            .collect()
+    }
+
+    #[test]
+    fn fold_nets() {
+        assert!(CubeNet::new(&cells("..#./###./..##")).is_ok());
+        assert!(CubeNet::new(&cells(".##/.#./##./#..")).is_ok());
+        assert!(CubeNet::new(&cells("######")).is_err());
+        assert!(CubeNet::new(&cells("###/##")).is_err());
+    }
+
+    #[test]
+    fn step_across_edges() {
+        //Example layout of 2022 day 22, with faces of size 2
+        let net: CubeNet =
+            CubeNet::new(&cells("....##/....##/######/######/....####/....####")).unwrap();
+        assert_eq!(net.face_size(), 2);
+        assert_eq!(net.step(Pos(4, 0), Dir::East), (Pos(5, 0), Dir::East));
+        assert_eq!(net.step(Pos(5, 3), Dir::East), (Pos(6, 4), Dir::South));
+        assert_eq!(net.step(Pos(4, 0), Dir::North), (Pos(1, 2), Dir::South));
+
+        //Walking straight always comes back to the start after 4 faces, on every face and direction
+        for y in 0..6 {
+            for x in 0..8 {
+                if net.face_of(Pos(x, y)).is_none() {
+                    continue;
+                }
+                for dir in [Dir::North, Dir::East, Dir::South, Dir::West] {
+                    let (mut pos, mut d) = (Pos(x, y), dir);
+                    (0..8).for_each(|_| (pos, d) = net.step(pos, d));
+                    assert_eq!((pos, d), (Pos(x, y), dir));
+                    //Stepping back undoes the step
+                    let (p, d) = net.step(Pos(x, y), dir);
+                    assert_eq!(net.step(p, d.half_turn()), (Pos(x, y), dir.half_turn()));
+                }
+            }
+        }
+    }
+}
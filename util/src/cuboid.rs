@@ -0,0 +1,245 @@
+use crate::interval::Interval;
+use crate::num::Integer;
+use crate::vector::Vector;
+
+//Axis-aligned box, one half-open interval per axis
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Cuboid<T, const N: usize>(pub [Interval<T>; N]);
+
+impl<T: Integer, const N: usize> Cuboid<T, N> {
+    //From the min corner (included) to the max corner (excluded)
+    pub fn new(min: Vector<T, N>, max: Vector<T, N>) -> Self {
+        Cuboid(std::array::from_fn(|i| Interval::new(min[i], max[i])))
+    }
+
+    //Both corners are included
+    pub fn closed(min: Vector<T, N>, max: Vector<T, N>) -> Self {
+        Cuboid(std::array::from_fn(|i| Interval::closed(min[i], max[i])))
+    }
+
+    pub fn min(&self) -> Vector<T, N> {
+        Vector(self.0.map(|i| i.start))
+    }
+
+    pub fn max(&self) -> Vector<T, N> {
+        Vector(self.0.map(|i| i.end))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().any(|i| i.is_empty())
+    }
+
+    pub fn volume(&self) -> T {
+        self.0.iter().fold(T::ONE, |acc, i| acc * i.len())
+    }
+
+    pub fn contains(&self, point: &Vector<T, N>) -> bool {
+        (0..N).all(|i| self.0[i].contains(point[i]))
+    }
+
+    //True when every point of "other" is in this cuboid
+    pub fn contains_cuboid(&self, other: &Cuboid<T, N>) -> bool {
+        other.is_empty()
+            || (0..N)
+                .all(|i| self.0[i].start <= other.0[i].start && other.0[i].end <= self.0[i].end)
+    }
+
+    pub fn intersection(&self, other: &Cuboid<T, N>) -> Option<Cuboid<T, N>> {
+        let mut axes: [Interval<T>; N] = self.0;
+        for (axis, o) in axes.iter_mut().zip(other.0.iter()) {
+            *axis = axis.intersection(o)?;
+        }
+        Some(Cuboid(axes))
+    }
+
+    pub fn overlaps(&self, other: &Cuboid<T, N>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    //Point of the cuboid nearest to "point" on every axis, the cuboid should not be empty
+    pub fn closest_point(&self, point: &Vector<T, N>) -> Vector<T, N> {
+        Vector(std::array::from_fn(|i| {
+            point[i].clamp(self.0[i].start, self.0[i].last())
+        }))
+    }
+
+    //Cuts every axis in two halves, giving at most 2^N disjoint non-empty cuboids
+    pub fn bisect(&self) -> Vec<Cuboid<T, N>> {
+        let mut pieces: Vec<Cuboid<T, N>> = vec![*self];
+        for axis in 0..N {
+            let mid: T = self.0[axis].start + self.0[axis].len() / (T::ONE + T::ONE);
+            pieces = pieces
+                .iter()
+                .flat_map(|p| {
+                    let (low, high) = p.0[axis].split_at(mid);
+                    [low, high].into_iter().flatten().map(move |half| {
+                        let mut piece: Cuboid<T, N> = *p;
+                        piece.0[axis] = half;
+                        piece
+                    })
+                })
+                .collect();
+        }
+        pieces
+    }
+
+    //Disjoint pieces covering this cuboid minus "other", at most 2N of them
+    //Each axis in turn, we cut the slabs below and above the intersection
+    pub fn subtract(&self, other: &Cuboid<T, N>) -> Vec<Cuboid<T, N>> {
+        let Some(inter) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let mut pieces: Vec<Cuboid<T, N>> = Vec::new();
+        let mut rest: Cuboid<T, N> = *self;
+        for axis in 0..N {
+            let (below, _) = rest.0[axis].split_at(inter.0[axis].start);
+            let (_, above) = rest.0[axis].split_at(inter.0[axis].end);
+            for slab in [below, above].into_iter().flatten() {
+                let mut piece: Cuboid<T, N> = rest;
+                piece.0[axis] = slab;
+                pieces.push(piece);
+            }
+            rest.0[axis] = inter.0[axis];
+        }
+        pieces
+    }
+}
+
+//Union of cuboids, stored as disjoint pieces
+#[derive(Clone, Debug, Default)]
+pub struct CuboidSet<T, const N: usize> {
+    pieces: Vec<Cuboid<T, N>>,
+}
+
+impl<T: Integer, const N: usize> CuboidSet<T, N> {
+    pub fn new() -> Self {
+        CuboidSet { pieces: Vec::new() }
+    }
+
+    pub fn pieces(&self) -> &[Cuboid<T, N>] {
+        &self.pieces
+    }
+
+    pub fn insert(&mut self, cuboid: Cuboid<T, N>) {
+        if cuboid.is_empty() {
+            return;
+        }
+        //Only the new cuboid is cut, the pieces already in the set are kept whole
+        let mut new_pieces: Vec<Cuboid<T, N>> = vec![cuboid];
+        for p in self.pieces.iter().filter(|p| p.overlaps(&cuboid)) {
+            new_pieces = new_pieces.iter().flat_map(|n| n.subtract(p)).collect();
+        }
+        self.pieces.extend(new_pieces);
+    }
+
+    pub fn remove(&mut self, cuboid: &Cuboid<T, N>) {
+        self.pieces = self
+            .pieces
+            .iter()
+            .flat_map(|p| p.subtract(cuboid))
+            .collect();
+    }
+
+    pub fn volume(&self) -> T {
+        self.pieces.iter().fold(T::ZERO, |acc, p| acc + p.volume())
+    }
+
+    pub fn contains(&self, point: &Vector<T, N>) -> bool {
+        self.pieces.iter().any(|p| p.contains(point))
+    }
+}
+
+impl<T: Integer, const N: usize> FromIterator<Cuboid<T, N>> for CuboidSet<T, N> {
+    fn from_iter<I: IntoIterator<Item = Cuboid<T, N>>>(iter: I) -> Self {
+        let mut set: CuboidSet<T, N> = CuboidSet::new();
+        iter.into_iter().for_each(|c| set.insert(c));
+        set
+    }
+}
+
+pub fn union_volume<T: Integer, const N: usize>(cuboids: &[Cuboid<T, N>]) -> T {
+    cuboids
+        .iter()
+        .copied()
+        .collect::<CuboidSet<T, N>>()
+        .volume()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(min: [i32; 3], max: [i32; 3]) -> Cuboid<i32, 3> {
+        Cuboid::closed(Vector(min), Vector(max))
+    }
+
+    #[test]
+    fn subtract_and_intersect() {
+        let a: Cuboid<i32, 3> = closed([0, 0, 0], [2, 2, 2]);
+        let b: Cuboid<i32, 3> = closed([1, 1, 1], [3, 3, 3]);
+        assert_eq!(
+            a.subtract(&b),
+            vec![
+                closed([0, 0, 0], [0, 2, 2]),
+                closed([1, 0, 0], [2, 0, 2]),
+                closed([1, 1, 0], [2, 2, 0])
+            ]
+        );
+        assert_eq!(a.intersection(&b), Some(closed([1, 1, 1], [2, 2, 2])));
+        assert!(!a.contains_cuboid(&b));
+
+        //A hole in the middle leaves a slab on both sides of every axis
+        let centre: Cuboid<i32, 3> = closed([1, 1, 1], [1, 1, 1]);
+        let pieces: Vec<Cuboid<i32, 3>> = a.subtract(&centre);
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(pieces.iter().map(|p| p.volume()).sum::<i32>(), 26);
+        assert!(pieces.iter().all(|p| !p.overlaps(&centre)));
+        assert!(a.contains_cuboid(&centre));
+        assert_eq!(centre.subtract(&a), vec![]);
+
+        let far: Cuboid<i32, 3> = closed([5, 5, 5], [6, 6, 6]);
+        assert_eq!(a.subtract(&far), vec![a]);
+        assert_eq!(a.intersection(&far), None);
+    }
+
+    #[test]
+    fn reactor_reboot() {
+        //2021 day 22 first example
+        let on_1: Cuboid<i32, 3> = closed([10, 10, 10], [12, 12, 12]);
+        let on_2: Cuboid<i32, 3> = closed([11, 11, 11], [13, 13, 13]);
+        let off: Cuboid<i32, 3> = closed([9, 9, 9], [11, 11, 11]);
+        let on_3: Cuboid<i32, 3> = closed([10, 10, 10], [10, 10, 10]);
+        assert_eq!(union_volume(&[on_1, on_2]), 46);
+
+        let mut set: CuboidSet<i32, 3> = [on_1, on_2].into_iter().collect();
+        set.remove(&off);
+        assert_eq!(set.volume(), 38);
+        assert!(!set.contains(&Vector([10, 10, 10])));
+        set.insert(on_3);
+        assert_eq!(set.volume(), 39);
+        assert!(set.contains(&Vector([10, 10, 10])));
+        assert!(set.contains(&Vector([13, 13, 13])));
+        assert!(!set.contains(&Vector([9, 9, 9])));
+    }
+
+    #[test]
+    fn bisect_and_closest_point() {
+        let c: Cuboid<i32, 3> = closed([0, 0, 0], [2, 3, 0]);
+        //The z axis is a single cell, so it is not cut
+        let halves: Vec<Cuboid<i32, 3>> = c.bisect();
+        assert_eq!(
+            halves,
+            vec![
+                closed([0, 0, 0], [0, 1, 0]),
+                closed([0, 2, 0], [0, 3, 0]),
+                closed([1, 0, 0], [2, 1, 0]),
+                closed([1, 2, 0], [2, 3, 0])
+            ]
+        );
+        let cell: Cuboid<i32, 3> = closed([4, 4, 4], [4, 4, 4]);
+        assert_eq!(cell.bisect(), vec![cell]);
+
+        assert_eq!(c.closest_point(&Vector([4, -3, 0])), Vector([2, 0, 0]));
+        assert_eq!(c.closest_point(&Vector([1, 2, 0])), Vector([1, 2, 0]));
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt::{Display, Formatter};
+
+//Graphviz shapes, see https://graphviz.org/doc/info/shapes.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Shape {
+    Ellipse,
+    Box,
+    Circle,
+    DoubleCircle,
+    Diamond,
+    Trapezium,
+    Plaintext,
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name: &str = match self {
+            Shape::Ellipse => "ellipse",
+            Shape::Box => "box",
+            Shape::Circle => "circle",
+            Shape::DoubleCircle => "doublecircle",
+            Shape::Diamond => "diamond",
+            Shape::Trapezium => "trapezium",
+            Shape::Plaintext => "plaintext",
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn quoted(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+//Writes a graph in the DOT language, to render with "dot -Tsvg graph.dot -o graph.svg"
+//Nodes that are only used in edges get the default shape
+#[derive(Clone, Debug)]
+pub struct Dot {
+    directed: bool,
+    lines: Vec<String>,
+}
+
+impl Dot {
+    pub fn directed() -> Self {
+        Dot {
+            directed: true,
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn undirected() -> Self {
+        Dot {
+            directed: false,
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn node(&mut self, id: &str, label: &str, shape: Shape) -> &mut Self {
+        self.lines.push(format!(
+            "{} [label={}, shape={shape}];",
+            quoted(id),
+            quoted(label)
+        ));
+        self
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str) -> &mut Self {
+        self.push_edge(from, to, String::new())
+    }
+
+    pub fn labelled_edge(&mut self, from: &str, to: &str, label: &str) -> &mut Self {
+        self.push_edge(from, to, format!(" [label={}]", quoted(label)))
+    }
+
+    fn push_edge(&mut self, from: &str, to: &str, attributes: String) -> &mut Self {
+        let arrow: &str = if self.directed { "->" } else { "--" };
+        self.lines.push(format!(
+            "{} {arrow} {}{attributes};",
+            quoted(from),
+            quoted(to)
+        ));
+        self
+    }
+}
+
+impl Display for Dot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} {{", if self.directed { "digraph" } else { "graph" })?;
+        for line in &self.lines {
+            writeln!(f, "  {line}")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+//True when the program got the "--dot" argument, to print its input as a graph instead
+pub fn requested() -> bool {
+    std::env::args().any(|arg| arg == "--dot")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writing() {
+        let mut dot: Dot = Dot::directed();
+        dot.node("a", "Start \"A\"", Shape::Diamond)
+            .edge("a", "b")
+            .labelled_edge("b", "c", "3");
+        assert_eq!(
+            dot.to_string(),
+            "digraph {
+  \"a\" [label=\"Start \\\"A\\\"\", shape=diamond];
+  \"a\" -> \"b\";
+  \"b\" -> \"c\" [label=\"3\"];
+}
+"
+        );
+        let mut dot: Dot = Dot::undirected();
+        dot.edge("a", "b");
+        assert_eq!(dot.to_string(), "graph {\n  \"a\" -- \"b\";\n}\n");
+    }
+}
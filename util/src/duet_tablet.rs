@@ -168,6 +168,78 @@ impl FromStr for DuetOp {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+enum Operand {
+    Reg(usize),
+    Val(isize),
+}
+
+impl Operand {
+    fn value(&self, regs: &[isize]) -> isize {
+        match *self {
+            Operand::Reg(r) => regs[r],
+            Operand::Val(v) => v,
+        }
+    }
+}
+
+//Compiled form of a DuetOp, registers are replaced by their dense index
+#[derive(Debug, Copy, Clone)]
+enum Bytecode {
+    Send(Operand),
+    Receive(Operand),
+    Set(usize, Operand),
+    Add(usize, Operand),
+    Sub(usize, Operand),
+    Mul(usize, Operand),
+    Mod(usize, Operand),
+    JumpGTZ(Operand, Operand),
+    JumpNotZero(Operand, Operand),
+}
+
+#[derive(Debug, Default)]
+struct RegisterMap {
+    indexes: FxHashMap<char, usize>,
+}
+
+impl RegisterMap {
+    fn index(&mut self, r: char) -> usize {
+        let next: usize = self.indexes.len();
+        *self.indexes.entry(r).or_insert(next)
+    }
+
+    fn reg(&mut self, r: char) -> Operand {
+        Operand::Reg(self.index(r))
+    }
+
+    fn compile(&mut self, op: &DuetOp) -> Bytecode {
+        match *op {
+            DuetOp::SendVal(v) => Bytecode::Send(Operand::Val(v)),
+            DuetOp::SendReg(r) => Bytecode::Send(self.reg(r)),
+            DuetOp::ReceiveVal(v) => Bytecode::Receive(Operand::Val(v)),
+            DuetOp::ReceiveReg(r) => Bytecode::Receive(self.reg(r)),
+            DuetOp::SetVal(r, v) => Bytecode::Set(self.index(r), Operand::Val(v)),
+            DuetOp::SetReg(r1, r2) => Bytecode::Set(self.index(r1), self.reg(r2)),
+            DuetOp::AddVal(r, v) => Bytecode::Add(self.index(r), Operand::Val(v)),
+            DuetOp::AddReg(r1, r2) => Bytecode::Add(self.index(r1), self.reg(r2)),
+            DuetOp::SubVal(r, v) => Bytecode::Sub(self.index(r), Operand::Val(v)),
+            DuetOp::SubReg(r1, r2) => Bytecode::Sub(self.index(r1), self.reg(r2)),
+            DuetOp::MulVal(r, v) => Bytecode::Mul(self.index(r), Operand::Val(v)),
+            DuetOp::MulReg(r1, r2) => Bytecode::Mul(self.index(r1), self.reg(r2)),
+            DuetOp::ModVal(r, v) => Bytecode::Mod(self.index(r), Operand::Val(v)),
+            DuetOp::ModReg(r1, r2) => Bytecode::Mod(self.index(r1), self.reg(r2)),
+            DuetOp::JumpGTZValVal(v1, v2) => Bytecode::JumpGTZ(Operand::Val(v1), Operand::Val(v2)),
+            DuetOp::JumpGTZRegVal(r, v) => Bytecode::JumpGTZ(self.reg(r), Operand::Val(v)),
+            DuetOp::JumpGTZRegReg(r1, r2) => Bytecode::JumpGTZ(self.reg(r1), self.reg(r2)),
+            DuetOp::JumpNotZeroValVal(v1, v2) => {
+                Bytecode::JumpNotZero(Operand::Val(v1), Operand::Val(v2))
+            }
+            DuetOp::JumpNotZeroRegVal(r, v) => Bytecode::JumpNotZero(self.reg(r), Operand::Val(v)),
+            DuetOp::JumpNotZeroRegReg(r1, r2) => Bytecode::JumpNotZero(self.reg(r1), self.reg(r2)),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 enum OpResult {
     Nothing,
@@ -177,87 +249,43 @@ enum OpResult {
 
 #[derive(Debug)]
 pub struct DuetTablet {
-    ops: Vec<DuetOp>,
+    code: Vec<Bytecode>,
+    registers: RegisterMap,
 }
 
 impl DuetTablet {
     pub fn play_solo(&self) -> isize {
-        let mut regs: FxHashMap<char, isize> = FxHashMap::default();
+        let mut regs: Vec<isize> = vec![0; self.registers.indexes.len()];
         let mut op: isize = 0;
         let mut sound: isize = 0;
         let mut nb_mul: isize = 0;
 
-        while (op as usize) < self.ops.len() {
-            match self.ops[op as usize] {
+        while (op as usize) < self.code.len() {
+            match self.code[op as usize] {
                 //For solo play, "snd" is the "Sound" instruction
-                DuetOp::SendVal(n) => sound = n,
-                DuetOp::SendReg(r) => sound = *regs.entry(r).or_insert(0),
+                Bytecode::Send(v) => sound = v.value(&regs),
                 //For solo play, "rcv" is the "Recover" instruction
-                DuetOp::ReceiveVal(n) => {
-                    if n != 0 {
-                        return sound;
-                    }
-                }
-                DuetOp::ReceiveReg(r) => {
-                    if *regs.entry(r).or_insert(0) != 0 {
+                Bytecode::Receive(v) => {
+                    if v.value(&regs) != 0 {
                         return sound;
                     }
                 }
-                DuetOp::SetVal(r, v) => {
-                    let _ = regs.insert(r, v);
-                }
-                DuetOp::SetReg(r1, r2) => {
-                    let r2_v: isize = *regs.entry(r2).or_insert(0);
-                    let _ = regs.insert(r1, r2_v);
-                }
-                DuetOp::AddVal(r, v) => *regs.entry(r).or_insert(0) += v,
-                DuetOp::AddReg(r1, r2) => {
-                    *regs.entry(r1).or_insert(0) += *regs.entry(r2).or_insert(0)
-                }
-                DuetOp::SubVal(r, v) => *regs.entry(r).or_insert(0) -= v,
-                DuetOp::SubReg(r1, r2) => {
-                    *regs.entry(r1).or_insert(0) -= *regs.entry(r2).or_insert(0)
-                }
-                DuetOp::MulVal(r, v) => {
-                    *regs.entry(r).or_insert(0) *= v;
-                    nb_mul += 1;
-                }
-                DuetOp::MulReg(r1, r2) => {
-                    *regs.entry(r1).or_insert(0) *= *regs.entry(r2).or_insert(0);
+                Bytecode::Set(r, v) => regs[r] = v.value(&regs),
+                Bytecode::Add(r, v) => regs[r] += v.value(&regs),
+                Bytecode::Sub(r, v) => regs[r] -= v.value(&regs),
+                Bytecode::Mul(r, v) => {
+                    regs[r] *= v.value(&regs);
                     nb_mul += 1;
                 }
-                DuetOp::ModVal(r, v) => *regs.entry(r).or_insert(0) %= v,
-                DuetOp::ModReg(r1, r2) => {
-                    *regs.entry(r1).or_insert(0) %= *regs.entry(r2).or_insert(0)
-                }
-                DuetOp::JumpGTZValVal(v1, v2) => {
-                    if v1 > 0 {
-                        op += v2 - 1;
-                    }
-                }
-                DuetOp::JumpGTZRegVal(r, v) => {
-                    if *regs.entry(r).or_insert(0) > 0 {
-                        op += v - 1;
-                    }
-                }
-                DuetOp::JumpGTZRegReg(r1, r2) => {
-                    if *regs.entry(r1).or_insert(0) > 0 {
-                        op += *regs.entry(r2).or_insert(0) - 1;
-                    }
-                }
-                DuetOp::JumpNotZeroValVal(v1, v2) => {
-                    if v1 != 0 {
-                        op += v2 - 1;
+                Bytecode::Mod(r, v) => regs[r] %= v.value(&regs),
+                Bytecode::JumpGTZ(v1, v2) => {
+                    if v1.value(&regs) > 0 {
+                        op += v2.value(&regs) - 1;
                     }
                 }
-                DuetOp::JumpNotZeroRegVal(r, v) => {
-                    if *regs.entry(r).or_insert(0) != 0 {
-                        op += v - 1;
-                    }
-                }
-                DuetOp::JumpNotZeroRegReg(r1, r2) => {
-                    if *regs.entry(r1).or_insert(0) != 0 {
-                        op += *regs.entry(r2).or_insert(0) - 1;
+                Bytecode::JumpNotZero(v1, v2) => {
+                    if v1.value(&regs) != 0 {
+                        op += v2.value(&regs) - 1;
                     }
                 }
             }
@@ -268,77 +296,42 @@ impl DuetTablet {
 
     fn apply_op(
         &self,
-        regs: &mut FxHashMap<char, isize>,
+        regs: &mut [isize],
         read_queue: &mut VecDeque<isize>,
         write_queue: &mut VecDeque<isize>,
         op: &mut isize,
     ) -> OpResult {
-        if *op < 0 || (*op as usize) >= self.ops.len() {
+        if *op < 0 || (*op as usize) >= self.code.len() {
             return OpResult::Waiting;
         }
 
-        match self.ops[*op as usize] {
-            DuetOp::SendVal(n) => {
-                write_queue.push_back(n);
+        match self.code[*op as usize] {
+            Bytecode::Send(v) => {
+                write_queue.push_back(v.value(regs));
                 *op += 1;
                 return OpResult::SentData;
             }
-            DuetOp::SendReg(r) => {
-                write_queue.push_back(*regs.entry(r).or_insert(0));
-                *op += 1;
-                return OpResult::SentData;
-            }
-            DuetOp::ReceiveVal(_) => (), //Should not happen
-            DuetOp::ReceiveReg(r) => {
+            Bytecode::Receive(Operand::Val(_)) => (), //Should not happen
+            Bytecode::Receive(Operand::Reg(r)) => {
                 if let Some(v) = read_queue.pop_front() {
-                    *regs.entry(r).or_insert(0) = v;
+                    regs[r] = v;
                 } else {
                     return OpResult::Waiting;
                 }
             }
-            DuetOp::SetVal(r, v) => {
-                let _ = regs.insert(r, v);
-            }
-            DuetOp::SetReg(r1, r2) => {
-                let r2_v: isize = *regs.entry(r2).or_insert(0);
-                let _ = regs.insert(r1, r2_v);
-            }
-            DuetOp::AddVal(r, v) => *regs.entry(r).or_insert(0) += v,
-            DuetOp::AddReg(r1, r2) => *regs.entry(r1).or_insert(0) += *regs.entry(r2).or_insert(0),
-            DuetOp::SubVal(r, v) => *regs.entry(r).or_insert(0) -= v,
-            DuetOp::SubReg(r1, r2) => *regs.entry(r1).or_insert(0) -= *regs.entry(r2).or_insert(0),
-            DuetOp::MulVal(r, v) => *regs.entry(r).or_insert(0) *= v,
-            DuetOp::MulReg(r1, r2) => *regs.entry(r1).or_insert(0) *= *regs.entry(r2).or_insert(0),
-            DuetOp::ModVal(r, v) => *regs.entry(r).or_insert(0) %= v,
-            DuetOp::ModReg(r1, r2) => *regs.entry(r1).or_insert(0) %= *regs.entry(r2).or_insert(0),
-            DuetOp::JumpGTZValVal(v1, v2) => {
-                if v1 > 0 {
-                    *op += v2 - 1;
-                }
-            }
-            DuetOp::JumpGTZRegVal(r, v) => {
-                if *regs.entry(r).or_insert(0) > 0 {
-                    *op += v - 1;
-                }
-            }
-            DuetOp::JumpGTZRegReg(r1, r2) => {
-                if *regs.entry(r1).or_insert(0) > 0 {
-                    *op += *regs.entry(r2).or_insert(0) - 1;
-                }
-            }
-            DuetOp::JumpNotZeroValVal(v1, v2) => {
-                if v1 != 0 {
-                    *op += v2 - 1;
-                }
-            }
-            DuetOp::JumpNotZeroRegVal(r, v) => {
-                if *regs.entry(r).or_insert(0) != 0 {
-                    *op += v - 1;
+            Bytecode::Set(r, v) => regs[r] = v.value(regs),
+            Bytecode::Add(r, v) => regs[r] += v.value(regs),
+            Bytecode::Sub(r, v) => regs[r] -= v.value(regs),
+            Bytecode::Mul(r, v) => regs[r] *= v.value(regs),
+            Bytecode::Mod(r, v) => regs[r] %= v.value(regs),
+            Bytecode::JumpGTZ(v1, v2) => {
+                if v1.value(regs) > 0 {
+                    *op += v2.value(regs) - 1;
                 }
             }
-            DuetOp::JumpNotZeroRegReg(r1, r2) => {
-                if *regs.entry(r1).or_insert(0) != 0 {
-                    *op += *regs.entry(r2).or_insert(0) - 1;
+            Bytecode::JumpNotZero(v1, v2) => {
+                if v1.value(regs) != 0 {
+                    *op += v2.value(regs) - 1;
                 }
             }
         }
@@ -348,10 +341,12 @@ impl DuetTablet {
     }
 
     pub fn play_duo(&self) -> isize {
-        let mut regs_zero: FxHashMap<char, isize> = FxHashMap::default();
-        regs_zero.insert('p', 0);
-        let mut regs_one: FxHashMap<char, isize> = FxHashMap::default();
-        regs_one.insert('p', 1);
+        let nb_regs: usize = self.registers.indexes.len();
+        let mut regs_zero: Vec<isize> = vec![0; nb_regs];
+        let mut regs_one: Vec<isize> = vec![0; nb_regs];
+        if let Some(&p) = self.registers.indexes.get(&'p') {
+            regs_one[p] = 1;
+        }
 
         let mut queue_zero: VecDeque<isize> = VecDeque::new();
         let mut queue_one: VecDeque<isize> = VecDeque::new();
@@ -390,6 +385,30 @@ impl FromStr for DuetTablet {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let ops: Vec<DuetOp> = s.lines().map(|l| l.parse().unwrap()).collect();
-        Ok(DuetTablet { ops })
+        //Compile the ops, mapping each register name to a dense index
+        let mut registers: RegisterMap = RegisterMap::default();
+        let code: Vec<Bytecode> = ops.iter().map(|op| registers.compile(op)).collect();
+        Ok(DuetTablet { code, registers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiled_registers() {
+        let tablet: DuetTablet =
+            "set a 3\nset b 1\nmul b a\nadd c b\nsub a 1\njnz a -3\nsnd c\nrcv c"
+                .parse()
+                .unwrap();
+        assert_eq!(tablet.registers.indexes.len(), 3);
+        assert!(matches!(tablet.code[2], Bytecode::Mul(1, Operand::Reg(0))));
+        assert!(matches!(
+            tablet.code[5],
+            Bytecode::JumpNotZero(Operand::Reg(0), Operand::Val(-3))
+        ));
+        //c = 3 + 6 + 6, the last sound played before recovering
+        assert_eq!(tablet.play_solo(), 15);
     }
 }
@@ -0,0 +1,520 @@
+use crate::num::Rational;
+use fxhash::{FxHashMap, FxHashSet};
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    pub fn from_char(c: char) -> Option<Op> {
+        match c {
+            '+' => Some(Op::Add),
+            '-' => Some(Op::Sub),
+            '*' => Some(Op::Mul),
+            '/' => Some(Op::Div),
+            _ => None,
+        }
+    }
+
+    //None when dividing by zero
+    pub fn apply<V: Value>(self, a: V, b: V) -> Option<V> {
+        match self {
+            Op::Add => Some(a + b),
+            Op::Sub => Some(a - b),
+            Op::Mul => Some(a * b),
+            Op::Div => a.divide(b),
+        }
+    }
+}
+
+//What expressions can be evaluated over
+pub trait Value:
+    Copy + Debug + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn from_i128(n: i128) -> Self;
+    //None when dividing by zero
+    fn divide(self, other: Self) -> Option<Self>;
+}
+
+//Integer division rounds towards zero
+impl Value for i128 {
+    fn from_i128(n: i128) -> Self {
+        n
+    }
+
+    fn divide(self, other: Self) -> Option<Self> {
+        self.checked_div(other)
+    }
+}
+
+impl Value for Rational {
+    fn from_i128(n: i128) -> Self {
+        Rational::from_integer(n)
+    }
+
+    fn divide(self, other: Self) -> Option<Self> {
+        self.checked_div(other)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Expr {
+    Num(i128),
+    Var(String),
+    Bin(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn bin(op: Op, left: Expr, right: Expr) -> Expr {
+        Expr::Bin(op, Box::new(left), Box::new(right))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Num(i128),
+    Name(String),
+    Op(Op),
+    Open,
+    Close,
+}
+
+fn tokenize(s: &str) -> Option<Vec<Token>> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() {
+            let mut word: String = String::new();
+            while let Some(&c) = chars
+                .peek()
+                .filter(|c| c.is_ascii_alphanumeric() || **c == '_')
+            {
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(match word.parse() {
+                Ok(n) => Token::Num(n),
+                Err(_) if c.is_ascii_digit() => return None,
+                Err(_) => Token::Name(word),
+            });
+        } else {
+            tokens.push(match c {
+                '(' => Token::Open,
+                ')' => Token::Close,
+                _ => Token::Op(Op::from_char(c)?),
+            });
+            chars.next();
+        }
+    }
+    Some(tokens)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+//Level and associativity of each binary operator, a higher level binds tighter
+#[derive(Clone, Debug, Default)]
+pub struct Precedence {
+    levels: FxHashMap<Op, (u8, Assoc)>,
+}
+
+impl Precedence {
+    pub fn new() -> Self {
+        Precedence {
+            levels: FxHashMap::default(),
+        }
+    }
+
+    pub fn with(mut self, op: Op, level: u8, assoc: Assoc) -> Self {
+        self.levels.insert(op, (level, assoc));
+        self
+    }
+
+    //The usual rules, products and divisions first
+    pub fn standard() -> Self {
+        Precedence::new()
+            .with(Op::Add, 1, Assoc::Left)
+            .with(Op::Sub, 1, Assoc::Left)
+            .with(Op::Mul, 2, Assoc::Left)
+            .with(Op::Div, 2, Assoc::Left)
+    }
+
+    //Every operator at the same level, so that they apply from left to right
+    pub fn flat() -> Self {
+        [Op::Add, Op::Sub, Op::Mul, Op::Div]
+            .into_iter()
+            .fold(Precedence::new(), |p, op| p.with(op, 1, Assoc::Left))
+    }
+
+    //None when the expression is malformed or uses an operator without a level
+    pub fn parse(&self, s: &str) -> Option<Expr> {
+        let tokens: Vec<Token> = tokenize(s)?;
+        let mut pos: usize = 0;
+        let expr: Expr = self.parse_from(&tokens, &mut pos, 0)?;
+        (pos == tokens.len()).then_some(expr)
+    }
+
+    //Pratt parsing: only operators of at least min_level can extend the left side
+    fn parse_from(&self, tokens: &[Token], pos: &mut usize, min_level: u8) -> Option<Expr> {
+        let mut left: Expr = match tokens.get(*pos)? {
+            Token::Num(n) => Expr::Num(*n),
+            Token::Name(name) => Expr::Var(name.clone()),
+            Token::Open => {
+                *pos += 1;
+                let inner: Expr = self.parse_from(tokens, pos, 0)?;
+                if tokens.get(*pos) != Some(&Token::Close) {
+                    return None;
+                }
+                inner
+            }
+            _ => return None,
+        };
+        *pos += 1;
+
+        while let Some(Token::Op(op)) = tokens.get(*pos) {
+            let &(level, assoc) = self.levels.get(op)?;
+            if level < min_level {
+                break;
+            }
+            *pos += 1;
+            let next_min: u8 = match assoc {
+                Assoc::Left => level + 1,
+                Assoc::Right => level,
+            };
+            let right: Expr = self.parse_from(tokens, pos, next_min)?;
+            left = Expr::bin(*op, left, right);
+        }
+        Some(left)
+    }
+}
+
+//Raised when a definition refers back to itself, with the variable met twice
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cycle(pub String);
+
+//Named expressions, a variable is replaced by its definition when evaluating
+#[derive(Clone, Debug, Default)]
+pub struct Scope {
+    defs: FxHashMap<String, Expr>,
+}
+
+impl Scope {
+    pub fn new() -> Self {
+        Scope {
+            defs: FxHashMap::default(),
+        }
+    }
+
+    pub fn define(&mut self, name: &str, expr: Expr) {
+        self.defs.insert(name.to_string(), expr);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Expr> {
+        self.defs.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Expr> {
+        self.defs.remove(name)
+    }
+
+    //None when a variable is not defined or on a division by zero
+    pub fn eval<V: Value>(&self, expr: &Expr) -> Result<Option<V>, Cycle> {
+        self.eval_visiting(expr, &mut FxHashSet::default())
+    }
+
+    pub fn value<V: Value>(&self, name: &str) -> Result<Option<V>, Cycle> {
+        self.eval(&Expr::Var(name.to_string()))
+    }
+
+    //"visiting" holds the variables whose definition is being evaluated, meeting one again is a cycle
+    fn eval_visiting<'a, V: Value>(
+        &'a self,
+        expr: &'a Expr,
+        visiting: &mut FxHashSet<&'a str>,
+    ) -> Result<Option<V>, Cycle> {
+        match expr {
+            Expr::Num(n) => Ok(Some(V::from_i128(*n))),
+            Expr::Var(name) => {
+                let Some(def) = self.defs.get(name) else {
+                    return Ok(None);
+                };
+                if !visiting.insert(name) {
+                    return Err(Cycle(name.clone()));
+                }
+                let value: Option<V> = self.eval_visiting(def, visiting)?;
+                visiting.remove(name.as_str());
+                Ok(value)
+            }
+            Expr::Bin(op, l, r) => {
+                let (Some(l), Some(r)) = (
+                    self.eval_visiting(l, visiting)?,
+                    self.eval_visiting(r, visiting)?,
+                ) else {
+                    return Ok(None);
+                };
+                Ok(op.apply(l, r))
+            }
+        }
+    }
+
+    fn depends_on<'a>(
+        &'a self,
+        expr: &'a Expr,
+        unknown: &str,
+        visiting: &mut FxHashSet<&'a str>,
+    ) -> Result<bool, Cycle> {
+        match expr {
+            Expr::Num(_) => Ok(false),
+            Expr::Var(name) if name == unknown => Ok(true),
+            Expr::Var(name) => {
+                let Some(def) = self.defs.get(name) else {
+                    return Ok(false);
+                };
+                if !visiting.insert(name) {
+                    return Err(Cycle(name.clone()));
+                }
+                let depends: bool = self.depends_on(def, unknown, visiting)?;
+                visiting.remove(name.as_str());
+                Ok(depends)
+            }
+            Expr::Bin(_, l, r) => {
+                Ok(self.depends_on(l, unknown, visiting)?
+                    || self.depends_on(r, unknown, visiting)?)
+            }
+        }
+    }
+
+    //Value of the unknown variable so that both sides are equal, its definition is ignored
+    //The unknown has to appear only once, each operation is undone in turn from the top
+    pub fn solve<V: Value>(
+        &self,
+        left: &Expr,
+        right: &Expr,
+        unknown: &str,
+    ) -> Result<Option<V>, Cycle> {
+        let depends = |e: &Expr| self.depends_on(e, unknown, &mut FxHashSet::default());
+        let (mut expr, target): (&Expr, Option<V>) = match (depends(left)?, depends(right)?) {
+            (true, false) => (left, self.eval(right)?),
+            (false, true) => (right, self.eval(left)?),
+            _ => return Ok(None),
+        };
+        let Some(mut target) = target else {
+            return Ok(None);
+        };
+        //depends_on stops at the first use of the unknown, so it may have missed a cycle on the way
+        let mut followed: FxHashSet<&str> = FxHashSet::default();
+        loop {
+            match expr {
+                Expr::Num(_) => return Ok(None),
+                Expr::Var(name) if name == unknown => return Ok(Some(target)),
+                Expr::Var(name) => {
+                    if !followed.insert(name) {
+                        return Err(Cycle(name.clone()));
+                    }
+                    match self.defs.get(name) {
+                        Some(def) => expr = def,
+                        None => return Ok(None),
+                    }
+                }
+                Expr::Bin(op, l, r) => {
+                    let (operand, unknown_left): (&Expr, bool) = match (depends(l)?, depends(r)?) {
+                        (true, false) => (r, true),
+                        (false, true) => (l, false),
+                        _ => return Ok(None),
+                    };
+                    let Some(value) = self.eval::<V>(operand)? else {
+                        return Ok(None);
+                    };
+                    let undone: Option<V> = match (op, unknown_left) {
+                        //target = x op value
+                        (Op::Add, true) => Some(target - value),
+                        (Op::Sub, true) => Some(target + value),
+                        (Op::Mul, true) => target.divide(value),
+                        (Op::Div, true) => Some(target * value),
+                        //target = value op x
+                        (Op::Add, false) => Some(target - value),
+                        (Op::Sub, false) => Some(value - target),
+                        (Op::Mul, false) => target.divide(value),
+                        (Op::Div, false) => value.divide(target),
+                    };
+                    let Some(undone) = undone else {
+                        return Ok(None);
+                    };
+                    target = undone;
+                    expr = if unknown_left { l } else { r };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence() {
+        let flat: Precedence = Precedence::flat();
+        let add_first: Precedence =
+            Precedence::new()
+                .with(Op::Add, 2, Assoc::Left)
+                .with(Op::Mul, 1, Assoc::Left);
+        let scope: Scope = Scope::new();
+        //2020 day 18 examples
+        for (s, flat_value, add_first_value) in [
+            ("1 + 2 * 3 + 4 * 5 + 6", 71, 231),
+            ("2 * 3 + (4 * 5)", 26, 46),
+            (
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                13632,
+                23340,
+            ),
+        ] {
+            assert_eq!(
+                scope.eval::<i128>(&flat.parse(s).unwrap()),
+                Ok(Some(flat_value))
+            );
+            assert_eq!(
+                scope.eval::<i128>(&add_first.parse(s).unwrap()),
+                Ok(Some(add_first_value))
+            );
+        }
+        let standard: Precedence = Precedence::standard();
+        assert_eq!(
+            scope.eval::<i128>(&standard.parse("2 + 3 * 4 - 6 / 2").unwrap()),
+            Ok(Some(11))
+        );
+        //Associativity
+        assert_eq!(
+            scope.eval::<i128>(&standard.parse("10 - 4 - 3").unwrap()),
+            Ok(Some(3))
+        );
+        let right: Precedence = Precedence::new().with(Op::Sub, 1, Assoc::Right);
+        assert_eq!(
+            scope.eval::<i128>(&right.parse("10 - 4 - 3").unwrap()),
+            Ok(Some(9))
+        );
+        //Malformed or unknown operators
+        assert_eq!(standard.parse("(1 + 2"), None);
+        assert_eq!(standard.parse("1 + 2)"), None);
+        assert_eq!(standard.parse("1 +"), None);
+        assert_eq!(add_first.parse("1 - 2"), None);
+        assert_eq!(standard.parse("1 % 2"), None);
+    }
+
+    #[test]
+    fn values() {
+        let standard: Precedence = Precedence::standard();
+        let mut scope: Scope = Scope::new();
+        scope.define("a", standard.parse("7 / 2").unwrap());
+        scope.define("b", standard.parse("a * 2").unwrap());
+        assert_eq!(scope.value::<i128>("b"), Ok(Some(6)));
+        assert_eq!(
+            scope.value::<Rational>("b"),
+            Ok(Some(Rational::from_integer(7)))
+        );
+        assert_eq!(scope.value::<i128>("c"), Ok(None));
+        scope.define("c", standard.parse("b / (a - a)").unwrap());
+        assert_eq!(scope.value::<i128>("c"), Ok(None));
+    }
+
+    #[test]
+    fn solving() {
+        //2022 day 21 example
+        let monkeys: &str = "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+        let standard: Precedence = Precedence::standard();
+        let mut scope: Scope = Scope::new();
+        for l in monkeys.lines() {
+            let (name, expr) = l.split_once(": ").unwrap();
+            scope.define(name, standard.parse(expr).unwrap());
+        }
+        assert_eq!(scope.value::<i128>("root"), Ok(Some(152)));
+        let Some(Expr::Bin(_, left, right)) = scope.get("root").cloned() else {
+            panic!("root is an operation")
+        };
+        assert_eq!(scope.solve::<i128>(&left, &right, "humn"), Ok(Some(301)));
+        assert_eq!(
+            scope.solve::<Rational>(&left, &right, "humn"),
+            Ok(Some(Rational::from_integer(301)))
+        );
+        //Every operation, with the unknown on each side
+        for s in [
+            "x + 3", "3 + x", "x - 3", "3 - x", "x * 3", "3 * x", "x / 3", "3 / x",
+        ] {
+            let expr: Expr = standard.parse(s).unwrap();
+            let mut with_x: Scope = Scope::new();
+            with_x.define("x", Expr::Num(6));
+            let target: Rational = with_x.eval(&expr).unwrap().unwrap();
+            let solved: Rational = with_x
+                .solve(
+                    &expr,
+                    &Expr::bin(
+                        Op::Div,
+                        Expr::Num(target.numer()),
+                        Expr::Num(target.denom()),
+                    ),
+                    "x",
+                )
+                .unwrap()
+                .unwrap();
+            assert_eq!(solved, Rational::from_integer(6), "{s}");
+        }
+        //The unknown on both sides
+        assert_eq!(
+            scope.solve::<i128>(
+                &Expr::Var("humn".to_string()),
+                &Expr::Var("humn".to_string()),
+                "humn"
+            ),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn cycles() {
+        let standard: Precedence = Precedence::standard();
+        let mut scope: Scope = Scope::new();
+        scope.define("a", standard.parse("b + 1").unwrap());
+        scope.define("b", standard.parse("a * 2").unwrap());
+        scope.define("c", standard.parse("x + a").unwrap());
+        assert_eq!(scope.value::<i128>("a"), Err(Cycle("a".to_string())));
+        assert_eq!(scope.value::<i128>("b"), Err(Cycle("b".to_string())));
+        assert_eq!(
+            scope.solve::<i128>(&Expr::Var("c".to_string()), &Expr::Num(3), "x"),
+            Err(Cycle("a".to_string()))
+        );
+        //Using a variable twice is not a cycle
+        scope.define("a", standard.parse("d * d").unwrap());
+        scope.define("d", Expr::Num(3));
+        assert_eq!(scope.value::<i128>("b"), Ok(Some(18)));
+        assert_eq!(
+            scope.solve::<i128>(&Expr::Var("c".to_string()), &Expr::Num(3), "x"),
+            Ok(Some(-6))
+        );
+    }
+}
@@ -0,0 +1,998 @@
+use crate::dot::{Dot, Shape};
+use fxhash::{FxHashMap, FxHashSet};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+
+//Gives a dense id to each node name, in order of first appearance
+#[derive(Clone, Debug)]
+pub struct NodeMap<K> {
+    ids: FxHashMap<K, usize>,
+    names: Vec<K>,
+}
+
+impl<K: Clone + Eq + Hash> Default for NodeMap<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash> NodeMap<K> {
+    pub fn new() -> Self {
+        NodeMap {
+            ids: FxHashMap::default(),
+            names: Vec::new(),
+        }
+    }
+
+    //Id of the node, creating it if needed
+    pub fn id(&mut self, name: K) -> usize {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+        let id: usize = self.names.len();
+        self.ids.insert(name.clone(), id);
+        self.names.push(name);
+        id
+    }
+
+    pub fn get(&self, name: &K) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &K {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+//Directed graph with weighted edges, nodes are 0..len
+//An undirected edge is stored once in each direction
+#[derive(Clone, Debug, Default)]
+pub struct Graph {
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl Graph {
+    pub fn new(nb_nodes: usize) -> Self {
+        Graph {
+            edges: vec![Vec::new(); nb_nodes],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    //Makes room for the node if needed
+    fn grow(&mut self, node: usize) {
+        if node >= self.edges.len() {
+            self.edges.resize(node + 1, Vec::new());
+        }
+    }
+
+    pub fn add_weighted_edge(&mut self, from: usize, to: usize, weight: usize) {
+        self.grow(from.max(to));
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_undirected_weighted_edge(&mut self, a: usize, b: usize, weight: usize) {
+        self.add_weighted_edge(a, b, weight);
+        self.add_weighted_edge(b, a, weight);
+    }
+
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize) {
+        self.add_undirected_weighted_edge(a, b, 1);
+    }
+
+    //(target, weight) of each edge leaving the node
+    pub fn edges(&self, node: usize) -> &[(usize, usize)] {
+        &self.edges[node]
+    }
+
+    pub fn neighbours(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.edges[node].iter().map(|&(to, _)| to)
+    }
+
+    pub fn reversed(&self) -> Graph {
+        let mut rev: Graph = Graph::new(self.len());
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, w) in edges {
+                rev.add_weighted_edge(to, from, w);
+            }
+        }
+        rev
+    }
+
+    //Every edge can also be followed backwards
+    pub fn undirected(&self) -> Graph {
+        let mut graph: Graph = self.clone();
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, w) in edges {
+                graph.add_weighted_edge(to, from, w);
+            }
+        }
+        graph
+    }
+
+    //matrix[a][b] is the total weight of the edges from a to b, 0 when there are none
+    pub fn adjacency_matrix(&self) -> Vec<Vec<usize>> {
+        let mut matrix: Vec<Vec<usize>> = vec![vec![0; self.len()]; self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, w) in edges {
+                matrix[from][to] += w;
+            }
+        }
+        matrix
+    }
+
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut degrees: Vec<usize> = vec![0; self.len()];
+        self.edges
+            .iter()
+            .flatten()
+            .for_each(|&(to, _)| degrees[to] += 1);
+        degrees
+    }
+
+    //Nodes without any incoming edge
+    pub fn roots(&self) -> Vec<usize> {
+        self.in_degrees()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(node, d)| (d == 0).then_some(node))
+            .collect()
+    }
+
+    //Number of edges from the start, following their direction
+    pub fn bfs_distances(&self, start: usize) -> Vec<Option<usize>> {
+        let mut distances: Vec<Option<usize>> = vec![None; self.len()];
+        distances[start] = Some(0);
+        let mut queue: VecDeque<usize> = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let d: usize = distances[node].unwrap();
+            for next in self.neighbours(node) {
+                if distances[next].is_none() {
+                    distances[next] = Some(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    pub fn reachable(&self, start: usize) -> Vec<usize> {
+        self.bfs_distances(start)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(node, d)| d.map(|_| node))
+            .collect()
+    }
+
+    //Connected components, ignoring the direction of the edges
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut set: UnionFind = UnionFind::new(self.len());
+        for (from, edges) in self.edges.iter().enumerate() {
+            edges.iter().for_each(|&(to, _)| {
+                set.union(from, to);
+            });
+        }
+        set.groups()
+    }
+
+    //Kahn's algorithm, among the available nodes the one with the smallest key goes first
+    //None if there is a cycle
+    pub fn topological_sort_by_key<K: Ord, F: Fn(usize) -> K>(&self, key: F) -> Option<Vec<usize>> {
+        let mut degrees: Vec<usize> = self.in_degrees();
+        let mut queue: BinaryHeap<Reverse<(K, usize)>> = (0..self.len())
+            .filter(|&node| degrees[node] == 0)
+            .map(|node| Reverse((key(node), node)))
+            .collect();
+        let mut order: Vec<usize> = Vec::with_capacity(self.len());
+        while let Some(Reverse((_, node))) = queue.pop() {
+            order.push(node);
+            for next in self.neighbours(node) {
+                degrees[next] -= 1;
+                if degrees[next] == 0 {
+                    queue.push(Reverse((key(next), next)));
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        self.topological_sort_by_key(|node| node)
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.topological_sort().is_none()
+    }
+
+    //Floyd-Warshall, dist[a][b] is the weight of the lightest path from a to b
+    pub fn all_pairs_distances(&self) -> Vec<Vec<Option<usize>>> {
+        let n: usize = self.len();
+        let mut dist: Vec<Vec<Option<usize>>> = vec![vec![None; n]; n];
+        for (from, edges) in self.edges.iter().enumerate() {
+            dist[from][from] = Some(0);
+            for &(to, w) in edges {
+                if dist[from][to].is_none_or(|d| w < d) {
+                    dist[from][to] = Some(w);
+                }
+            }
+        }
+        for k in 0..n {
+            let through_k: Vec<Option<usize>> = dist[k].clone();
+            for row in dist.iter_mut() {
+                let Some(ak) = row[k] else {
+                    continue;
+                };
+                for (ab, kb) in row.iter_mut().zip(through_k.iter()) {
+                    if let Some(kb) = kb {
+                        if ab.is_none_or(|d| ak + kb < d) {
+                            *ab = Some(ak + kb);
+                        }
+                    }
+                }
+            }
+        }
+        dist
+    }
+
+    //Computes a value for the node from the values of its children, each node is computed once
+    //eval gets the node and its (child, weight, child value) list, None if the graph has a cycle
+    pub fn dag_evaluate<T: Clone, F: Fn(usize, &[(usize, usize, T)]) -> T>(
+        &self,
+        node: usize,
+        eval: F,
+    ) -> Option<T> {
+        let order: Vec<usize> = self.topological_sort()?;
+        let mut needed: Vec<bool> = vec![false; self.len()];
+        self.reachable(node)
+            .into_iter()
+            .for_each(|n| needed[n] = true);
+        let mut memo: Vec<Option<T>> = vec![None; self.len()];
+        //Children come after their parents, so going backwards they are always computed first
+        for &current in order.iter().rev().filter(|&&n| needed[n]) {
+            let children: Vec<(usize, usize, T)> = self.edges[current]
+                .iter()
+                .map(|&(to, w)| (to, w, memo[to].clone().unwrap()))
+                .collect();
+            memo[current] = Some(eval(current, &children));
+        }
+        memo[node].take()
+    }
+
+    //Number of distinct paths going from "from" to "to", None if the graph has a cycle
+    pub fn count_paths(&self, from: usize, to: usize) -> Option<usize> {
+        self.dag_evaluate(from, |node, children| {
+            if node == to {
+                1
+            } else {
+                children.iter().map(|(_, _, n)| n).sum()
+            }
+        })
+    }
+
+    //Graphviz view, label names each node, the edges show their weight unless it is 1
+    pub fn to_dot<F: Fn(usize) -> String>(&self, label: F) -> String {
+        let mut dot: Dot = Dot::directed();
+        for node in 0..self.len() {
+            dot.node(&node.to_string(), &label(node), Shape::Ellipse);
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, w) in edges {
+                let (from, to) = (from.to_string(), to.to_string());
+                match w {
+                    1 => dot.edge(&from, &to),
+                    _ => dot.labelled_edge(&from, &to, &w.to_string()),
+                };
+            }
+        }
+        dot.to_string()
+    }
+}
+
+//A partition of the nodes, with the edges going from one side to the other
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cut {
+    pub weight: usize,
+    pub edges: Vec<(usize, usize)>,
+    pub sides: (Vec<usize>, Vec<usize>),
+}
+
+impl Cut {
+    fn from_side(graph: &Graph, in_first: &[bool]) -> Cut {
+        let crossing: Vec<(usize, usize, usize)> = (0..graph.len())
+            .filter(|&from| in_first[from])
+            .flat_map(|from| graph.edges(from).iter().map(move |&(to, w)| (from, to, w)))
+            .filter(|&(_, to, _)| !in_first[to])
+            .collect();
+        let (first, second): (Vec<usize>, Vec<usize>) =
+            (0..graph.len()).partition(|&node| in_first[node]);
+        Cut {
+            weight: crossing.iter().map(|&(_, _, w)| w).sum(),
+            edges: crossing.iter().map(|&(from, to, _)| (from, to)).collect(),
+            sides: (first, second),
+        }
+    }
+}
+
+//Residual network for Dinic's algorithm, edge i and i ^ 1 are the two directions of a pipe
+struct FlowNetwork {
+    targets: Vec<usize>,
+    capacities: Vec<usize>,
+    out: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    fn new(graph: &Graph) -> Self {
+        let mut network: FlowNetwork = FlowNetwork {
+            targets: Vec::new(),
+            capacities: Vec::new(),
+            out: vec![Vec::new(); graph.len()],
+        };
+        for from in 0..graph.len() {
+            for &(to, w) in graph.edges(from) {
+                network.out[from].push(network.targets.len());
+                network.targets.push(to);
+                network.capacities.push(w);
+                network.out[to].push(network.targets.len());
+                network.targets.push(from);
+                network.capacities.push(0);
+            }
+        }
+        network
+    }
+
+    //Distance from the source in the residual network
+    fn levels(&self, source: usize) -> Vec<Option<usize>> {
+        let mut levels: Vec<Option<usize>> = vec![None; self.out.len()];
+        levels[source] = Some(0);
+        let mut queue: VecDeque<usize> = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &e in self.out[node].iter() {
+                let to: usize = self.targets[e];
+                if self.capacities[e] > 0 && levels[to].is_none() {
+                    levels[to] = Some(levels[node].unwrap() + 1);
+                    queue.push_back(to);
+                }
+            }
+        }
+        levels
+    }
+
+    //Pushes flow along a path going one level further at each step
+    fn augment(
+        &mut self,
+        node: usize,
+        sink: usize,
+        limit: usize,
+        levels: &[Option<usize>],
+        next_edge: &mut [usize],
+    ) -> usize {
+        if node == sink {
+            return limit;
+        }
+        while next_edge[node] < self.out[node].len() {
+            let e: usize = self.out[node][next_edge[node]];
+            let to: usize = self.targets[e];
+            if self.capacities[e] > 0 && levels[to] == levels[node].map(|l| l + 1) {
+                let pushed: usize =
+                    self.augment(to, sink, limit.min(self.capacities[e]), levels, next_edge);
+                if pushed > 0 {
+                    self.capacities[e] -= pushed;
+                    self.capacities[e ^ 1] += pushed;
+                    return pushed;
+                }
+            }
+            next_edge[node] += 1;
+        }
+        0
+    }
+
+    fn max_flow(&mut self, source: usize, sink: usize) -> usize {
+        let mut flow: usize = 0;
+        loop {
+            let levels: Vec<Option<usize>> = self.levels(source);
+            if levels[sink].is_none() {
+                return flow;
+            }
+            let mut next_edge: Vec<usize> = vec![0; self.out.len()];
+            loop {
+                let pushed: usize = self.augment(source, sink, usize::MAX, &levels, &mut next_edge);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+    }
+}
+
+impl Graph {
+    //Edge weights are the capacities
+    pub fn max_flow(&self, source: usize, sink: usize) -> usize {
+        FlowNetwork::new(self).max_flow(source, sink)
+    }
+
+    //Cheapest set of edges to remove so that the sink cannot be reached from the source
+    pub fn min_st_cut(&self, source: usize, sink: usize) -> Cut {
+        let mut network: FlowNetwork = FlowNetwork::new(self);
+        network.max_flow(source, sink);
+        //The source side is what can still be reached in the residual network
+        let in_source_side: Vec<bool> = network
+            .levels(source)
+            .into_iter()
+            .map(|l| l.is_some())
+            .collect();
+        Cut::from_side(self, &in_source_side)
+    }
+
+    //Stoer-Wagner global minimum cut, the graph has to be undirected
+    //A disconnected graph gives a cut of weight 0
+    pub fn min_cut(&self) -> Option<Cut> {
+        let n: usize = self.len();
+        if n < 2 {
+            return None;
+        }
+        let mut adjacency: Vec<FxHashMap<usize, usize>> = vec![FxHashMap::default(); n];
+        for (from, adj) in adjacency.iter_mut().enumerate() {
+            for &(to, w) in self.edges(from) {
+                if from != to {
+                    *adj.entry(to).or_insert(0) += w;
+                }
+            }
+        }
+        //Each remaining node stands for a group of merged original nodes
+        let mut groups: Vec<Vec<usize>> = (0..n).map(|node| vec![node]).collect();
+        let mut remaining: Vec<usize> = (0..n).collect();
+        let mut best: Option<(usize, Vec<usize>)> = None;
+
+        while remaining.len() > 1 {
+            //Maximum adjacency order: always add the node most tightly linked to the added ones
+            let mut weights: Vec<usize> = vec![0; n];
+            let mut added: Vec<bool> = vec![false; n];
+            let mut heap: BinaryHeap<(usize, usize)> = BinaryHeap::from([(0, remaining[0])]);
+            let mut order: Vec<usize> = Vec::with_capacity(remaining.len());
+            while let Some((w, node)) = heap.pop() {
+                if added[node] || w != weights[node] {
+                    continue;
+                }
+                added[node] = true;
+                order.push(node);
+                for (&next, &c) in adjacency[node].iter() {
+                    if !added[next] {
+                        weights[next] += c;
+                        heap.push((weights[next], next));
+                    }
+                }
+            }
+            if order.len() < remaining.len() {
+                //Nothing links the added nodes to the other ones
+                let side: Vec<usize> = order.iter().flat_map(|&g| groups[g].clone()).collect();
+                best = Some((0, side));
+                break;
+            }
+
+            let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
+            if best.as_ref().is_none_or(|(w, _)| weights[t] < *w) {
+                best = Some((weights[t], groups[t].clone()));
+            }
+
+            //Merge t into s
+            let merged: Vec<usize> = std::mem::take(&mut groups[t]);
+            groups[s].extend(merged);
+            let t_edges: FxHashMap<usize, usize> = std::mem::take(&mut adjacency[t]);
+            for (next, c) in t_edges {
+                adjacency[next].remove(&t);
+                if next != s {
+                    *adjacency[s].entry(next).or_insert(0) += c;
+                    *adjacency[next].entry(s).or_insert(0) += c;
+                }
+            }
+            remaining.retain(|&node| node != t);
+        }
+
+        best.map(|(_, side)| {
+            let mut in_first: Vec<bool> = vec![false; n];
+            side.into_iter().for_each(|node| in_first[node] = true);
+            Cut::from_side(self, &in_first)
+        })
+    }
+}
+
+//Cliques ignore the direction and the weight of the edges, as well as self loops
+impl Graph {
+    fn neighbour_sets(&self) -> Vec<FxHashSet<usize>> {
+        let mut sets: Vec<FxHashSet<usize>> = vec![FxHashSet::default(); self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, _) in edges.iter().filter(|&&(to, _)| to != from) {
+                sets[from].insert(to);
+                sets[to].insert(from);
+            }
+        }
+        sets
+    }
+
+    //Bron-Kerbosch with pivoting, "pot" are the nodes that can extend the clique
+    //and "ex" the ones already tried, whose cliques were reported
+    //When only_biggest is set, found holds the best clique so far and smaller branches are pruned
+    fn bron_kerbosch(
+        adj: &[FxHashSet<usize>],
+        clique: &mut Vec<usize>,
+        mut pot: Vec<usize>,
+        mut ex: Vec<usize>,
+        found: &mut Vec<Vec<usize>>,
+        only_biggest: bool,
+    ) {
+        if only_biggest
+            && found
+                .first()
+                .is_some_and(|best| clique.len() + pot.len() <= best.len())
+        {
+            return;
+        }
+        if pot.is_empty() {
+            if ex.is_empty() {
+                let mut sorted: Vec<usize> = clique.clone();
+                sorted.sort_unstable();
+                if only_biggest {
+                    found.clear();
+                }
+                found.push(sorted);
+            }
+            return;
+        }
+        //The pivot leaves the fewest candidates, any maximal clique has one of them or a non-neighbour of the pivot
+        let pivot: usize = pot
+            .iter()
+            .chain(ex.iter())
+            .copied()
+            .max_by_key(|&u| pot.iter().filter(|v| adj[u].contains(v)).count())
+            .unwrap();
+        let candidates: Vec<usize> = pot
+            .iter()
+            .copied()
+            .filter(|v| !adj[pivot].contains(v))
+            .collect();
+        for v in candidates {
+            clique.push(v);
+            let next_pot: Vec<usize> = pot.iter().copied().filter(|u| adj[v].contains(u)).collect();
+            let next_ex: Vec<usize> = ex.iter().copied().filter(|u| adj[v].contains(u)).collect();
+            Self::bron_kerbosch(adj, clique, next_pot, next_ex, found, only_biggest);
+            clique.pop();
+            pot.retain(|&u| u != v);
+            ex.push(v);
+        }
+    }
+
+    //Every clique that cannot be extended by another node, each one sorted
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let mut cliques: Vec<Vec<usize>> = Vec::new();
+        let pot: Vec<usize> = (0..self.len()).collect();
+        Self::bron_kerbosch(
+            &self.neighbour_sets(),
+            &mut Vec::new(),
+            pot,
+            Vec::new(),
+            &mut cliques,
+            false,
+        );
+        cliques
+    }
+
+    //One of the biggest cliques, sorted
+    pub fn max_clique(&self) -> Vec<usize> {
+        let mut best: Vec<Vec<usize>> = Vec::new();
+        let pot: Vec<usize> = (0..self.len()).collect();
+        Self::bron_kerbosch(
+            &self.neighbour_sets(),
+            &mut Vec::new(),
+            pot,
+            Vec::new(),
+            &mut best,
+            true,
+        );
+        best.pop().unwrap_or_default()
+    }
+
+    //Every clique of exactly k nodes, each one sorted
+    pub fn cliques_of_size(&self, k: usize) -> Vec<Vec<usize>> {
+        //Nodes are added in increasing order, so that each clique is found once
+        fn extend(
+            adj: &[FxHashSet<usize>],
+            clique: &mut Vec<usize>,
+            candidates: &[usize],
+            k: usize,
+            cliques: &mut Vec<Vec<usize>>,
+        ) {
+            if clique.len() == k {
+                cliques.push(clique.clone());
+                return;
+            }
+            for (i, &node) in candidates.iter().enumerate() {
+                if clique.len() + candidates.len() - i < k {
+                    break;
+                }
+                let next: Vec<usize> = candidates[i + 1..]
+                    .iter()
+                    .copied()
+                    .filter(|c| adj[node].contains(c))
+                    .collect();
+                clique.push(node);
+                extend(adj, clique, &next, k, cliques);
+                clique.pop();
+            }
+        }
+        let mut cliques: Vec<Vec<usize>> = Vec::new();
+        let candidates: Vec<usize> = (0..self.len()).collect();
+        extend(
+            &self.neighbour_sets(),
+            &mut Vec::with_capacity(k),
+            &candidates,
+            k,
+            &mut cliques,
+        );
+        cliques
+    }
+
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        self.cliques_of_size(3)
+            .into_iter()
+            .map(|c| [c[0], c[1], c[2]])
+            .collect()
+    }
+}
+
+//Disjoint sets with path compression and union by rank
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    nb_sets: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            nb_sets: len,
+        }
+    }
+
+    pub fn find(&mut self, a: usize) -> usize {
+        let mut root: usize = a;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current: usize = a;
+        while self.parents[current] != root {
+            let next: usize = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    //Returns false if both were already in the same set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return false;
+        }
+        let (big, small) = if self.ranks[ra] < self.ranks[rb] {
+            (rb, ra)
+        } else {
+            (ra, rb)
+        };
+        self.parents[small] = big;
+        self.sizes[big] += self.sizes[small];
+        if self.ranks[big] == self.ranks[small] {
+            self.ranks[big] += 1;
+        }
+        self.nb_sets -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn set_size(&mut self, a: usize) -> usize {
+        let root: usize = self.find(a);
+        self.sizes[root]
+    }
+
+    pub fn nb_sets(&self) -> usize {
+        self.nb_sets
+    }
+
+    //Size of each set, in no particular order
+    pub fn set_sizes(&self) -> Vec<usize> {
+        (0..self.parents.len())
+            .filter(|&a| self.parents[a] == a)
+            .map(|a| self.sizes[a])
+            .collect()
+    }
+
+    //The elements of each set, sets are ordered by their smallest element
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut group_ids: Vec<Option<usize>> = vec![None; self.parents.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.nb_sets);
+        for a in 0..self.parents.len() {
+            let root: usize = self.find(a);
+            let id: usize = *group_ids[root].get_or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[id].push(a);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::next_random;
+
+    #[test]
+    fn node_map() {
+        let mut nodes: NodeMap<&str> = NodeMap::new();
+        assert_eq!(nodes.id("COM"), 0);
+        assert_eq!(nodes.id("B"), 1);
+        assert_eq!(nodes.id("COM"), 0);
+        assert_eq!(nodes.get(&"C"), None);
+        assert_eq!(nodes.name(1), &"B");
+        assert_eq!(nodes.len(), 2);
+    }
+
+    #[test]
+    fn union_find() {
+        let mut set: UnionFind = UnionFind::new(6);
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+        assert!(set.same_set(0, 3) && !set.same_set(0, 4));
+        assert_eq!(set.set_size(2), 4);
+        assert_eq!(set.nb_sets(), 3);
+        let mut sizes: Vec<usize> = set.set_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 1, 4]);
+        assert_eq!(set.groups(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn traversals() {
+        //2017 day 12 example
+        let mut graph: Graph = Graph::new(7);
+        for (a, b) in [(0, 2), (2, 3), (2, 4), (3, 4), (4, 6), (5, 6)] {
+            graph.add_undirected_edge(a, b);
+        }
+        graph.add_undirected_edge(1, 1);
+        assert_eq!(graph.reachable(0), vec![0, 2, 3, 4, 5, 6]);
+        assert_eq!(graph.bfs_distances(0)[5], Some(4));
+        assert_eq!(graph.components().len(), 2);
+        assert_eq!(graph.adjacency_matrix()[4][3], 1);
+    }
+
+    #[test]
+    fn topological_order() {
+        //2018 day 7 example, C=2 A=0 B=1 D=3 E=4 F=5
+        let mut graph: Graph = Graph::new(6);
+        for (a, b) in [(2, 0), (2, 5), (0, 1), (0, 3), (1, 4), (3, 4), (5, 4)] {
+            graph.add_edge(a, b);
+        }
+        assert_eq!(graph.topological_sort(), Some(vec![2, 0, 1, 3, 5, 4]));
+        assert_eq!(graph.roots(), vec![2]);
+        assert_eq!(graph.count_paths(2, 4), Some(3));
+        //Reversed keys change the order between available nodes
+        assert_eq!(
+            graph.topological_sort_by_key(Reverse),
+            Some(vec![2, 5, 0, 3, 1, 4])
+        );
+        assert!(!graph.has_cycle());
+        graph.add_edge(4, 2);
+        assert!(graph.has_cycle());
+    }
+
+    #[test]
+    fn dag_values() {
+        //2020 day 7 part 2 example: every bag contains 2 bags of the next color
+        let mut graph: Graph = Graph::new(7);
+        (0..6).for_each(|n| graph.add_weighted_edge(n, n + 1, 2));
+        let inside = |graph: &Graph| -> Option<usize> {
+            graph.dag_evaluate(0, |_, children| {
+                children.iter().map(|(_, w, n)| w * (n + 1)).sum()
+            })
+        };
+        assert_eq!(inside(&graph), Some(126));
+        //A cycle gives nothing instead of looping forever
+        graph.add_edge(1, 0);
+        assert_eq!(inside(&graph), None);
+    }
+
+    #[test]
+    fn dot_view() {
+        let mut graph: Graph = Graph::new(3);
+        graph.add_edge(0, 1);
+        graph.add_weighted_edge(1, 2, 5);
+        let dot: String = graph.to_dot(|node| format!("n{node}"));
+        assert!(dot.contains("\"2\" [label=\"n2\", shape=ellipse];"));
+        assert!(dot.contains("\"0\" -> \"1\";"));
+        assert!(dot.contains("\"1\" -> \"2\" [label=\"5\"];"));
+    }
+
+    #[test]
+    fn flows() {
+        //Classic example with a maximum flow of 23
+        let mut graph: Graph = Graph::new(6);
+        for (a, b, c) in [
+            (0, 1, 16),
+            (0, 2, 13),
+            (1, 2, 10),
+            (2, 1, 4),
+            (1, 3, 12),
+            (3, 2, 9),
+            (2, 4, 14),
+            (4, 3, 7),
+            (3, 5, 20),
+            (4, 5, 4),
+        ] {
+            graph.add_weighted_edge(a, b, c);
+        }
+        assert_eq!(graph.max_flow(0, 5), 23);
+        let cut: Cut = graph.min_st_cut(0, 5);
+        assert_eq!(cut.weight, 23);
+        assert_eq!(cut.sides, (vec![0, 1, 2, 4], vec![3, 5]));
+        assert_eq!(cut.edges, vec![(1, 3), (4, 3), (4, 5)]);
+    }
+
+    #[test]
+    fn global_min_cut() {
+        //2023 day 25 example
+        let wiring: &str = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+        let mut names: NodeMap<&str> = NodeMap::new();
+        let mut graph: Graph = Graph::default();
+        for l in wiring.lines() {
+            let (from, tos) = l.split_once(": ").unwrap();
+            for to in tos.split(' ') {
+                graph.add_undirected_edge(names.id(from), names.id(to));
+            }
+        }
+        let cut: Cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(cut.sides.0.len() * cut.sides.1.len(), 54);
+        let mut wires: Vec<(&str, &str)> = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let (a, b) = (*names.name(a), *names.name(b));
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        wires.sort_unstable();
+        assert_eq!(wires, vec![("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+    }
+
+    #[test]
+    fn min_cut_against_brute_force() {
+        let mut seed: u32 = 7;
+        let mut next = |n: u32| next_random(&mut seed, n);
+        for _ in 0..30 {
+            let n: usize = 2 + next(6);
+            let mut graph: Graph = Graph::new(n);
+            for _ in 0..next(12) {
+                let (a, b) = (next(n as u32), next(n as u32));
+                if a != b {
+                    graph.add_undirected_weighted_edge(a, b, 1 + next(5));
+                }
+            }
+            let matrix: Vec<Vec<usize>> = graph.adjacency_matrix();
+            //Node 0 is always on the first side
+            let brute: usize = (1..(1usize << (n - 1)))
+                .map(|mask| {
+                    let side = |node: usize| node > 0 && mask & (1 << (node - 1)) != 0;
+                    (0..n)
+                        .flat_map(|a| (0..n).map(move |b| (a, b)))
+                        .filter(|&(a, b)| !side(a) && side(b))
+                        .map(|(a, b)| matrix[a][b])
+                        .sum::<usize>()
+                })
+                .min()
+                .unwrap();
+            let cut: Cut = graph.min_cut().unwrap();
+            assert_eq!(cut.weight, brute);
+            assert!(!cut.sides.0.is_empty() && !cut.sides.1.is_empty());
+            //A global cut is also the best cut between any node of each side
+            assert_eq!(graph.max_flow(cut.sides.0[0], cut.sides.1[0]), brute);
+        }
+    }
+
+    #[test]
+    fn lan_party_cliques() {
+        //2024 day 23 example
+        let links: &str =
+            "kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc yn-cg kh-ub \
+            ta-co de-co tc-td tb-wq wh-td ta-ka td-qp aq-cg wq-ub ub-vc de-ta wq-aq wq-vc wh-yn \
+            ka-de kh-ta co-tc wh-qp tb-vc td-yn";
+        let mut names: NodeMap<&str> = NodeMap::new();
+        let mut graph: Graph = Graph::default();
+        for link in links.split_whitespace() {
+            let (a, b) = link.split_once('-').unwrap();
+            graph.add_undirected_edge(names.id(a), names.id(b));
+        }
+        let triangles: Vec<[usize; 3]> = graph.triangles();
+        assert_eq!(triangles.len(), 12);
+        let with_t: usize = triangles
+            .iter()
+            .filter(|t| t.iter().any(|&n| names.name(n).starts_with('t')))
+            .count();
+        assert_eq!(with_t, 7);
+        let mut lan: Vec<&str> = graph
+            .max_clique()
+            .into_iter()
+            .map(|n| *names.name(n))
+            .collect();
+        lan.sort_unstable();
+        assert_eq!(lan, vec!["co", "de", "ka", "ta"]);
+    }
+
+    #[test]
+    fn small_cliques() {
+        //A square with one diagonal, a pendant node and an isolated one
+        let mut graph: Graph = Graph::new(6);
+        for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (3, 5)] {
+            graph.add_undirected_edge(a, b);
+        }
+        let mut maximal: Vec<Vec<usize>> = graph.maximal_cliques();
+        maximal.sort();
+        assert_eq!(
+            maximal,
+            vec![vec![0, 1, 2], vec![0, 2, 3], vec![3, 5], vec![4]]
+        );
+        assert_eq!(graph.max_clique().len(), 3);
+        assert_eq!(graph.cliques_of_size(3), vec![vec![0, 1, 2], vec![0, 2, 3]]);
+        assert_eq!(graph.cliques_of_size(2).len(), 6);
+        assert_eq!(graph.cliques_of_size(1).len(), 6);
+        assert!(graph.cliques_of_size(4).is_empty());
+        //A single directed edge is enough to link two nodes
+        let mut graph: Graph = Graph::new(3);
+        graph.add_edge(0, 1);
+        graph.add_edge(2, 1);
+        graph.add_edge(0, 2);
+        assert_eq!(graph.maximal_cliques(), vec![vec![0, 1, 2]]);
+    }
+}
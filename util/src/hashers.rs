@@ -6,20 +6,49 @@ use nom::Parser;
 use std::fmt::Write;
 use std::str::FromStr;
 
+const DEFAULT_ROUNDS: usize = 64;
+const DEFAULT_SUFFIX: [u8; 5] = [17, 31, 73, 47, 23];
+const DISK_SIZE: usize = 128;
+
 #[derive(Debug, Clone)]
 pub struct KnotHash {
     lengths: Vec<u8>,
+    rounds: usize,
+    suffix: Vec<u8>,
 }
 
 impl KnotHash {
     pub fn from_usize_list(l: &[usize]) -> KnotHash {
+        KnotHash::from_bytes(l.iter().map(|n| *n as u8))
+    }
+
+    pub fn from_bytes<I: IntoIterator<Item = u8>>(bytes: I) -> KnotHash {
         KnotHash {
-            lengths: l.iter().map(|n| *n as u8).collect(),
+            lengths: bytes.into_iter().collect(),
+            rounds: DEFAULT_ROUNDS,
+            suffix: DEFAULT_SUFFIX.to_vec(),
         }
     }
+
     pub fn new(s: &str) -> KnotHash {
-        let lengths: Vec<u8> = s.chars().map(|c| c as u8).collect();
-        KnotHash { lengths }
+        KnotHash::from_bytes(s.bytes())
+    }
+
+    pub fn with_rounds(mut self, rounds: usize) -> KnotHash {
+        self.rounds = rounds;
+        self
+    }
+
+    pub fn with_suffix(mut self, suffix: &[u8]) -> KnotHash {
+        self.suffix = suffix.to_vec();
+        self
+    }
+
+    //Build the 128x128 disk grid, one row per hash of "key-row"
+    pub fn disk_grid(key: &str) -> Vec<Vec<bool>> {
+        (0..DISK_SIZE)
+            .map(|i| KnotHash::new(&format!("{key}-{i}")).bits().collect())
+            .collect()
     }
 
     pub fn weak_hash(&self, len: usize) -> usize {
@@ -42,15 +71,19 @@ impl KnotHash {
     }
 
     pub fn hash_vec(&self) -> Vec<u8> {
-        let mut lengths: Vec<u8> = self.lengths.clone();
-        lengths.extend(vec![17, 31, 73, 47, 23]);
+        let lengths: Vec<u8> = self
+            .lengths
+            .iter()
+            .chain(self.suffix.iter())
+            .copied()
+            .collect();
 
         let mut v: Vec<u8> = (0..256).map(|n| n as u8).collect();
 
         let mut skip: usize = 0;
         let mut total_rot: usize = 0;
 
-        for _ in 0..64 {
+        for _ in 0..self.rounds {
             for &l in &lengths {
                 let slice: &mut [u8] = &mut v[0..l as usize];
                 slice.reverse();
@@ -70,6 +103,13 @@ impl KnotHash {
             .collect()
     }
 
+    //Bits of the dense hash, most significant bit of each byte first
+    pub fn bits(&self) -> impl Iterator<Item = bool> {
+        self.hash_vec()
+            .into_iter()
+            .flat_map(|b| (0..8).rev().map(move |i| (b >> i) & 1 == 1))
+    }
+
     pub fn hash(&self) -> String {
         self.hash_vec()
             .into_iter()
@@ -96,3 +136,38 @@ impl FromStr for KnotHash {
         Ok(KnotHash::from_usize_list(&lengths))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_bits() {
+        let khash: KnotHash = KnotHash::new("AoC 2017");
+        let bits: Vec<bool> = khash.bits().take(8).collect();
+        //First byte of the hash is 0x33
+        assert_eq!(
+            bits,
+            vec![false, false, true, true, false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn explicit_defaults() {
+        let khash: KnotHash = KnotHash::from_bytes("1,2,3".bytes())
+            .with_rounds(64)
+            .with_suffix(&[17, 31, 73, 47, 23]);
+        assert_eq!("3efbe78a8d82f29979031a4aa0b16a9d", khash.hash());
+    }
+
+    #[test]
+    fn disk_grid() {
+        let grid: Vec<Vec<bool>> = KnotHash::disk_grid("flqrgnkx");
+        assert_eq!(grid.len(), 128);
+        let start: String = grid[0][..8]
+            .iter()
+            .map(|&b| if b { '#' } else { '.' })
+            .collect();
+        assert_eq!(start, "##.#.#..");
+    }
+}
@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+//Hexagonal grids in axial coordinates, the third cube coordinate is s = -q - r
+//q grows to the east and r to the south-east (pointy) or to the south (flat)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+//The 6 axial unit vectors, turning clockwise
+const AXIAL_DIRS: [Hex; 6] = [
+    Hex { q: 1, r: -1 },
+    Hex { q: 1, r: 0 },
+    Hex { q: 0, r: 1 },
+    Hex { q: -1, r: 1 },
+    Hex { q: -1, r: 0 },
+    Hex { q: 0, r: -1 },
+];
+
+impl Hex {
+    pub fn new(q: isize, r: isize) -> Self {
+        Hex { q, r }
+    }
+
+    pub fn origin() -> Self {
+        Hex::default()
+    }
+
+    pub fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (isize, isize, isize) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn add(&self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+
+    pub fn sub(&self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+
+    pub fn scale(&self, k: isize) -> Hex {
+        Hex::new(self.q * k, self.r * k)
+    }
+
+    pub fn step(&self, orientation: Orientation, dir: HexDir) -> Hex {
+        self.add(orientation.delta(dir))
+    }
+
+    pub fn walk(&self, orientation: Orientation, path: &[HexDir]) -> Hex {
+        path.iter()
+            .fold(*self, |pos, &dir| pos.step(orientation, dir))
+    }
+
+    pub fn distance(&self, other: Hex) -> usize {
+        let (q, r, s) = self.sub(other).cube();
+        (q.unsigned_abs() + r.unsigned_abs() + s.unsigned_abs()) / 2
+    }
+
+    //The neighbours do not depend on the orientation
+    pub fn neighbours(&self) -> [Hex; 6] {
+        AXIAL_DIRS.map(|d| self.add(d))
+    }
+
+    //All the hexes at exactly "radius" steps, going around clockwise
+    pub fn ring(&self, radius: usize) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut pos: Hex = self.add(AXIAL_DIRS[4].scale(radius as isize));
+        let mut ring: Vec<Hex> = Vec::with_capacity(6 * radius);
+        for d in AXIAL_DIRS {
+            for _ in 0..radius {
+                ring.push(pos);
+                pos = pos.add(d);
+            }
+        }
+        ring
+    }
+
+    //All the hexes at most "radius" steps away, ring by ring from the center
+    pub fn spiral(&self, radius: usize) -> Vec<Hex> {
+        (0..=radius).flat_map(|n| self.ring(n)).collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum HexDir {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl FromStr for HexDir {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "n" => Ok(HexDir::North),
+            "ne" => Ok(HexDir::NorthEast),
+            "e" => Ok(HexDir::East),
+            "se" => Ok(HexDir::SouthEast),
+            "s" => Ok(HexDir::South),
+            "sw" => Ok(HexDir::SouthWest),
+            "w" => Ok(HexDir::West),
+            "nw" => Ok(HexDir::NorthWest),
+            _ => Err(format!("Invalid hex direction [{s}]")),
+        }
+    }
+}
+
+//Pointy hexes are laid out in rows (no North/South), flat hexes in columns (no East/West)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Orientation {
+    Pointy,
+    Flat,
+}
+
+impl Orientation {
+    pub fn dirs(&self) -> [HexDir; 6] {
+        match self {
+            Orientation::Pointy => [
+                HexDir::NorthEast,
+                HexDir::East,
+                HexDir::SouthEast,
+                HexDir::SouthWest,
+                HexDir::West,
+                HexDir::NorthWest,
+            ],
+            Orientation::Flat => [
+                HexDir::North,
+                HexDir::NorthEast,
+                HexDir::SouthEast,
+                HexDir::South,
+                HexDir::SouthWest,
+                HexDir::NorthWest,
+            ],
+        }
+    }
+
+    pub fn is_valid(&self, dir: HexDir) -> bool {
+        self.dirs().contains(&dir)
+    }
+
+    //Panics if the direction does not exist in this orientation
+    pub fn delta(&self, dir: HexDir) -> Hex {
+        let i: usize = self
+            .dirs()
+            .iter()
+            .position(|&d| d == dir)
+            .unwrap_or_else(|| panic!("No {dir:?} direction on {self:?} hexes"));
+        match self {
+            Orientation::Pointy => AXIAL_DIRS[i],
+            Orientation::Flat => AXIAL_DIRS[(i + 5) % 6],
+        }
+    }
+
+    //Parse a path of directions, either separated by commas or stuck together like "nwwswee"
+    pub fn parse_path(&self, s: &str) -> Result<Vec<HexDir>, String> {
+        let chars: Vec<char> = s.trim().chars().filter(|&c| c != ',').collect();
+        let valid = |s: String| s.parse().ok().filter(|&d| self.is_valid(d));
+        let mut path: Vec<HexDir> = Vec::new();
+        let mut i: usize = 0;
+        while i < chars.len() {
+            //Two letters directions come first, "ne" is never "n" then "e"
+            let pair: Option<HexDir> = chars.get(i..i + 2).and_then(|p| valid(p.iter().collect()));
+            match (pair, valid(chars[i].to_string())) {
+                (Some(d), _) => {
+                    path.push(d);
+                    i += 2;
+                }
+                (None, Some(d)) => {
+                    path.push(d);
+                    i += 1;
+                }
+                (None, None) => {
+                    return Err(format!(
+                        "Invalid direction [{}] on {self:?} hexes",
+                        chars[i]
+                    ))
+                }
+            }
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions_and_distances() {
+        let flat: Vec<HexDir> = Orientation::Flat.parse_path("ne,ne,s,s").unwrap();
+        assert_eq!(
+            Hex::origin()
+                .walk(Orientation::Flat, &flat)
+                .distance(Hex::origin()),
+            2
+        );
+        let pointy: Vec<HexDir> = Orientation::Pointy.parse_path("nwwswee").unwrap();
+        assert_eq!(pointy.len(), 5);
+        assert_eq!(
+            Hex::origin().walk(Orientation::Pointy, &pointy),
+            Hex::origin()
+        );
+        assert!(Orientation::Pointy.parse_path("nen").is_err());
+        //Opposite directions cancel each other in both orientations
+        for o in [Orientation::Pointy, Orientation::Flat] {
+            let dirs: [HexDir; 6] = o.dirs();
+            (0..3).for_each(|i| {
+                assert_eq!(o.delta(dirs[i]).add(o.delta(dirs[i + 3])), Hex::origin())
+            });
+        }
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        let center: Hex = Hex::new(2, -5);
+        let ring: Vec<Hex> = center.ring(3);
+        assert_eq!(ring.len(), 18);
+        assert!(ring.iter().all(|h| h.distance(center) == 3));
+        assert_eq!(center.spiral(2).len(), 19);
+        assert!(center.neighbours().iter().all(|h| h.distance(center) == 1));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::num::Integer;
+use std::cmp::{max, min};
+
+//Half-open interval [start, end[, empty when start >= end
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    //[start, end], the upper bound is included
+    //Panics when "end" is T::MAX, as the exclusive end cannot be represented
+    pub fn closed(start: T, end: T) -> Self {
+        Interval::checked_closed(start, end).expect("Closed interval ending at the maximum value")
+    }
+
+    //[start, end], or None when "end" is T::MAX
+    pub fn checked_closed(start: T, end: T) -> Option<Self> {
+        end.checked_sum(T::ONE).map(|end| Interval::new(start, end))
+    }
+
+    pub fn with_len(start: T, len: T) -> Self {
+        Interval::new(start, start + len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::ZERO
+        } else {
+            self.end - self.start
+        }
+    }
+
+    //Last value of the interval, only meaningful when not empty
+    pub fn last(&self) -> T {
+        self.end - T::ONE
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let inter = Interval::new(max(self.start, other.start), min(self.end, other.end));
+        (!inter.is_empty()).then_some(inter)
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    //The values strictly below "at", and the ones from "at" onwards
+    pub fn split_at(&self, at: T) -> (Option<Interval<T>>, Option<Interval<T>>) {
+        let below = Interval::new(self.start, min(self.end, at));
+        let above = Interval::new(max(self.start, at), self.end);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    //Moves the interval so that it starts at "start"
+    pub fn move_to(&self, start: T) -> Interval<T> {
+        Interval::with_len(start, self.len())
+    }
+}
+
+//Disjoint intervals sorted by start, with no two of them touching
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    //Number of values in the set
+    pub fn len(&self) -> T {
+        self.intervals.iter().fold(T::ZERO, |acc, i| acc + i.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|i| i.last())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx: usize = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(idx).is_some_and(|i| i.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        //Every interval from "first" to "last" overlaps or touches the new one
+        let first: usize = self.intervals.partition_point(|i| i.end < interval.start);
+        let last: usize = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = if first < last {
+            Interval::new(
+                min(interval.start, self.intervals[first].start),
+                max(interval.end, self.intervals[last - 1].end),
+            )
+        } else {
+            interval
+        };
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut set: IntervalSet<T> = self.clone();
+        other.iter().for_each(|&i| set.insert(i));
+        set
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals: Vec<Interval<T>> = Vec::new();
+        let (mut a, mut b): (usize, usize) = (0, 0);
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (ia, ib) = (self.intervals[a], other.intervals[b]);
+            if let Some(inter) = ia.intersection(&ib) {
+                intervals.push(inter);
+            }
+            if ia.end < ib.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    //The values of "bounds" that are not in the set
+    pub fn complement(&self, bounds: Interval<T>) -> IntervalSet<T> {
+        let mut intervals: Vec<Interval<T>> = Vec::new();
+        let mut start: T = bounds.start;
+        for i in self.intervals.iter() {
+            if i.start > start {
+                intervals.push(Interval::new(start, min(i.start, bounds.end)));
+            }
+            start = max(start, i.end);
+            if start >= bounds.end {
+                break;
+            }
+        }
+        if start < bounds.end {
+            intervals.push(Interval::new(start, bounds.end));
+        }
+        IntervalSet {
+            intervals: intervals.into_iter().filter(|i| !i.is_empty()).collect(),
+        }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        match (self.min(), self.intervals.last()) {
+            (Some(start), Some(last)) => {
+                self.intersection(&other.complement(Interval::new(start, last.end)))
+            }
+            _ => IntervalSet::new(),
+        }
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set: IntervalSet<T> = IntervalSet::new();
+        iter.into_iter().for_each(|i| set.insert(i));
+        set
+    }
+}
+
+//Piecewise translation: each source interval is moved to its own destination start
+//Values outside of every source interval are left untouched
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OffsetMap<T> {
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Integer> OffsetMap<T> {
+    pub fn new() -> Self {
+        OffsetMap { pieces: Vec::new() }
+    }
+
+    //The source intervals should not overlap
+    pub fn add(&mut self, source: Interval<T>, dest_start: T) {
+        self.pieces.push((source, dest_start));
+    }
+
+    pub fn map_value(&self, value: T) -> T {
+        self.pieces
+            .iter()
+            .find(|(source, _)| source.contains(value))
+            .map(|&(source, dest_start)| value - source.start + dest_start)
+            .unwrap_or(value)
+    }
+
+    pub fn map_interval(&self, interval: Interval<T>) -> IntervalSet<T> {
+        self.map_set(&IntervalSet::from_iter([interval]))
+    }
+
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut mapped: IntervalSet<T> = IntervalSet::new();
+        let mut untouched: IntervalSet<T> = set.clone();
+        for &(source, dest_start) in self.pieces.iter() {
+            let moved: IntervalSet<T> = set.intersection(&IntervalSet::from_iter([source]));
+            moved
+                .iter()
+                .map(|i| i.move_to(i.start - source.start + dest_start))
+                .for_each(|i| mapped.insert(i));
+            untouched = untouched.difference(&moved);
+        }
+        mapped.union(&untouched)
+    }
+}
+
+impl<T: Integer> FromIterator<(Interval<T>, T)> for OffsetMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Interval<T>, T)>>(iter: I) -> Self {
+        OffsetMap {
+            pieces: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals() {
+        let a: Interval<i32> = Interval::closed(3, 7);
+        assert_eq!(a.len(), 5);
+        assert!(a.contains(7) && !a.contains(8));
+        assert_eq!(
+            a.intersection(&Interval::new(7, 10)),
+            Some(Interval::new(7, 8))
+        );
+        assert_eq!(a.intersection(&Interval::new(8, 10)), None);
+        assert_eq!(
+            a.split_at(5),
+            (Some(Interval::new(3, 5)), Some(Interval::new(5, 8)))
+        );
+        assert_eq!(a.split_at(2), (None, Some(a)));
+        assert_eq!(
+            Interval::checked_closed(0, u8::MAX - 1),
+            Some(Interval::new(0, u8::MAX))
+        );
+        assert_eq!(Interval::checked_closed(0, u8::MAX), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn closed_at_max() {
+        Interval::closed(i64::MAX - 1, i64::MAX);
+    }
+
+    #[test]
+    fn sets() {
+        let set: IntervalSet<u32> = [
+            Interval::closed(5, 8),
+            Interval::closed(0, 2),
+            Interval::closed(4, 7),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.intervals(), &[Interval::new(0, 3), Interval::new(4, 9)]);
+        assert_eq!(set.len(), 8);
+        assert!(set.contains(8) && !set.contains(3));
+        //Touching intervals are merged
+        let mut touching: IntervalSet<u32> = set.clone();
+        touching.insert(Interval::new(3, 4));
+        assert_eq!(touching.intervals(), &[Interval::new(0, 9)]);
+
+        let bounds: Interval<u32> = Interval::new(0, 10);
+        assert_eq!(
+            set.complement(bounds).intervals(),
+            &[Interval::new(3, 4), Interval::new(9, 10)]
+        );
+        let other: IntervalSet<u32> = IntervalSet::from_iter([Interval::new(2, 6)]);
+        assert_eq!(
+            set.intersection(&other).intervals(),
+            &[Interval::new(2, 3), Interval::new(4, 6)]
+        );
+        assert_eq!(
+            set.difference(&other).intervals(),
+            &[Interval::new(0, 2), Interval::new(6, 9)]
+        );
+        assert_eq!(set.union(&other).intervals(), &[Interval::new(0, 9)]);
+    }
+
+    #[test]
+    fn offset_map() {
+        //2023 day 5 seed-to-soil example
+        let map: OffsetMap<u64> = [
+            (Interval::with_len(98, 2), 50),
+            (Interval::with_len(50, 48), 52),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(map.map_value(79), 81);
+        assert_eq!(map.map_value(99), 51);
+        assert_eq!(map.map_value(10), 10);
+        assert_eq!(
+            map.map_interval(Interval::new(45, 100)).intervals(),
+            &[Interval::new(45, 100)]
+        );
+        assert_eq!(
+            map.map_interval(Interval::new(96, 102)).intervals(),
+            &[Interval::new(50, 52), Interval::new(98, 102)]
+        );
+    }
+}
@@ -1,13 +1,33 @@
+pub mod affine_map;
+pub mod assembunny;
+pub mod automaton;
 pub mod basic_parser;
+pub mod bitgrid;
 pub mod chinese_remainders;
+pub mod circuit;
+pub mod contraction;
 pub mod coord;
+pub mod cube_net;
+pub mod cuboid;
+pub mod dot;
 pub mod duet_tablet;
+pub mod expr;
+pub mod graph;
 pub mod hashers;
+pub mod hex;
 pub mod intcode;
+pub mod interval;
+pub mod num;
 pub mod orientation;
-pub mod registry;
+pub mod paths;
+pub mod polygon;
+pub mod rotation;
+pub mod symmetry;
+pub mod vector;
 pub mod wrist_device;
 
+pub use num::{gcd, lcm};
+
 use std::fs::File;
 use std::io::{Error, Read};
 
@@ -28,19 +48,12 @@ pub fn split_blocks(data: &str) -> Vec<&str> {
         .collect()
 }
 
-pub fn gcd(a: isize, b: isize) -> isize {
-    let mut dd: isize = a.abs();
-    let mut dv: isize = b.abs();
-
-    while dv != 0 {
-        let r: isize = dd % dv;
-        dd = dv;
-        dv = r;
+//Small deterministic generator, for the tests that need a few arbitrary inputs
+#[cfg(test)]
+pub(crate) mod test_rng {
+    //A random number in 0..n, from a linear congruential generator
+    pub(crate) fn next_random(seed: &mut u32, n: u32) -> usize {
+        *seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        ((*seed >> 16) % n) as usize
     }
-    dd
-}
-
-pub fn lcm(a: isize, b: isize) -> isize {
-    let g: isize = gcd(a, b);
-    a * b / g
 }
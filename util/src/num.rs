@@ -0,0 +1,454 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+pub trait Integer:
+    Copy
+    + Debug
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    //None for the MIN of signed types, its opposite does not fit
+    fn checked_abs_value(self) -> Option<Self>;
+    fn checked_sum(self, other: Self) -> Option<Self>;
+    fn checked_difference(self, other: Self) -> Option<Self>;
+    fn checked_product(self, other: Self) -> Option<Self>;
+    fn checked_remainder(self, other: Self) -> Option<Self>;
+    fn to_u128(self) -> Option<u128>;
+    fn from_u128(n: u128) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($abs:ident: $($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_abs_value(self) -> Option<Self> {
+                impl_integer!(@$abs self)
+            }
+            fn checked_sum(self, other: Self) -> Option<Self> {
+                self.checked_add(other)
+            }
+            fn checked_difference(self, other: Self) -> Option<Self> {
+                self.checked_sub(other)
+            }
+            fn checked_product(self, other: Self) -> Option<Self> {
+                self.checked_mul(other)
+            }
+            fn checked_remainder(self, other: Self) -> Option<Self> {
+                self.checked_rem(other)
+            }
+            fn to_u128(self) -> Option<u128> {
+                u128::try_from(self).ok()
+            }
+            fn from_u128(n: u128) -> Self {
+                n as $t
+            }
+        }
+    )*};
+    (@signed $n:ident) => { $n.checked_abs() };
+    (@unsigned $n:ident) => { Some($n) };
+}
+
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize);
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd overflows")
+}
+
+//Euclid on the signed values, so only a gcd equal to -T::MIN does not fit and returns None
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let mut dd: T = a;
+    let mut dv: T = b;
+
+    while dv != T::ZERO {
+        //Only MIN % -1 fails, and it is 0
+        let r: T = dd.checked_remainder(dv).unwrap_or(T::ZERO);
+        dd = dv;
+        dv = r;
+    }
+    dd.checked_abs_value()
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflows")
+}
+
+//Divide before multiplying, so only a result that does not fit returns None
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    let g: T = checked_gcd(a, b)?;
+    (a / g)
+        .checked_abs_value()?
+        .checked_product(b.checked_abs_value()?)
+}
+
+pub fn gcd_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+pub fn lcm_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> T {
+    checked_lcm_all(values).expect("lcm overflows")
+}
+
+pub fn checked_lcm_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::ONE, |acc, n| checked_lcm(acc, n))
+}
+
+//Sieve of Eratosthenes, sieve[n] is true when n is prime
+pub fn prime_sieve(limit: usize) -> Vec<bool> {
+    let mut sieve: Vec<bool> = vec![true; limit + 1];
+    sieve[0] = false;
+    if limit >= 1 {
+        sieve[1] = false;
+    }
+    let mut i: usize = 2;
+    while i * i <= limit {
+        if sieve[i] {
+            (i * i..=limit).step_by(i).for_each(|m| sieve[m] = false);
+        }
+        i += 1;
+    }
+    sieve
+}
+
+pub fn primes_up_to(limit: usize) -> Vec<usize> {
+    prime_sieve(limit)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(n, is_prime)| is_prime.then_some(n))
+        .collect()
+}
+
+//a + b mod m, for a and b below m
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+//a * b mod m, for a and b below m
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u64::MAX as u128 {
+        return a * b % m;
+    }
+    //Above 64 bits the product can overflow, so we double and add
+    let (mut a, mut b, mut acc): (u128, u128, u128) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            acc = add_mod(acc, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    acc
+}
+
+fn pow_mod(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut acc: u128 = 1;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+//Miller-Rabin with the first prime bases, it is exact below 3.3e24 (so for every 64 bits
+//number), above no composite is known to pass them all
+pub fn is_prime<T: Integer>(n: T) -> bool {
+    const BASES: [u128; 20] = [
+        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+    ];
+
+    let Some(n) = n.to_u128() else {
+        return false;
+    };
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n % p == 0) {
+        return n == p;
+    }
+
+    let s: u32 = (n - 1).trailing_zeros();
+    let d: u128 = (n - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut x: u128 = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..s).any(|_| {
+            x = mul_mod(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+//Prime factors with their multiplicity, in increasing order
+//Trial division, stopping as soon as what is left is prime, so only numbers with two large
+//prime factors are slow. Panics on 0 and negative numbers, which have no factorization
+pub fn factorize<T: Integer>(n: T) -> Vec<(T, u32)> {
+    let mut n: u128 = n
+        .to_u128()
+        .filter(|&n| n > 0)
+        .expect("Only positive numbers can be factorized");
+    let mut factors: Vec<(T, u32)> = Vec::new();
+    let mut add_factor = |n: &mut u128, p: u128| {
+        let mut exp: u32 = 0;
+        while n.is_multiple_of(p) {
+            *n /= p;
+            exp += 1;
+        }
+        if exp > 0 {
+            factors.push((T::from_u128(p), exp));
+        }
+        exp > 0
+    };
+
+    add_factor(&mut n, 2);
+    add_factor(&mut n, 3);
+    //Every other prime is of the form 6k +/- 1
+    let mut p: u128 = 5;
+    let mut prime_left: bool = is_prime(n);
+    while !prime_left && p <= n / p {
+        let found: bool = add_factor(&mut n, p);
+        if add_factor(&mut n, p + 2) || found {
+            prime_left = is_prime(n);
+        }
+        p += 6;
+    }
+    if n > 1 {
+        factors.push((T::from_u128(n), 1));
+    }
+    factors
+}
+
+//All divisors of n, in increasing order
+pub fn divisors<T: Integer>(n: T) -> Vec<T> {
+    let mut divs: Vec<T> = vec![T::ONE];
+    for (p, exp) in factorize(n) {
+        let previous: Vec<T> = divs.clone();
+        let mut power: T = T::ONE;
+        for _ in 0..exp {
+            power = power * p;
+            divs.extend(previous.iter().map(|&d| d * power));
+        }
+    }
+    divs.sort();
+    divs
+}
+
+//sums[n] is the sum of all divisors of n
+pub fn divisor_sum_sieve(limit: usize) -> Vec<usize> {
+    let mut sums: Vec<usize> = vec![0; limit + 1];
+    for d in 1..=limit {
+        (d..=limit).step_by(d).for_each(|m| sums[m] += d);
+    }
+    sums
+}
+
+//Exact fraction, kept in lowest terms with a positive denominator
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Zero denominator");
+        let g: i128 = gcd(num, den).max(1) * den.signum();
+        Rational {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    pub fn from_integer(n: i128) -> Self {
+        Rational { num: n, den: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    //None when dividing by zero
+    pub fn checked_div(self, other: Rational) -> Option<Rational> {
+        (other.num != 0).then(|| Rational::new(self.num * other.den, self.den * other.num))
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        Rational::new(
+            self.num * other.den + other.num * self.den,
+            self.den * other.den,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        Rational::new(self.num * other.num, self.den * other.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, other: Rational) -> Rational {
+        self.checked_div(other).expect("Division by zero")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::next_random;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(-12isize, 18), 6);
+        assert_eq!(gcd(i64::MIN, 6), 2);
+        assert_eq!(gcd(i8::MIN, -1), 1);
+        assert_eq!(checked_gcd(i8::MIN, 0), None);
+        assert_eq!(checked_lcm(i8::MIN, 3), None);
+        assert_eq!(checked_lcm(i8::MIN, 2), None);
+        assert_eq!(checked_lcm(-64i8, 2), Some(64));
+        assert_eq!(lcm(4u8, 6), 12);
+        assert_eq!(lcm_all([2usize, 3, 4, 5]), 60);
+        assert_eq!(gcd_all([12u32, 18, 30]), 6);
+        //The product overflows, but not the lcm
+        assert_eq!(checked_lcm(200u8, 100), Some(200));
+        assert_eq!(checked_lcm(200u8, 3), None);
+    }
+
+    #[test]
+    fn primes() {
+        assert_eq!(primes_up_to(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+        assert!(is_prime(1_000_000_007u64));
+        assert!(!is_prime(3_215_031_751u64));
+        assert!(!is_prime(-7i32));
+        assert!(is_prime(u64::MAX - 58));
+        assert!(is_prime((1u128 << 89) - 1));
+        assert!(!is_prime((1u128 << 89) + 1));
+        assert!(!is_prime(((1u128 << 61) - 1) * ((1 << 31) - 1)));
+        let sieve: Vec<bool> = prime_sieve(12);
+        assert_eq!(sieve.iter().filter(|&&p| p).count(), 5);
+        assert!(sieve[11] && !sieve[12] && !sieve[1]);
+    }
+
+    #[test]
+    fn factors_and_divisors() {
+        assert_eq!(factorize(360u32), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(1u8), vec![]);
+        //A prime near the top of u64, and a large prime factor of a u128
+        assert_eq!(factorize(u64::MAX - 58), vec![(u64::MAX - 58, 1)]);
+        let mersenne: u128 = (1 << 89) - 1;
+        assert_eq!(factorize(6 * mersenne), vec![(2, 1), (3, 1), (mersenne, 1)]);
+        let mut seed: u32 = 7;
+        for _ in 0..200 {
+            let n: u64 =
+                (next_random(&mut seed, 1000) * 1000 + next_random(&mut seed, 1000)) as u64 + 1;
+            let product: u64 = factorize(n).iter().map(|&(p, e)| p.pow(e)).product();
+            assert_eq!(product, n);
+            assert!(factorize(n).iter().all(|&(p, _)| is_prime(p)));
+        }
+        assert_eq!(divisors(28i64), vec![1, 2, 4, 7, 14, 28]);
+        assert_eq!(
+            divisor_sum_sieve(12),
+            vec![0, 1, 3, 4, 7, 6, 12, 8, 15, 13, 18, 12, 28]
+        );
+    }
+
+    #[test]
+    fn rationals() {
+        let half: Rational = Rational::new(2, -4);
+        assert_eq!((half.numer(), half.denom()), (-1, 2));
+        let third: Rational = Rational::new(1, 3);
+        assert_eq!(half + third, Rational::new(-1, 6));
+        assert_eq!(half * third, Rational::new(-1, 6));
+        assert_eq!(half / third, Rational::new(-3, 2));
+        assert_eq!(third.checked_div(Rational::from_integer(0)), None);
+        assert_eq!((third * Rational::from_integer(6)).to_integer(), Some(2));
+        assert!(half < third);
+        assert_eq!(format!("{half} {}", Rational::from_integer(7)), "-1/2 7");
+    }
+}
@@ -1,3 +1,7 @@
+use crate::coord::{Pos, PosI};
+use std::str::FromStr;
+
+//Deltas use screen coordinates, y grows to the south
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
 pub enum Dir {
     North,
@@ -7,6 +11,9 @@ pub enum Dir {
 }
 
 impl Dir {
+    //Clockwise, starting from North
+    pub const ALL: [Dir; 4] = [Dir::North, Dir::East, Dir::South, Dir::West];
+
     pub fn from_char(c: char) -> Result<Self, String> {
         match c {
             'U' | 'N' | '^' => Ok(Dir::North),
@@ -36,4 +43,198 @@ impl Dir {
     pub fn half_turn(&self) -> Self {
         self.turn_left().turn_left()
     }
+
+    fn index(&self) -> usize {
+        Dir::ALL.iter().position(|d| d == self).unwrap()
+    }
+
+    //Clockwise for a positive number of quarter turns
+    pub fn rotate(&self, quarter_turns: isize) -> Self {
+        Dir::ALL[(self.index() as isize + quarter_turns).rem_euclid(4) as usize]
+    }
+
+    pub fn turn(&self, turn: Turn) -> Result<Self, String> {
+        Ok(self.rotate(turn.quarter_turns()?))
+    }
+
+    pub fn delta(&self) -> PosI {
+        match self {
+            Dir::North => PosI(0, -1),
+            Dir::East => PosI(1, 0),
+            Dir::South => PosI(0, 1),
+            Dir::West => PosI(-1, 0),
+        }
+    }
+
+    //None when going below 0
+    pub fn step(&self, pos: Pos) -> Option<Pos> {
+        pos.checked_add(self.delta())
+    }
+
+    //None when leaving the max_x * max_y grid
+    pub fn step_bounded(&self, pos: Pos, max_x: usize, max_y: usize) -> Option<Pos> {
+        pos.add_bounded(self.delta(), max_x, max_y)
+    }
+
+    pub fn step_n(&self, PosI(x, y): PosI, n: isize) -> PosI {
+        let PosI(dx, dy) = self.delta();
+        PosI(x + n * dx, y + n * dy)
+    }
+}
+
+//Same as Dir, with the diagonals
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    //Clockwise, starting from North
+    pub const ALL: [Dir8; 8] = [
+        Dir8::North,
+        Dir8::NorthEast,
+        Dir8::East,
+        Dir8::SouthEast,
+        Dir8::South,
+        Dir8::SouthWest,
+        Dir8::West,
+        Dir8::NorthWest,
+    ];
+
+    fn index(&self) -> usize {
+        Dir8::ALL.iter().position(|d| d == self).unwrap()
+    }
+
+    //Clockwise for a positive number of eighth turns
+    pub fn rotate(&self, eighth_turns: isize) -> Self {
+        Dir8::ALL[(self.index() as isize + eighth_turns).rem_euclid(8) as usize]
+    }
+
+    pub fn turn(&self, turn: Turn) -> Result<Self, String> {
+        Ok(self.rotate(turn.eighth_turns()?))
+    }
+
+    pub fn half_turn(&self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn delta(&self) -> PosI {
+        match self {
+            Dir8::North => PosI(0, -1),
+            Dir8::NorthEast => PosI(1, -1),
+            Dir8::East => PosI(1, 0),
+            Dir8::SouthEast => PosI(1, 1),
+            Dir8::South => PosI(0, 1),
+            Dir8::SouthWest => PosI(-1, 1),
+            Dir8::West => PosI(-1, 0),
+            Dir8::NorthWest => PosI(-1, -1),
+        }
+    }
+
+    pub fn step(&self, pos: Pos) -> Option<Pos> {
+        pos.checked_add(self.delta())
+    }
+
+    pub fn step_bounded(&self, pos: Pos, max_x: usize, max_y: usize) -> Option<Pos> {
+        pos.add_bounded(self.delta(), max_x, max_y)
+    }
+
+    pub fn step_n(&self, PosI(x, y): PosI, n: isize) -> PosI {
+        let PosI(dx, dy) = self.delta();
+        PosI(x + n * dx, y + n * dy)
+    }
+}
+
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Self {
+        Dir8::ALL[2 * dir.index()]
+    }
+}
+
+//A rotation in degrees, clockwise when positive
+//Parsed from instructions like "R90" or "L270"
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub struct Turn(pub isize);
+
+impl Turn {
+    pub fn quarter_turns(&self) -> Result<isize, String> {
+        if self.0 % 90 == 0 {
+            Ok(self.0 / 90)
+        } else {
+            Err(format!("{} degrees is not a multiple of 90", self.0))
+        }
+    }
+
+    pub fn eighth_turns(&self) -> Result<isize, String> {
+        if self.0 % 45 == 0 {
+            Ok(self.0 / 45)
+        } else {
+            Err(format!("{} degrees is not a multiple of 45", self.0))
+        }
+    }
+
+    //Rotate a vector around the origin, by a multiple of 90 degrees
+    pub fn rotate(&self, PosI(x, y): PosI) -> Result<PosI, String> {
+        Ok(match self.quarter_turns()?.rem_euclid(4) {
+            0 => PosI(x, y),
+            1 => PosI(-y, x),
+            2 => PosI(-x, -y),
+            _ => PosI(y, -x),
+        })
+    }
+}
+
+impl FromStr for Turn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sign: isize = match s.chars().next() {
+            Some('R') => 1,
+            Some('L') => -1,
+            _ => return Err(format!("Invalid turn [{s}]")),
+        };
+        let degrees: isize = s[1..].parse().map_err(|_| format!("Invalid turn [{s}]"))?;
+        Ok(Turn(sign * degrees))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns() {
+        let left: Turn = "L270".parse().unwrap();
+        assert_eq!(left, Turn(-270));
+        assert_eq!(Dir::West.turn(left), Ok(Dir::North));
+        assert_eq!(Dir::East.rotate(-1), Dir::East.turn_left());
+        assert!(Dir::East.turn(Turn(45)).is_err());
+        assert_eq!(Dir8::South.turn(Turn(135)), Ok(Dir8::NorthWest));
+        assert_eq!(Turn(90).rotate(PosI(10, -4)), Ok(PosI(4, 10)));
+        //Rotating a direction rotates its delta
+        for d in Dir::ALL {
+            assert_eq!(Turn(90).rotate(d.delta()), Ok(d.turn_right().delta()));
+            assert_eq!(Dir8::from(d).delta(), d.delta());
+        }
+    }
+
+    #[test]
+    fn steps() {
+        assert_eq!(Dir::North.step(Pos(3, 0)), None);
+        assert_eq!(Dir::East.step_bounded(Pos(3, 0), 4, 4), None);
+        assert_eq!(Dir::South.step(Pos(3, 0)), Some(Pos(3, 1)));
+        assert_eq!(Dir8::NorthWest.step_n(PosI(0, 0), 3), PosI(-3, -3));
+        assert_eq!(Dir8::ALL.iter().filter(|d| d.is_diagonal()).count(), 4);
+    }
 }
@@ -0,0 +1,250 @@
+use crate::graph::Graph;
+use crate::num::Integer;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+//Solvers for small graphs, the visited nodes are kept in a bitmask
+
+//A partial path, "bound" is the most the unvisited nodes can still add to it
+#[derive(Copy, Clone, Debug)]
+struct Walk {
+    node: usize,
+    visited: u64,
+    len: usize,
+    bound: usize,
+}
+
+struct LongestPath<'a> {
+    graph: &'a Graph,
+    to: usize,
+    //Heaviest edge entering each node, entering a node can never add more
+    max_in: Vec<usize>,
+    //The nodes with an edge to "to"
+    to_in: u64,
+    //Best length found so far plus one, 0 when none was found yet
+    best: AtomicUsize,
+}
+
+impl LongestPath<'_> {
+    fn next_walks(&self, walk: Walk) -> impl Iterator<Item = Walk> + '_ {
+        //When every other way into "to" is used, going anywhere else is a dead end
+        let to_last_chance: bool = self.to_in & (1 << walk.node) != 0
+            && self.to_in & !walk.visited & !(1 << walk.node) == 0;
+        self.graph
+            .edges(walk.node)
+            .iter()
+            .filter(move |&&(next, _)| {
+                walk.visited & (1 << next) == 0 && (!to_last_chance || next == self.to)
+            })
+            .map(move |&(next, w)| Walk {
+                node: next,
+                visited: walk.visited | (1 << next),
+                len: walk.len + w,
+                bound: walk.bound - self.max_in[next],
+            })
+    }
+
+    fn found(&self, walk: Walk) -> bool {
+        if walk.node == self.to {
+            self.best.fetch_max(walk.len + 1, Ordering::Relaxed);
+        }
+        walk.node == self.to
+    }
+
+    fn is_hopeless(&self, walk: Walk) -> bool {
+        walk.len + walk.bound < self.best.load(Ordering::Relaxed)
+    }
+
+    fn explore(&self, walk: Walk) {
+        if self.found(walk) || self.is_hopeless(walk) {
+            return;
+        }
+        for next in self.next_walks(walk) {
+            self.explore(next);
+        }
+    }
+}
+
+//Length of the heaviest path from "from" to "to" that never visits a node twice, 64 nodes at most
+//The first branches are explored in parallel, sharing the best length found for pruning
+pub fn longest_simple_path(graph: &Graph, from: usize, to: usize) -> Option<usize> {
+    assert!(graph.len() <= 64, "Too many nodes for a bitmask");
+    let mut max_in: Vec<usize> = vec![0; graph.len()];
+    let mut to_in: u64 = 0;
+    for node in 0..graph.len() {
+        for &(next, w) in graph.edges(node) {
+            max_in[next] = max_in[next].max(w);
+            if next == to {
+                to_in |= 1 << node;
+            }
+        }
+    }
+    let start: Walk = Walk {
+        node: from,
+        visited: 1 << from,
+        len: 0,
+        bound: max_in.iter().sum::<usize>() - max_in[from],
+    };
+    let solver: LongestPath = LongestPath {
+        graph,
+        to,
+        max_in,
+        to_in,
+        best: AtomicUsize::new(0),
+    };
+
+    //Enough branches to keep every thread busy
+    let mut walks: Vec<Walk> = vec![start];
+    while !walks.is_empty() && walks.len() < 256 {
+        walks = walks
+            .iter()
+            .filter(|&&walk| !solver.found(walk))
+            .flat_map(|&walk| solver.next_walks(walk))
+            .collect();
+    }
+    walks.par_iter().for_each(|&walk| solver.explore(walk));
+
+    solver.best.load(Ordering::Relaxed).checked_sub(1)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Route {
+    //Every node once, starting and ending anywhere
+    Path,
+    //Every node once, then back to the first one
+    Cycle,
+}
+
+//Held-Karp: best[mask][last] is the best weight to visit the nodes of mask, ending at last
+//weights[a][b] is None when there is no edge from a to b, 20 nodes or so at most
+fn held_karp<T: Integer, F: Fn(T, T) -> bool>(
+    weights: &[Vec<Option<T>>],
+    route: Route,
+    better: F,
+) -> Option<T> {
+    let n: usize = weights.len();
+    if n == 0 {
+        return None;
+    }
+    let full: usize = (1 << n) - 1;
+    let mut best: Vec<Vec<Option<T>>> = vec![vec![None; n]; 1 << n];
+    match route {
+        Route::Path => (0..n).for_each(|node| best[1 << node][node] = Some(T::ZERO)),
+        //A cycle can start anywhere, so from the first node
+        Route::Cycle => best[1][0] = Some(T::ZERO),
+    }
+    for mask in 1..full {
+        for (last, &weight) in best[mask].clone().iter().enumerate() {
+            let Some(weight) = weight else {
+                continue;
+            };
+            for (next, &edge) in weights[last].iter().enumerate() {
+                if let (0, Some(edge)) = (mask & (1 << next), edge) {
+                    let slot: &mut Option<T> = &mut best[mask | (1 << next)][next];
+                    if slot.is_none_or(|s| better(weight + edge, s)) {
+                        *slot = Some(weight + edge);
+                    }
+                }
+            }
+        }
+    }
+    best[full]
+        .iter()
+        .enumerate()
+        .filter_map(|(last, &weight)| match route {
+            Route::Path => weight,
+            Route::Cycle => Some(weight? + weights[last][0]?),
+        })
+        .reduce(|a, b| if better(b, a) { b } else { a })
+}
+
+//Lightest way to visit every node once, None if there is none
+pub fn shortest_tour<T: Integer>(weights: &[Vec<Option<T>>], route: Route) -> Option<T> {
+    held_karp(weights, route, |a, b| a < b)
+}
+
+//Heaviest way to visit every node once, None if there is none
+pub fn longest_tour<T: Integer>(weights: &[Vec<Option<T>>], route: Route) -> Option<T> {
+    held_karp(weights, route, |a, b| a > b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_rng::next_random;
+    use itertools::Itertools;
+
+    #[test]
+    fn longest_paths() {
+        //From 2, going back through 1 is longer than going straight to 3
+        let mut graph: Graph = Graph::new(5);
+        for (a, b, w) in [(0, 1, 1), (1, 3, 1), (0, 2, 5), (2, 3, 1), (1, 2, 1)] {
+            graph.add_undirected_weighted_edge(a, b, w);
+        }
+        assert_eq!(longest_simple_path(&graph, 0, 3), Some(7));
+        assert_eq!(longest_simple_path(&graph, 0, 0), Some(0));
+        assert_eq!(longest_simple_path(&graph, 0, 4), None);
+        //Slopes only go one way
+        graph.add_weighted_edge(3, 4, 2);
+        assert_eq!(longest_simple_path(&graph, 0, 4), Some(9));
+        assert_eq!(longest_simple_path(&graph, 4, 0), None);
+    }
+
+    #[test]
+    fn tours() {
+        //2015 day 9 example: London, Dublin, Belfast
+        let towns: Vec<Vec<Option<u32>>> = vec![
+            vec![None, Some(464), Some(518)],
+            vec![Some(464), None, Some(141)],
+            vec![Some(518), Some(141), None],
+        ];
+        assert_eq!(shortest_tour(&towns, Route::Path), Some(605));
+        assert_eq!(longest_tour(&towns, Route::Path), Some(982));
+
+        //2015 day 13 example, happiness of both neighbours summed
+        let table: Vec<Vec<Option<i32>>> = vec![
+            vec![None, Some(137), Some(-141), Some(44)],
+            vec![Some(137), None, Some(53), Some(-70)],
+            vec![Some(-141), Some(53), None, Some(96)],
+            vec![Some(44), Some(-70), Some(96), None],
+        ];
+        assert_eq!(longest_tour(&table, Route::Cycle), Some(330));
+        assert_eq!(shortest_tour(&table, Route::Cycle), Some(-114));
+    }
+
+    #[test]
+    fn tours_against_brute_force() {
+        let mut seed: u32 = 5;
+        for _ in 0..30 {
+            let n: usize = 1 + next_random(&mut seed, 6);
+            let weights: Vec<Vec<Option<i32>>> = (0..n)
+                .map(|_| {
+                    (0..n)
+                        .map(|_| {
+                            (next_random(&mut seed, 4) > 0)
+                                .then(|| next_random(&mut seed, 50) as i32 - 25)
+                        })
+                        .collect()
+                })
+                .collect();
+            let tour = |perm: &Vec<usize>, route: Route| -> Option<i32> {
+                let back: Option<i32> = match route {
+                    Route::Path => Some(0),
+                    Route::Cycle => weights[perm[n - 1]][perm[0]],
+                };
+                perm.windows(2)
+                    .map(|w| weights[w[0]][w[1]])
+                    .chain([back])
+                    .sum()
+            };
+            for route in [Route::Path, Route::Cycle] {
+                let all: Vec<i32> = (0..n)
+                    .permutations(n)
+                    .filter_map(|perm| tour(&perm, route))
+                    .collect();
+                assert_eq!(shortest_tour(&weights, route), all.iter().copied().min());
+                assert_eq!(longest_tour(&weights, route), all.iter().copied().max());
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::coord::PosI;
+use crate::num::gcd;
+use crate::orientation::Dir;
+
+//Vertices are given in order, the closing side from the last one to the first one is implied
+//Every computation is done with i128 so that big dig plans cannot overflow
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn to_i128(PosI(x, y): PosI) -> (i128, i128) {
+    (x as i128, y as i128)
+}
+
+fn sides(vertices: &[(i128, i128)]) -> impl Iterator<Item = ((i128, i128), (i128, i128))> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+//Shoelace formula, positive when the vertices turn counterclockwise (with y going up)
+pub fn double_signed_area(vertices: &[PosI]) -> i128 {
+    let vertices: Vec<(i128, i128)> = vertices.iter().copied().map(to_i128).collect();
+    sides(&vertices)
+        .map(|((xa, ya), (xb, yb))| xa * yb - ya * xb)
+        .sum()
+}
+
+//Rounded down when the area is not an integer
+pub fn area(vertices: &[PosI]) -> i128 {
+    double_signed_area(vertices).abs() / 2
+}
+
+//Number of lattice points lying on the sides
+pub fn boundary_points(vertices: &[PosI]) -> i128 {
+    let vertices: Vec<(i128, i128)> = vertices.iter().copied().map(to_i128).collect();
+    sides(&vertices)
+        .map(|((xa, ya), (xb, yb))| gcd(xb - xa, yb - ya))
+        .sum()
+}
+
+//Pick's theorem: A = I + B/2 - 1
+pub fn interior_points(vertices: &[PosI]) -> i128 {
+    (double_signed_area(vertices).abs() - boundary_points(vertices) + 2) / 2
+}
+
+//Interior and boundary lattice points, the number of tiles of a dug out lagoon
+pub fn lattice_points(vertices: &[PosI]) -> i128 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+fn locate_i128(vertices: &[(i128, i128)], (x, y): (i128, i128)) -> Location {
+    let mut inside: bool = false;
+    for ((xa, ya), (xb, yb)) in sides(vertices) {
+        let cross: i128 = (xb - xa) * (y - ya) - (yb - ya) * (x - xa);
+        if cross == 0 && xa.min(xb) <= x && x <= xa.max(xb) && ya.min(yb) <= y && y <= ya.max(yb) {
+            return Location::Boundary;
+        }
+        //Cast a ray towards +x, each side counts for its lower end only
+        if (ya > y) != (yb > y) {
+            let x_cross_side: bool = if yb > ya { cross > 0 } else { cross < 0 };
+            if x_cross_side {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+pub fn locate(vertices: &[PosI], p: PosI) -> Location {
+    let vertices: Vec<(i128, i128)> = vertices.iter().copied().map(to_i128).collect();
+    locate_i128(&vertices, to_i128(p))
+}
+
+//The corners met while following a dig plan from the origin
+pub fn dig_plan<I: IntoIterator<Item = (Dir, usize)>>(steps: I) -> Vec<PosI> {
+    let mut pos: PosI = PosI(0, 0);
+    let mut vertices: Vec<PosI> = vec![pos];
+    for (dir, len) in steps {
+        pos = dir.step_n(pos, len as isize);
+        vertices.push(pos);
+    }
+    //Closing the loop is implied
+    if vertices.len() > 1 && vertices.last() == vertices.first() {
+        vertices.pop();
+    }
+    vertices
+}
+
+//Polygon with only horizontal and vertical sides, answering rectangle containment queries
+//Every distinct vertex coordinate cuts the plane, with the lines themselves and the gaps between them as cells
+//Each cell is either fully in the (closed) polygon or fully out of it
+pub struct RectilinearPolygon {
+    xs: Vec<i128>,
+    ys: Vec<i128>,
+    //outside[y][x] is the number of outside cells in [0, x[ x [0, y[
+    outside: Vec<Vec<usize>>,
+}
+
+impl RectilinearPolygon {
+    pub fn new(vertices: &[PosI]) -> Self {
+        let vertices: Vec<(i128, i128)> = vertices.iter().copied().map(to_i128).collect();
+        let compress = |coords: Vec<i128>| -> Vec<i128> {
+            let mut coords = coords;
+            coords.sort_unstable();
+            coords.dedup();
+            coords
+        };
+        let xs: Vec<i128> = compress(vertices.iter().map(|v| v.0).collect());
+        let ys: Vec<i128> = compress(vertices.iter().map(|v| v.1).collect());
+
+        //Doubled coordinates, so that the middle of a gap is an integer
+        let doubled: Vec<(i128, i128)> = vertices.iter().map(|(x, y)| (2 * x, 2 * y)).collect();
+        let middles = |coords: &[i128]| -> Vec<i128> {
+            (0..2 * coords.len() - 1)
+                .map(|i| coords[i / 2] + coords[i.div_ceil(2)])
+                .collect()
+        };
+        let (mid_xs, mid_ys) = (middles(&xs), middles(&ys));
+
+        let mut outside: Vec<Vec<usize>> = vec![vec![0; mid_xs.len() + 1]; mid_ys.len() + 1];
+        for (j, &y) in mid_ys.iter().enumerate() {
+            let row: Vec<bool> = Self::outside_row(&doubled, y, &mid_xs);
+            for (i, &out) in row.iter().enumerate() {
+                outside[j + 1][i + 1] =
+                    out as usize + outside[j][i + 1] + outside[j + 1][i] - outside[j][i];
+            }
+        }
+        RectilinearPolygon { xs, ys, outside }
+    }
+
+    //Same as calling locate on every point of the row, but sweeping from the left
+    fn outside_row(vertices: &[(i128, i128)], y: i128, xs: &[i128]) -> Vec<bool> {
+        let mut crossings: Vec<i128> = Vec::new();
+        let mut boundary: Vec<(i128, i128)> = Vec::new();
+        for ((xa, ya), (xb, yb)) in sides(vertices) {
+            if ya.min(yb) <= y && y <= ya.max(yb) {
+                boundary.push((xa.min(xb), xa.max(xb)));
+            }
+            if (ya > y) != (yb > y) {
+                crossings.push(xa);
+            }
+        }
+        crossings.sort_unstable();
+
+        let mut crossed: usize = 0;
+        xs.iter()
+            .map(|&x| {
+                while crossed < crossings.len() && crossings[crossed] < x {
+                    crossed += 1;
+                }
+                crossed.is_multiple_of(2)
+                    && boundary.iter().all(|&(start, end)| x < start || end < x)
+            })
+            .collect()
+    }
+
+    //Index of the cell holding the coordinate, None when out of the bounding box
+    fn cell(coords: &[i128], c: i128) -> Option<usize> {
+        match coords.binary_search(&c) {
+            Ok(i) => Some(2 * i),
+            Err(i) if i > 0 && i < coords.len() => Some(2 * i - 1),
+            _ => None,
+        }
+    }
+
+    //True when the whole rectangle, sides included, is in the polygon
+    pub fn contains_rect(&self, a: PosI, b: PosI) -> bool {
+        let (a, b) = (to_i128(a), to_i128(b));
+        let cells = || -> Option<(usize, usize, usize, usize)> {
+            Some((
+                Self::cell(&self.xs, a.0.min(b.0))?,
+                Self::cell(&self.xs, a.0.max(b.0))? + 1,
+                Self::cell(&self.ys, a.1.min(b.1))?,
+                Self::cell(&self.ys, a.1.max(b.1))? + 1,
+            ))
+        };
+        let Some((x0, x1, y0, y1)) = cells() else {
+            return false;
+        };
+        let o = &self.outside;
+        o[y1][x1] + o[y0][x0] == o[y0][x1] + o[y1][x0]
+    }
+
+    pub fn contains(&self, p: PosI) -> bool {
+        self.contains_rect(p, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //2025 day 9 example
+    const THEATER: [PosI; 8] = [
+        PosI(7, 1),
+        PosI(11, 1),
+        PosI(11, 7),
+        PosI(9, 7),
+        PosI(9, 5),
+        PosI(2, 5),
+        PosI(2, 3),
+        PosI(7, 3),
+    ];
+
+    #[test]
+    fn areas_and_points() {
+        let square: Vec<PosI> = vec![PosI(0, 0), PosI(4, 0), PosI(4, 4), PosI(0, 4)];
+        assert_eq!(double_signed_area(&square), 32);
+        assert_eq!(area(&square), 16);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(lattice_points(&square), 25);
+        let triangle: Vec<PosI> = vec![PosI(0, 0), PosI(3, 3), PosI(6, 0)];
+        assert_eq!(double_signed_area(&triangle), -18);
+        assert_eq!(boundary_points(&triangle), 12);
+        assert_eq!(interior_points(&triangle), 4);
+    }
+
+    #[test]
+    fn dig_plans() {
+        //2023 day 18 example
+        let plan: Vec<(Dir, usize)> = "R6 D5 L2 D2 R2 D2 L5 U2 L1 U2 R2 U3 L2 U2"
+            .split(' ')
+            .map(|s| {
+                (
+                    Dir::from_char(s.chars().next().unwrap()).unwrap(),
+                    s[1..].parse().unwrap(),
+                )
+            })
+            .collect();
+        let vertices: Vec<PosI> = dig_plan(plan);
+        assert_eq!(vertices.len(), 14);
+        assert_eq!(lattice_points(&vertices), 62);
+        //No overflow with large sides
+        let big: Vec<PosI> = dig_plan([
+            (Dir::East, 1 << 40),
+            (Dir::South, 1 << 40),
+            (Dir::West, 1 << 40),
+            (Dir::North, 1 << 40),
+        ]);
+        assert_eq!(area(&big), 1 << 80);
+    }
+
+    #[test]
+    fn point_location() {
+        assert_eq!(locate(&THEATER, PosI(8, 4)), Location::Inside);
+        assert_eq!(locate(&THEATER, PosI(9, 6)), Location::Boundary);
+        assert_eq!(locate(&THEATER, PosI(11, 4)), Location::Boundary);
+        assert_eq!(locate(&THEATER, PosI(5, 2)), Location::Outside);
+        assert_eq!(locate(&THEATER, PosI(10, 8)), Location::Outside);
+        //On the ray of a vertex, but outside
+        assert_eq!(locate(&THEATER, PosI(1, 3)), Location::Outside);
+        assert_eq!(locate(&THEATER, PosI(0, 5)), Location::Outside);
+    }
+
+    #[test]
+    fn rectangles() {
+        let poly: RectilinearPolygon = RectilinearPolygon::new(&THEATER);
+        assert!(poly.contains_rect(PosI(9, 5), PosI(2, 3)));
+        assert!(poly.contains_rect(PosI(7, 3), PosI(11, 1)));
+        assert!(poly.contains_rect(PosI(9, 7), PosI(9, 5)));
+        //Corners need not be vertices
+        assert!(poly.contains_rect(PosI(2, 3), PosI(11, 5)));
+        assert!(!poly.contains_rect(PosI(11, 1), PosI(2, 5)));
+        assert!(!poly.contains_rect(PosI(2, 2), PosI(7, 3)));
+        assert!(!poly.contains_rect(PosI(9, 7), PosI(11, 8)));
+        //The largest rectangle with two vertices as corners
+        let largest: isize = THEATER
+            .iter()
+            .flat_map(|&a| THEATER.iter().map(move |&b| (a, b)))
+            .filter(|&(a, b)| poly.contains_rect(a, b))
+            .map(|(a, b)| ((a.0 - b.0).abs() + 1) * ((a.1 - b.1).abs() + 1))
+            .max()
+            .unwrap();
+        assert_eq!(largest, 24);
+    }
+}
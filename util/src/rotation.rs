@@ -0,0 +1,187 @@
+use crate::vector::Vector;
+use itertools::Itertools;
+
+//Built once at compile time, aligning scanners goes through them for every pair
+static ALL_ROTATIONS: [Rotation; 24] = build_rotations();
+
+//Permutations of the axes, in lexicographic order
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+const fn build_rotations() -> [Rotation; 24] {
+    let mut rotations: [Rotation; 24] = [Rotation {
+        matrix: [[0; 3]; 3],
+    }; 24];
+    let mut count: usize = 0;
+    let mut p: usize = 0;
+    while p < PERMUTATIONS.len() {
+        let mut bits: isize = 0;
+        while bits < 8 {
+            let mut matrix: [[isize; 3]; 3] = [[0; 3]; 3];
+            let mut row: usize = 0;
+            while row < 3 {
+                matrix[row][PERMUTATIONS[p][row]] = 1 - 2 * ((bits >> row) & 1);
+                row += 1;
+            }
+            let rotation: Rotation = Rotation { matrix };
+            if rotation.determinant() == 1 {
+                rotations[count] = rotation;
+                count += 1;
+            }
+            bits += 1;
+        }
+        p += 1;
+    }
+    rotations
+}
+
+//One of the 24 rotations of the cube, as a signed permutation matrix
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Rotation {
+    matrix: [[isize; 3]; 3],
+}
+
+impl Rotation {
+    pub fn identity() -> Self {
+        Rotation {
+            matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+        }
+    }
+
+    //Every signed permutation matrix with a determinant of 1
+    pub fn all() -> &'static [Rotation; 24] {
+        &ALL_ROTATIONS
+    }
+
+    pub fn matrix(&self) -> [[isize; 3]; 3] {
+        self.matrix
+    }
+
+    const fn determinant(&self) -> isize {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, Vector([x, y, z]): Vector<isize, 3>) -> Vector<isize, 3> {
+        let m = &self.matrix;
+        Vector([
+            m[0][0] * x + m[0][1] * y + m[0][2] * z,
+            m[1][0] * x + m[1][1] * y + m[1][2] * z,
+            m[2][0] * x + m[2][1] * y + m[2][2] * z,
+        ])
+    }
+
+    //Apply self first, then other
+    pub fn then(&self, other: &Rotation) -> Rotation {
+        let mut matrix: [[isize; 3]; 3] = [[0; 3]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| other.matrix[i][k] * self.matrix[k][j]).sum();
+            }
+        }
+        Rotation { matrix }
+    }
+
+    //Rotation matrices are orthogonal, the inverse is the transpose
+    pub fn inverse(&self) -> Rotation {
+        let mut matrix: [[isize; 3]; 3] = [[0; 3]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = self.matrix[j][i];
+            }
+        }
+        Rotation { matrix }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Alignment {
+    pub rotation: Rotation,
+    pub translation: Vector<isize, 3>,
+}
+
+impl Alignment {
+    pub fn apply(&self, pos: Vector<isize, 3>) -> Vector<isize, 3> {
+        self.rotation.apply(pos) + self.translation
+    }
+}
+
+//Find the rotation and translation bringing at least "min_overlap" points of "moving" onto "fixed"
+pub fn align(
+    fixed: &[Vector<isize, 3>],
+    moving: &[Vector<isize, 3>],
+    min_overlap: usize,
+) -> Option<Alignment> {
+    Rotation::all().iter().find_map(|&rotation| {
+        let rotated: Vec<Vector<isize, 3>> = moving.iter().map(|&p| rotation.apply(p)).collect();
+        //If enough pairs of points share the same difference, it is the translation we need
+        fixed
+            .iter()
+            .flat_map(|f| rotated.iter().map(|&r| *f - r))
+            .counts()
+            .into_iter()
+            .find(|&(_, c)| c >= min_overlap)
+            .map(|(translation, _)| Alignment {
+                rotation,
+                translation,
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn rotation_group() {
+        let all: &[Rotation; 24] = Rotation::all();
+        assert_eq!(all.len(), 24);
+        //The group is closed under composition and inversion
+        let set: HashSet<Rotation> = all.iter().copied().collect();
+        assert!(all
+            .iter()
+            .cartesian_product(all.iter())
+            .all(|(a, b)| set.contains(&a.then(b))));
+        assert!(all
+            .iter()
+            .all(|r| r.then(&r.inverse()) == Rotation::identity()));
+        //Every rotation gives a different image of an asymmetric point
+        let images: HashSet<Vector<isize, 3>> =
+            all.iter().map(|r| r.apply(Vector([1, 2, 3]))).collect();
+        assert_eq!(images.len(), 24);
+    }
+
+    #[test]
+    fn align_points() {
+        let fixed: Vec<Vector<isize, 3>> = vec![
+            Vector([0, 0, 0]),
+            Vector([1, 2, 3]),
+            Vector([-4, 5, 1]),
+            Vector([7, 7, -2]),
+        ];
+        let hidden: Alignment = Alignment {
+            rotation: Rotation::all()[17],
+            translation: Vector([10, -3, 8]),
+        };
+        //Build the moving set so that hidden maps it back onto fixed
+        let inverse: Rotation = hidden.rotation.inverse();
+        let moving: Vec<Vector<isize, 3>> = fixed
+            .iter()
+            .map(|&p| inverse.apply(p - hidden.translation))
+            .collect();
+        let found: Alignment = align(&fixed, &moving, 4).unwrap();
+        assert!(moving
+            .iter()
+            .zip(fixed.iter())
+            .all(|(&m, &f)| found.apply(m) == f));
+    }
+}
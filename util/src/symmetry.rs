@@ -0,0 +1,206 @@
+//The 8 symmetries of a square (dihedral group D4), applied to 2D grids stored as rows
+//A symmetry is an optional horizontal flip followed by a number of clockwise quarter turns
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Symmetry {
+    flipped: bool,
+    quarter_turns: u8,
+}
+
+type Matrix = [[isize; 2]; 2];
+
+impl Symmetry {
+    pub fn identity() -> Self {
+        Symmetry {
+            flipped: false,
+            quarter_turns: 0,
+        }
+    }
+
+    pub fn new(flipped: bool, quarter_turns: u8) -> Self {
+        Symmetry {
+            flipped,
+            quarter_turns: quarter_turns % 4,
+        }
+    }
+
+    //Clockwise rotations
+    pub fn rotation(quarter_turns: u8) -> Self {
+        Symmetry::new(false, quarter_turns)
+    }
+
+    //Mirror along the vertical axis, each row is reversed
+    pub fn flip() -> Self {
+        Symmetry::new(true, 0)
+    }
+
+    pub fn all() -> [Symmetry; 8] {
+        let mut all: [Symmetry; 8] = [Symmetry::identity(); 8];
+        for (i, sym) in all.iter_mut().enumerate() {
+            *sym = Symmetry::new(i >= 4, (i % 4) as u8);
+        }
+        all
+    }
+
+    //Action on centered coordinates, with y going down
+    fn matrix(&self) -> Matrix {
+        let flip: Matrix = if self.flipped {
+            [[-1, 0], [0, 1]]
+        } else {
+            [[1, 0], [0, 1]]
+        };
+        (0..self.quarter_turns).fold(flip, |m, _| mul(&[[0, -1], [1, 0]], &m))
+    }
+
+    fn from_matrix(m: &Matrix) -> Symmetry {
+        Symmetry::all()
+            .into_iter()
+            .find(|s| s.matrix() == *m)
+            .unwrap()
+    }
+
+    //Apply self first, then other
+    pub fn then(&self, other: &Symmetry) -> Symmetry {
+        Symmetry::from_matrix(&mul(&other.matrix(), &self.matrix()))
+    }
+
+    pub fn inverse(&self) -> Symmetry {
+        let m: Matrix = self.matrix();
+        Symmetry::from_matrix(&[[m[0][0], m[1][0]], [m[0][1], m[1][1]]])
+    }
+
+    //Width and height of a width x height grid once transformed
+    pub fn dims(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.quarter_turns.is_multiple_of(2) {
+            (width, height)
+        } else {
+            (height, width)
+        }
+    }
+
+    //Position in the source grid of the cell (x, y) of the transformed grid
+    pub fn source_pos(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (w, h) = self.dims(width, height);
+        //Doubled centered coordinates, to stay on integers
+        let (cx, cy) = (
+            2 * x as isize - (w as isize - 1),
+            2 * y as isize - (h as isize - 1),
+        );
+        //The inverse of the matrix is its transpose
+        let m: Matrix = self.matrix();
+        let (sx, sy) = (m[0][0] * cx + m[1][0] * cy, m[0][1] * cx + m[1][1] * cy);
+        (
+            ((sx + width as isize - 1) / 2) as usize,
+            ((sy + height as isize - 1) / 2) as usize,
+        )
+    }
+
+    //Lazy view of the transformed grid, no cell is copied
+    pub fn view<'a, T>(&self, grid: &'a [Vec<T>]) -> GridView<'a, T> {
+        GridView {
+            grid,
+            symmetry: *self,
+        }
+    }
+}
+
+fn mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut m: Matrix = [[0; 2]; 2];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+        }
+    }
+    m
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a [Vec<T>],
+    symmetry: Symmetry,
+}
+
+impl<'a, T> GridView<'a, T> {
+    fn source_dims(&self) -> (usize, usize) {
+        (
+            self.grid.first().map_or(0, |row| row.len()),
+            self.grid.len(),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        let (w, h) = self.source_dims();
+        self.symmetry.dims(w, h).0
+    }
+
+    pub fn height(&self) -> usize {
+        let (w, h) = self.source_dims();
+        self.symmetry.dims(w, h).1
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &'a T {
+        let (w, h) = self.source_dims();
+        let (sx, sy) = self.symmetry.source_pos(x, y, w, h);
+        &self.grid[sy][sx]
+    }
+
+    pub fn to_vec(&self) -> Vec<Vec<T>>
+    where
+        T: Clone,
+    {
+        (0..self.height())
+            .map(|y| (0..self.width()).map(|x| self.get(x, y).clone()).collect())
+            .collect()
+    }
+}
+
+//The smallest of the 8 transformed grids, equal for all the grids that are symmetric of each other
+pub fn canonical<T: Clone + Ord>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    Symmetry::all()
+        .iter()
+        .map(|s| s.view(grid).to_vec())
+        .min()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(s: &str) -> Vec<Vec<char>> {
+        s.split('/').map(|row| row.chars().collect()).collect()
+    }
+
+    #[test]
+    fn transform_grids() {
+        let g: Vec<Vec<char>> = grid("abc/def");
+        assert_eq!(Symmetry::rotation(1).view(&g).to_vec(), grid("da/eb/fc"));
+        assert_eq!(Symmetry::flip().view(&g).to_vec(), grid("cba/fed"));
+        assert_eq!(Symmetry::rotation(2).view(&g).to_vec(), grid("fed/cba"));
+    }
+
+    #[test]
+    fn group_operations() {
+        let all: [Symmetry; 8] = Symmetry::all();
+        let g: Vec<Vec<char>> = grid("abc/def");
+        for a in all.iter() {
+            assert_eq!(a.then(&a.inverse()), Symmetry::identity());
+            for b in all.iter() {
+                let twice: Vec<Vec<char>> = b.view(&a.view(&g).to_vec()).to_vec();
+                assert_eq!(a.then(b).view(&g).to_vec(), twice);
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_form() {
+        assert_eq!(
+            canonical(&grid(".#./..#/###")),
+            canonical(&grid("#../#.#/##."))
+        );
+        assert_ne!(
+            canonical(&grid(".#./..#/###")),
+            canonical(&grid("##./#../..."))
+        );
+    }
+}
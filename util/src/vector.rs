@@ -0,0 +1,209 @@
+use crate::coord::{Pos, PosI};
+use crate::num::Integer;
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct Vector<T, const N: usize>(pub [T; N]);
+
+fn abs_diff<T: Integer>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T: Integer, const N: usize> Vector<T, N> {
+    pub fn zero() -> Self {
+        Vector([T::ZERO; N])
+    }
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        (0..N).fold(T::ZERO, |acc, i| acc + abs_diff(self[i], other[i]))
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        (0..N).fold(T::ZERO, |acc, i| acc.max(abs_diff(self[i], other[i])))
+    }
+
+    pub fn distance_squared(&self, other: &Self) -> T {
+        (0..N).fold(T::ZERO, |acc, i| {
+            let d: T = abs_diff(self[i], other[i]);
+            acc + d * d
+        })
+    }
+
+    //Applies the offsets (0 is -1, 1 is 0, 2 is +1) to every axis
+    //Returns None if a coordinate gets out of the type bounds
+    fn offset(&self, mut code: usize) -> Option<Self> {
+        let mut coords: [T; N] = self.0;
+        for c in coords.iter_mut() {
+            *c = match code % 3 {
+                0 => c.checked_difference(T::ONE)?,
+                2 => c.checked_sum(T::ONE)?,
+                _ => *c,
+            };
+            code /= 3;
+        }
+        Some(Vector(coords))
+    }
+
+    //The 2N orthogonal neighbours
+    pub fn neighbours(&self) -> Vec<Self> {
+        (0..N)
+            .flat_map(|i| {
+                [
+                    self[i].checked_difference(T::ONE),
+                    self[i].checked_sum(T::ONE),
+                ]
+                .into_iter()
+                .flatten()
+                .map(move |c| {
+                    let mut v: Self = *self;
+                    v[i] = c;
+                    v
+                })
+            })
+            .collect()
+    }
+
+    //The 3^N - 1 neighbours, diagonals included
+    pub fn neighbours_diag(&self) -> Vec<Self> {
+        let center: usize = (3usize.pow(N as u32) - 1) / 2;
+        (0..3usize.pow(N as u32))
+            .filter(|&code| code != center)
+            .filter_map(|code| self.offset(code))
+            .collect()
+    }
+}
+
+impl<const N: usize> Vector<usize, N> {
+    pub fn to_signed(&self) -> Vector<isize, N> {
+        Vector(self.0.map(|c| c as isize))
+    }
+}
+
+impl<const N: usize> Vector<isize, N> {
+    //None if any coordinate is negative
+    pub fn to_unsigned(&self) -> Option<Vector<usize, N>> {
+        let mut coords: [usize; N] = [0; N];
+        for (c, &v) in coords.iter_mut().zip(self.0.iter()) {
+            *c = usize::try_from(v).ok()?;
+        }
+        Some(Vector(coords))
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        &self.0[i]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Vector<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        &mut self.0[i]
+    }
+}
+
+impl<T: Integer, const N: usize> Add for Vector<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut coords: [T; N] = self.0;
+        coords
+            .iter_mut()
+            .zip(other.0)
+            .for_each(|(c, o)| *c = *c + o);
+        Vector(coords)
+    }
+}
+
+impl<T: Integer, const N: usize> Sub for Vector<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let mut coords: [T; N] = self.0;
+        coords
+            .iter_mut()
+            .zip(other.0)
+            .for_each(|(c, o)| *c = *c - o);
+        Vector(coords)
+    }
+}
+
+//Scalar multiplication
+impl<T: Integer, const N: usize> Mul<T> for Vector<T, N> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Vector(self.0.map(|c| c * k))
+    }
+}
+
+impl<T: Integer + Neg<Output = T>, const N: usize> Neg for Vector<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Vector(self.0.map(|c| -c))
+    }
+}
+
+macro_rules! impl_pos_conversion {
+    ($pos:ident, $t:ty, $n:literal, $($i:tt),*) => {
+        impl From<$pos> for Vector<$t, $n> {
+            fn from(p: $pos) -> Self {
+                Vector([$(p.$i),*])
+            }
+        }
+
+        impl From<Vector<$t, $n>> for $pos {
+            fn from(v: Vector<$t, $n>) -> Self {
+                $pos($(v.0[$i]),*)
+            }
+        }
+    };
+}
+
+impl_pos_conversion!(Pos, usize, 2, 0, 1);
+impl_pos_conversion!(PosI, isize, 2, 0, 1);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators_and_distances() {
+        let a: Vector<isize, 3> = Vector([1, -2, 3]);
+        let b: Vector<isize, 3> = Vector([-4, 5, 3]);
+        assert_eq!(a + b, Vector([-3, 3, 6]));
+        assert_eq!(a - b, Vector([5, -7, 0]));
+        assert_eq!(-a * 2, Vector([-2, 4, -6]));
+        assert_eq!(a.manhattan(&b), 12);
+        assert_eq!(a.chebyshev(&b), 7);
+        assert_eq!(a.distance_squared(&b), 74);
+    }
+
+    #[test]
+    fn neighbours() {
+        let v: Vector<isize, 4> = Vector::zero();
+        assert_eq!(v.neighbours().len(), 8);
+        assert_eq!(v.neighbours_diag().len(), 80);
+        //Unsigned neighbours never wrap below zero
+        let u: Vector<usize, 2> = Vector([0, 5]);
+        assert_eq!(u.neighbours().len(), 3);
+        assert_eq!(u.neighbours_diag().len(), 5);
+    }
+
+    #[test]
+    fn conversions() {
+        let p: PosI = PosI(1, -2);
+        let v: Vector<isize, 2> = p.into();
+        assert_eq!(v.to_unsigned(), None);
+        assert_eq!(PosI::from(v), p);
+        let u: Vector<usize, 2> = Pos(3, 4).into();
+        assert_eq!(u.to_signed().to_unsigned(), Some(u));
+    }
+}
@@ -77,20 +77,70 @@ impl FromStr for Opcode {
     }
 }
 
+//The instruction is compiled when it is built, executing it needs no decoding
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Instruction {
-    opcode: Opcode,
-    a: usize,
-    b: usize,
-    c: usize,
+    code: Bytecode,
 }
 
 impl Instruction {
     pub fn from_op(opcode: Opcode, a: usize, b: usize, c: usize) -> Self {
-        Instruction { opcode, a, b, c }
+        Instruction {
+            code: Self::compile(opcode, a, b, c),
+        }
+    }
+
+    //The "r" or "i" suffixes of the opcode become the kind of the operands
+    fn compile(opcode: Opcode, a: usize, b: usize, c: usize) -> Bytecode {
+        let (reg, val) = (Operand::Reg, Operand::Val);
+        match opcode {
+            Opcode::Addr => Bytecode::Add(a, reg(b), c),
+            Opcode::Addi => Bytecode::Add(a, val(b), c),
+            Opcode::Mulr => Bytecode::Mul(a, reg(b), c),
+            Opcode::Muli => Bytecode::Mul(a, val(b), c),
+            Opcode::Banr => Bytecode::And(a, reg(b), c),
+            Opcode::Bani => Bytecode::And(a, val(b), c),
+            Opcode::Borr => Bytecode::Or(a, reg(b), c),
+            Opcode::Bori => Bytecode::Or(a, val(b), c),
+            Opcode::Setr => Bytecode::Set(reg(a), c),
+            Opcode::Seti => Bytecode::Set(val(a), c),
+            Opcode::Gtir => Bytecode::Greater(val(a), reg(b), c),
+            Opcode::Gtri => Bytecode::Greater(reg(a), val(b), c),
+            Opcode::Gtrr => Bytecode::Greater(reg(a), reg(b), c),
+            Opcode::Eqir => Bytecode::Equal(val(a), reg(b), c),
+            Opcode::Eqri => Bytecode::Equal(reg(a), val(b), c),
+            Opcode::Eqrr => Bytecode::Equal(reg(a), reg(b), c),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Operand {
+    Reg(usize),
+    Val(usize),
+}
+
+impl Operand {
+    fn value(&self, regs: &[usize]) -> usize {
+        match *self {
+            Operand::Reg(r) => regs[r],
+            Operand::Val(v) => v,
+        }
     }
 }
 
+//Compiled form of an Instruction, the last field is the register written
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Bytecode {
+    Add(usize, Operand, usize),
+    Mul(usize, Operand, usize),
+    And(usize, Operand, usize),
+    Or(usize, Operand, usize),
+    Set(Operand, usize),
+    Greater(Operand, Operand, usize),
+    Equal(Operand, Operand, usize),
+}
+
 impl FromStr for Instruction {
     type Err = ();
 
@@ -101,15 +151,7 @@ impl FromStr for Instruction {
             })
             .parse(s)?;
             let (s, v) = separated_list1(space1, parse_usize).parse(s)?;
-            Ok((
-                s,
-                Instruction {
-                    opcode: op,
-                    a: v[0],
-                    b: v[1],
-                    c: v[2],
-                },
-            ))
+            Ok((s, Instruction::from_op(op, v[0], v[1], v[2])))
         }
         Ok(parse_instruction(s).unwrap().1)
     }
@@ -146,31 +188,25 @@ impl WristDevice {
     }
 
     pub fn apply_instruction(&mut self, instruction: &Instruction) {
-        let (a, b, c) = (instruction.a, instruction.b, instruction.c);
-        match instruction.opcode {
-            Opcode::Addr => self.registers[c] = self.registers[a] + self.registers[b],
-            Opcode::Addi => self.registers[c] = self.registers[a] + b,
-            Opcode::Mulr => self.registers[c] = self.registers[a] * self.registers[b],
-            Opcode::Muli => self.registers[c] = self.registers[a] * b,
-            Opcode::Banr => self.registers[c] = self.registers[a] & self.registers[b],
-            Opcode::Bani => self.registers[c] = self.registers[a] & b,
-            Opcode::Borr => self.registers[c] = self.registers[a] | self.registers[b],
-            Opcode::Bori => self.registers[c] = self.registers[a] | b,
-            Opcode::Setr => self.registers[c] = self.registers[a],
-            Opcode::Seti => self.registers[c] = a,
-            Opcode::Gtir => self.registers[c] = (a > self.registers[b]) as usize,
-            Opcode::Gtri => self.registers[c] = (self.registers[a] > b) as usize,
-            Opcode::Gtrr => self.registers[c] = (self.registers[a] > self.registers[b]) as usize,
-            Opcode::Eqir => self.registers[c] = (a == self.registers[b]) as usize,
-            Opcode::Eqri => self.registers[c] = (self.registers[a] == b) as usize,
-            Opcode::Eqrr => self.registers[c] = (self.registers[a] == self.registers[b]) as usize,
+        self.execute(instruction.code);
+    }
+
+    fn execute(&mut self, code: Bytecode) {
+        let regs: &mut [usize] = &mut self.registers;
+        match code {
+            Bytecode::Add(a, b, c) => regs[c] = regs[a] + b.value(regs),
+            Bytecode::Mul(a, b, c) => regs[c] = regs[a] * b.value(regs),
+            Bytecode::And(a, b, c) => regs[c] = regs[a] & b.value(regs),
+            Bytecode::Or(a, b, c) => regs[c] = regs[a] | b.value(regs),
+            Bytecode::Set(a, c) => regs[c] = a.value(regs),
+            Bytecode::Greater(a, b, c) => regs[c] = (a.value(regs) > b.value(regs)) as usize,
+            Bytecode::Equal(a, b, c) => regs[c] = (a.value(regs) == b.value(regs)) as usize,
         }
     }
 
     pub fn apply_all(&mut self) {
         for i in 0..self.instructions.len() {
-            let instr = self.instructions[i];
-            self.apply_instruction(&instr);
+            self.execute(self.instructions[i].code);
         }
     }
 
@@ -178,8 +214,7 @@ impl WristDevice {
         let pointer: usize = self.reg_pointer.unwrap();
         while self.registers[pointer] < self.instructions.len() {
             let instr_idx: usize = self.registers[pointer];
-            let instr: Instruction = self.instructions[instr_idx];
-            self.apply_instruction(&instr);
+            self.execute(self.instructions[instr_idx].code);
             self.registers[pointer] += 1;
             //Stopping after the first "init" loop
             if instr_idx >= self.registers[pointer] && stop_after_init {
@@ -230,14 +265,18 @@ mod tests {
     #[test]
     fn parse_instruction() {
         let instruction: Instruction = "addi 1 2 3".parse().unwrap();
-        assert_eq!(
-            instruction,
-            Instruction {
-                opcode: Opcode::Addi,
-                a: 1,
-                b: 2,
-                c: 3
-            }
-        )
+        assert_eq!(instruction, Instruction::from_op(Opcode::Addi, 1, 2, 3));
+        assert_eq!(instruction.code, Bytecode::Add(1, Operand::Val(2), 3));
+    }
+
+    #[test]
+    fn compiled_opcodes() {
+        //Register 2 after applying each opcode with a = 2, b = 1, c = 2 on [3, 2, 1, 1]
+        let expected: [usize; 16] = [3, 2, 2, 1, 0, 1, 3, 1, 1, 2, 0, 0, 0, 1, 1, 0];
+        for (opcode, value) in Opcode::all().into_iter().zip(expected) {
+            let mut device: WristDevice = WristDevice::from_registers(&[3, 2, 1, 1]);
+            device.apply_instruction(&Instruction::from_op(opcode, 2, 1, 2));
+            assert_eq!(device.reg_value(2), value, "{opcode:?}");
+        }
     }
 }